@@ -0,0 +1,286 @@
+//! What a file holds before it is parsed.
+//!
+//! Files over `indexing.max_file_size`, binary files, minified bundles and
+//! generated code are left out of the index with a [`SkipReason`]. Text is
+//! decoded from UTF-8, or from UTF-16 when it starts with a byte order mark;
+//! anything else is decoded lossily, invalid bytes becoming U+FFFD.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::analyzer::embedded;
+use crate::config::{ByteSize, IndexingConfig};
+use crate::table;
+
+/// Bytes looked at to tell binary files from text.
+pub const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Share of control characters above which a file is binary.
+const MAX_CONTROL_RATIO: f64 = 0.1;
+
+/// Lines this long are not written by hand.
+const MINIFIED_LINE: usize = 500;
+
+/// Long lines of repeated characters (separators, tables) stay below this
+/// many bits of entropy per byte; minified code does not.
+const MIN_MINIFIED_ENTROPY: f64 = 4.0;
+
+/// Bytes sampled for the entropy of a file.
+const ENTROPY_SAMPLE: usize = 64 * 1024;
+
+/// Leading lines searched for a generated-code marker.
+const HEADER_LINES: usize = 30;
+
+/// Markers generators put in the header comment of their output.
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "code generated by",
+    "auto-generated",
+    "autogenerated",
+    "automatically generated",
+];
+
+/// File name endings of protobuf and gRPC generator output.
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    "_pb2.py",
+    "_pb2.pyi",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    "_grpc_pb.js",
+    "_grpc_pb.d.ts",
+    ".pb.ts",
+];
+
+/// File name endings of minified bundles.
+const MINIFIED_SUFFIXES: &[&str] = &[".min.js", ".min.mjs", ".min.cjs"];
+
+/// Why a file was left out of the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    TooLarge { size: u64, limit: u64 },
+    Binary,
+    /// With what gave it away.
+    Minified(String),
+    /// With the marker or file name pattern found.
+    Generated(String),
+    Unreadable(String),
+    /// The file was read but could not be parsed.
+    Failed(String),
+}
+
+impl SkipReason {
+    /// Short name stored with the reason.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SkipReason::TooLarge { .. } => "too_large",
+            SkipReason::Binary => "binary",
+            SkipReason::Minified(_) => "minified",
+            SkipReason::Generated(_) => "generated",
+            SkipReason::Unreadable(_) => "unreadable",
+            SkipReason::Failed(_) => "failed",
+        }
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::TooLarge { size, limit } => write!(f, "larger than {} ({})", ByteSize(*limit), ByteSize(*size)),
+            SkipReason::Binary => write!(f, "binary content"),
+            SkipReason::Minified(why) => write!(f, "minified ({})", why),
+            SkipReason::Generated(why) => write!(f, "generated ({})", why),
+            SkipReason::Unreadable(e) => write!(f, "unreadable: {}", e),
+            SkipReason::Failed(e) => write!(f, "parse failed: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encoding {
+    Utf8,
+    /// UTF-8 with a byte order mark, which is dropped.
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Not valid UTF-8; invalid bytes were replaced.
+    Lossy,
+}
+
+impl Encoding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf8Bom => "utf-8-bom",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Lossy => "lossy",
+        }
+    }
+}
+
+/// A file's text and the encoding it was decoded from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceText {
+    pub text: String,
+    pub encoding: Encoding,
+}
+
+/// A file left out of the index, as listed in build and analysis reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedFile {
+    pub file: String,
+    /// [`SkipReason::kind`].
+    pub kind: &'static str,
+    pub reason: String,
+}
+
+impl SkippedFile {
+    pub fn new(file: impl Into<String>, reason: &SkipReason) -> Self {
+        Self { file: file.into(), kind: reason.kind(), reason: reason.to_string() }
+    }
+}
+
+pub const SKIPPED_HEADERS: &[&str] = &["FILE", "REASON"];
+
+pub fn skipped_rows(files: &[SkippedFile]) -> impl Iterator<Item = Vec<String>> + '_ {
+    files.iter().map(|f| vec![f.file.clone(), f.reason.clone()])
+}
+
+pub fn render_skipped(files: &[SkippedFile]) -> String {
+    format!("Skipped files: {}\n{}", files.len(), table::render(SKIPPED_HEADERS, skipped_rows(files)))
+}
+
+/// Read `path` for indexing, or say why it is left out.
+pub fn read_source(path: &Path, indexing: &IndexingConfig) -> Result<SourceText, SkipReason> {
+    let unreadable = |e: std::io::Error| SkipReason::Unreadable(e.to_string());
+    let size = std::fs::metadata(path).map_err(unreadable)?.len();
+    if let Some(reason) = check_size(size, indexing) {
+        return Err(reason);
+    }
+    let bytes = std::fs::read(path).map_err(unreadable)?;
+    if is_binary(&bytes) {
+        return Err(SkipReason::Binary);
+    }
+    let source = decode(&bytes);
+    match classify(path, &source.text, indexing) {
+        Some(reason) => Err(reason),
+        None => Ok(source),
+    }
+}
+
+pub fn check_size(size: u64, indexing: &IndexingConfig) -> Option<SkipReason> {
+    let limit = indexing.max_file_size.0;
+    (size > limit).then_some(SkipReason::TooLarge { size, limit })
+}
+
+/// Generated or minified text, as far as `indexing` skips them. Minification
+/// is not checked in Markdown and notebooks, whose prose lines and encoded
+/// outputs are long too.
+pub fn classify(path: &Path, text: &str, indexing: &IndexingConfig) -> Option<SkipReason> {
+    if indexing.skip_generated {
+        if let Some(marker) = generated_marker(path, text) {
+            return Some(SkipReason::Generated(marker));
+        }
+    }
+    if indexing.skip_minified && !embedded::is_host(path) {
+        if let Some(why) = minified(path, text) {
+            return Some(SkipReason::Minified(why));
+        }
+    }
+    None
+}
+
+/// NUL bytes or mostly control characters at the start of a file. UTF-16
+/// text, full of NUL bytes, is recognised by its byte order mark first.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.starts_with(&[0xFF, 0xFE]) || bytes.starts_with(&[0xFE, 0xFF]) {
+        return false;
+    }
+    let head = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    if head.contains(&0) {
+        return true;
+    }
+    let control = head.iter().filter(|&&b| (b < 0x20 && !b"\t\n\r\x0c".contains(&b)) || b == 0x7F).count();
+    !head.is_empty() && control as f64 / head.len() as f64 > MAX_CONTROL_RATIO
+}
+
+pub fn decode(bytes: &[u8]) -> SourceText {
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| from([pair[0], pair[1]])).collect();
+        String::from_utf16_lossy(&units)
+    };
+    let (text, encoding) = if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        match std::str::from_utf8(rest) {
+            Ok(text) => (text.to_string(), Encoding::Utf8Bom),
+            Err(_) => (String::from_utf8_lossy(rest).into_owned(), Encoding::Lossy),
+        }
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        (utf16(rest, u16::from_le_bytes), Encoding::Utf16Le)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        (utf16(rest, u16::from_be_bytes), Encoding::Utf16Be)
+    } else {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => (text.to_string(), Encoding::Utf8),
+            Err(_) => (String::from_utf8_lossy(bytes).into_owned(), Encoding::Lossy),
+        }
+    };
+    SourceText { text, encoding }
+}
+
+/// The generator marker in the leading comments of `text`, or the generated
+/// file name pattern `path` matches. Only comment lines count, so code that
+/// merely mentions a marker is indexed.
+pub fn generated_marker(path: &Path, text: &str) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    if let Some(suffix) = GENERATED_SUFFIXES.iter().find(|suffix| name.ends_with(*suffix)) {
+        return Some(format!("*{} file", suffix));
+    }
+    text.lines()
+        .take(HEADER_LINES)
+        .map(str::trim_start)
+        .filter(|line| ["//", "#", "/*", "*", "--", "<!--"].iter().any(|start| line.starts_with(start)))
+        .find_map(|line| {
+            let line = line.to_ascii_lowercase();
+            GENERATED_MARKERS.iter().find(|marker| line.contains(*marker)).map(|marker| format!("`{}` marker", marker))
+        })
+}
+
+/// Why `text` looks minified: a minified file name, or most of the bytes on
+/// lines no one would write by hand with the entropy of code.
+pub fn minified(path: &Path, text: &str) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    if let Some(suffix) = MINIFIED_SUFFIXES.iter().find(|suffix| name.ends_with(*suffix)) {
+        return Some(format!("*{} file", suffix));
+    }
+    let long: usize = text.lines().map(str::len).filter(|&length| length > MINIFIED_LINE).sum();
+    if long * 2 <= text.len() {
+        return None;
+    }
+    let entropy = entropy(&text.as_bytes()[..text.len().min(ENTROPY_SAMPLE)]);
+    (entropy >= MIN_MINIFIED_ENTROPY).then(|| {
+        format!("{:.0}% of bytes on lines over {} characters", long as f64 * 100.0 / text.len() as f64, MINIFIED_LINE)
+    })
+}
+
+/// Shannon entropy in bits per byte.
+fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &b in bytes {
+        counts[b as usize] += 1;
+    }
+    let total = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
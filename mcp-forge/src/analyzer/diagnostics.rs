@@ -0,0 +1,209 @@
+//! Syntax errors tree-sitter recovered from while parsing a file.
+//!
+//! tree-sitter always produces a tree: text it cannot fit into the grammar
+//! becomes `ERROR` nodes, and tokens it had to assume become zero-width
+//! `MISSING` nodes. Symbols inside those regions are lost, so the parser
+//! reports them and indexing skips files that are mostly errors.
+
+use serde::Serialize;
+use tree_sitter::{Node, Tree};
+
+pub use crate::database::records::{SyntaxError, SyntaxErrorKind};
+use crate::table;
+
+/// Errors kept per file; the rest are only counted.
+pub const MAX_RECORDED_ERRORS: usize = 100;
+
+/// Characters of unparsable text quoted in an error message.
+const SNIPPET_CHARS: usize = 40;
+
+/// Files listed in a [`ParseHealth`] table.
+const MAX_LISTED_FILES: usize = 20;
+
+impl SyntaxError {
+    fn new(node: &Node, source: &str) -> Self {
+        let (kind, message) = if node.is_missing() {
+            (SyntaxErrorKind::Missing, format!("missing `{}`", node.kind()))
+        } else {
+            let text = node.utf8_text(source.as_bytes()).unwrap_or("");
+            let line = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("");
+            let mut snippet: String = line.chars().take(SNIPPET_CHARS).collect();
+            if snippet.len() < line.len() {
+                snippet.push('…');
+            }
+            (SyntaxErrorKind::Error, format!("unexpected `{}`", snippet))
+        };
+        Self {
+            kind,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            end_column: node.end_position().column + 1,
+            byte_range: node.byte_range(),
+            message,
+        }
+    }
+}
+
+/// How well one file parsed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParseDiagnostics {
+    /// The first [`MAX_RECORDED_ERRORS`] errors in document order.
+    pub errors: Vec<SyntaxError>,
+    pub error_count: usize,
+    /// Bytes covered by `ERROR` nodes.
+    pub error_bytes: usize,
+    pub total_bytes: usize,
+    /// The parser gave up after `indexing.parse_timeout_ms`; there is no tree.
+    pub timed_out: bool,
+}
+
+impl ParseDiagnostics {
+    /// Outermost `ERROR` nodes and every `MISSING` node of `tree`.
+    pub fn from_tree(tree: &Tree, source: &str) -> Self {
+        let mut diagnostics = Self { total_bytes: source.len(), ..Default::default() };
+        let mut to_visit = vec![tree.root_node()];
+        while let Some(node) = to_visit.pop() {
+            if node.is_error() || node.is_missing() {
+                if node.is_error() {
+                    diagnostics.error_bytes += node.end_byte() - node.start_byte();
+                }
+                diagnostics.error_count += 1;
+                if diagnostics.errors.len() < MAX_RECORDED_ERRORS {
+                    diagnostics.errors.push(SyntaxError::new(&node, source));
+                }
+                continue;
+            }
+            if node.has_error() {
+                to_visit.extend((0..node.child_count()).rev().filter_map(|i| node.child(i)));
+            }
+        }
+        diagnostics
+    }
+
+    /// Diagnostics for a parse abandoned after the timeout.
+    pub fn timed_out(source: &str) -> Self {
+        Self { total_bytes: source.len(), error_bytes: source.len(), timed_out: true, ..Default::default() }
+    }
+
+    /// Share of the file inside `ERROR` nodes, 1.0 when the parse timed out.
+    pub fn error_ratio(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return if self.timed_out { 1.0 } else { 0.0 };
+        }
+        self.error_bytes as f64 / self.total_bytes as f64
+    }
+
+    /// Add the diagnostics of another part of the same file.
+    pub fn merge(&mut self, other: &ParseDiagnostics) {
+        let room = MAX_RECORDED_ERRORS.saturating_sub(self.errors.len());
+        self.errors.extend(other.errors.iter().take(room).cloned());
+        self.error_count += other.error_count;
+        self.error_bytes += other.error_bytes;
+        self.total_bytes += other.total_bytes;
+        self.timed_out |= other.timed_out;
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.error_count == 0 && !self.timed_out
+    }
+
+    /// Why the file should not be indexed under `max_error_ratio`, if it should not.
+    pub fn skip_reason(&self, max_error_ratio: f64) -> Option<String> {
+        if self.timed_out {
+            Some("parse timed out".to_string())
+        } else if self.error_ratio() > max_error_ratio {
+            Some(format!("{:.0}% syntax errors", self.error_ratio() * 100.0))
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse diagnostics of one file, as listed in reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileHealth {
+    pub file: String,
+    pub error_count: usize,
+    pub error_ratio: f64,
+    pub timed_out: bool,
+    /// Set when the file was left out of the index.
+    pub skip_reason: Option<String>,
+    /// `line:column message` of the first error.
+    pub first_error: Option<String>,
+}
+
+/// Parse diagnostics aggregated over a build or analysis.
+#[derive(Debug, Default, Serialize)]
+pub struct ParseHealth {
+    pub clean_files: usize,
+    /// Indexed despite syntax errors; symbols inside the errors are missing.
+    pub partial_files: usize,
+    /// Left out of the index, including timeouts.
+    pub skipped_files: usize,
+    pub timed_out_files: usize,
+    pub total_errors: usize,
+    /// Files with errors, skipped ones first, then by error ratio.
+    pub files: Vec<FileHealth>,
+}
+
+impl ParseHealth {
+    pub fn record(&mut self, file: &str, diagnostics: &ParseDiagnostics, skip_reason: Option<String>) {
+        self.total_errors += diagnostics.error_count;
+        if diagnostics.timed_out {
+            self.timed_out_files += 1;
+        }
+        match (&skip_reason, diagnostics.is_clean()) {
+            (Some(_), _) => self.skipped_files += 1,
+            (None, true) => {
+                self.clean_files += 1;
+                return;
+            }
+            (None, false) => self.partial_files += 1,
+        }
+        self.files.push(FileHealth {
+            file: file.to_string(),
+            error_count: diagnostics.error_count,
+            error_ratio: diagnostics.error_ratio(),
+            timed_out: diagnostics.timed_out,
+            skip_reason,
+            first_error: diagnostics.errors.first().map(|e| format!("{}:{} {}", e.start_line, e.start_column, e.message)),
+        });
+    }
+
+    /// Order [`Self::files`]; call once everything is recorded.
+    pub fn finish(&mut self) {
+        self.files.sort_by(|a, b| {
+            b.skip_reason
+                .is_some()
+                .cmp(&a.skip_reason.is_some())
+                .then_with(|| b.error_ratio.total_cmp(&a.error_ratio))
+                .then_with(|| a.file.cmp(&b.file))
+        });
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Parse health: {} clean, {} partial, {} skipped ({} timed out), {} syntax errors",
+            self.clean_files, self.partial_files, self.skipped_files, self.timed_out_files, self.total_errors,
+        )
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.files.iter().take(MAX_LISTED_FILES).map(|f| {
+            vec![
+                f.file.clone(),
+                f.error_count.to_string(),
+                format!("{:.1}%", f.error_ratio * 100.0),
+                f.skip_reason.clone().map_or_else(|| "partial".to_string(), |reason| format!("skipped: {}", reason)),
+                f.first_error.clone().unwrap_or_default(),
+            ]
+        })
+    }
+
+    pub fn render_table(&self) -> String {
+        format!("{}\n{}", self.summary(), table::render(HEALTH_HEADERS, self.rows()))
+    }
+}
+
+pub const HEALTH_HEADERS: &[&str] = &["FILE", "ERRORS", "ERROR RATIO", "STATUS", "FIRST ERROR"];
@@ -0,0 +1,276 @@
+//! Structured documentation parsed from the cleaned text of doc comments and
+//! docstrings (see [`crate::analyzer::tags`]).
+//!
+//! Understands block tags of JSDoc, TSDoc and Javadoc (`@param`, `@returns`,
+//! `@throws`, any other `@tag` as a section), Sphinx fields (`:param x:`),
+//! Google-style (`Args:`, `Returns:`, `Raises:`) and NumPy-style (underlined
+//! headings) docstring sections, and Markdown headings in Rust docs
+//! (`# Arguments`, `# Errors`, `# Panics`).
+
+use std::collections::BTreeMap;
+
+pub use crate::database::records::{DocComment, DocParam, DocValue};
+
+/// Google and NumPy section names, lowercase.
+const PARAM_SECTIONS: &[&str] = &["args", "arguments", "parameters", "params", "keyword args", "keyword arguments", "other parameters"];
+const RETURN_SECTIONS: &[&str] = &["returns", "return"];
+const THROW_SECTIONS: &[&str] = &["raises", "raise", "throws", "exceptions", "errors"];
+const OTHER_SECTIONS: &[&str] = &[
+    "yields", "yield", "attributes", "example", "examples", "note", "notes", "warning", "warnings", "see also", "references", "todo",
+    "methods",
+];
+
+enum Block<'a> {
+    Prose,
+    /// `@name rest`.
+    Tag(&'a str),
+    /// `:field: rest`.
+    Field(&'a str),
+    /// A Google, NumPy or Markdown heading, lowercase. Google sections end at
+    /// the first line indented no deeper than their heading.
+    Section { name: String, ends_at: Option<usize>, markdown: bool },
+}
+
+/// Parse cleaned documentation; `None` when it is empty.
+pub fn parse(text: &str) -> Option<DocComment> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut blocks: Vec<(Block, Vec<&str>)> = vec![(Block::Prose, Vec::new())];
+    let mut fenced = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        // Code in a fenced block, such as a Rust example's hidden `# ` lines, is not structure.
+        if fenced || trimmed.starts_with("```") {
+            fenced ^= trimmed.starts_with("```");
+            if let Some((_, body)) = blocks.last_mut() {
+                body.push(line);
+            }
+            i += 1;
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let underlined = lines.get(i + 1).is_some_and(|next| next.trim().len() >= 3 && next.trim().chars().all(|c| c == '-'));
+        if let Some((Block::Section { ends_at: Some(heading), .. }, _)) = blocks.last() {
+            if !trimmed.is_empty() && indent <= *heading {
+                blocks.push((Block::Prose, Vec::new()));
+            }
+        }
+        if let Some(tag) = trimmed.strip_prefix('@').filter(|tag| tag.starts_with(|c: char| c.is_ascii_alphabetic())) {
+            let (name, rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            blocks.push((Block::Tag(name), vec![rest]));
+        } else if let Some((field, rest)) = trimmed.strip_prefix(':').and_then(|field| field.split_once(':')).filter(|(field, _)| !field.is_empty()) {
+            blocks.push((Block::Field(field), vec![rest]));
+        } else if let Some(heading) = trimmed.strip_prefix("# ") {
+            blocks.push((Block::Section { name: heading.trim().to_lowercase(), ends_at: None, markdown: true }, Vec::new()));
+        } else if let Some(name) = known_section(trimmed.strip_suffix(':').unwrap_or("")) {
+            blocks.push((Block::Section { name, ends_at: Some(indent), markdown: false }, Vec::new()));
+        } else if let Some(name) = known_section(trimmed).filter(|_| underlined) {
+            blocks.push((Block::Section { name, ends_at: None, markdown: false }, Vec::new()));
+            i += 1;
+        } else if let Some((_, body)) = blocks.last_mut() {
+            body.push(line);
+        }
+        i += 1;
+    }
+
+    let mut doc = DocComment::default();
+    for (n, (block, body)) in blocks.iter().enumerate() {
+        match block {
+            Block::Prose if n == 0 => {
+                let paragraph = body.iter().map(|line| line.trim()).skip_while(|line| line.is_empty()).take_while(|line| !line.is_empty());
+                doc.summary = paragraph.collect::<Vec<_>>().join(" ");
+            }
+            Block::Prose => {}
+            Block::Tag(name) => tag(&mut doc, name, body),
+            Block::Field(field) => sphinx_field(&mut doc, field, &one_line(body)),
+            Block::Section { name, markdown, .. } => section(&mut doc, name, *markdown, body),
+        }
+    }
+    let empty = doc.summary.is_empty() && doc.params.is_empty() && doc.returns.is_none() && doc.throws.is_empty() && doc.sections.is_empty();
+    (!empty).then_some(doc)
+}
+
+fn known_section(heading: &str) -> Option<String> {
+    let name = heading.trim().to_lowercase();
+    [PARAM_SECTIONS, RETURN_SECTIONS, THROW_SECTIONS, OTHER_SECTIONS].iter().any(|names| names.contains(&name.as_str())).then_some(name)
+}
+
+/// A JSDoc/Javadoc block tag; `body` starts with the rest of its line.
+fn tag(doc: &mut DocComment, name: &str, body: &[&str]) {
+    let rest = &one_line(body);
+    match name {
+        "param" | "arg" | "argument" => {
+            let (type_name, rest) = braced_type(rest);
+            let (name, description) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            // `[name=default]` marks an optional parameter.
+            let name = name.trim_start_matches('[').split(['=', ']']).next().unwrap_or(name);
+            doc.params.push(DocParam { name: name.to_string(), type_name, description: description_of(description) });
+        }
+        "returns" | "return" => {
+            let (type_name, description) = braced_type(rest);
+            doc.returns = Some(DocValue { type_name, description: description_of(description) });
+        }
+        "throws" | "throw" | "exception" => {
+            let (type_name, description) = match braced_type(rest) {
+                (Some(type_name), description) => (Some(type_name), description),
+                // Javadoc names the exception class first.
+                (None, rest) => match rest.split_once(char::is_whitespace) {
+                    Some((type_name, description)) => (Some(type_name.to_string()), description),
+                    None if !rest.is_empty() => (Some(rest.to_string()), ""),
+                    None => (None, ""),
+                },
+            };
+            doc.throws.push(DocValue { type_name, description: description_of(description) });
+        }
+        // Examples and the like keep their lines.
+        _ => {
+            let text = format!("{}\n{}", body[0], dedent(&body[1..]));
+            append(&mut doc.sections, name.to_lowercase(), text.trim());
+        }
+    }
+}
+
+/// A Sphinx field: `param [type] name`, `type name`, `returns`, `rtype` or `raises Type`.
+fn sphinx_field(doc: &mut DocComment, field: &str, rest: &str) {
+    let words: Vec<&str> = field.split_whitespace().collect();
+    match words.as_slice() {
+        ["param" | "parameter" | "arg" | "argument" | "key" | "keyword", .., name] => {
+            let type_name = (words.len() > 2).then(|| words[1..words.len() - 1].join(" "));
+            doc.params.push(DocParam { name: name.to_string(), type_name, description: rest.to_string() });
+        }
+        ["type", name] => match doc.params.iter_mut().find(|param| param.name == *name) {
+            Some(param) => param.type_name = Some(rest.to_string()),
+            None => doc.params.push(DocParam { name: name.to_string(), type_name: Some(rest.to_string()), description: String::new() }),
+        },
+        ["returns" | "return"] => doc.returns.get_or_insert(DocValue { type_name: None, description: String::new() }).description = rest.to_string(),
+        ["rtype"] => doc.returns.get_or_insert(DocValue { type_name: None, description: String::new() }).type_name = Some(rest.to_string()),
+        ["raises" | "raise" | "except" | "exception" | "throws", type_name] => {
+            doc.throws.push(DocValue { type_name: Some(type_name.to_string()), description: rest.to_string() })
+        }
+        _ => append(&mut doc.sections, field.to_lowercase(), rest),
+    }
+}
+
+fn section(doc: &mut DocComment, name: &str, markdown: bool, body: &[&str]) {
+    if PARAM_SECTIONS.contains(&name) {
+        for (head, description) in entries(body) {
+            doc.params.push(param_entry(head, description));
+        }
+    } else if RETURN_SECTIONS.contains(&name) {
+        doc.returns = Some(value_section(body));
+    } else if THROW_SECTIONS.contains(&name) && !markdown {
+        for (head, description) in entries(body) {
+            let (type_name, inline) = head.split_once(':').map_or((head, ""), |(type_name, inline)| (type_name, inline));
+            doc.throws.push(DocValue { type_name: Some(type_name.trim().to_string()), description: join(inline.trim(), &description) });
+        }
+    } else {
+        let text = dedent(body);
+        if !text.is_empty() {
+            doc.sections.insert(name.to_string(), text);
+        }
+    }
+}
+
+/// Entries of a section: lines at its smallest indentation, or Markdown list
+/// items, each with the more indented lines that follow it.
+fn entries<'a>(body: &[&'a str]) -> Vec<(&'a str, String)> {
+    let indent = body.iter().filter(|line| !line.trim().is_empty()).map(|line| line.len() - line.trim_start().len()).min().unwrap_or(0);
+    let mut entries: Vec<(&str, String)> = Vec::new();
+    for line in body.iter().filter(|line| !line.trim().is_empty()) {
+        let trimmed = line.trim();
+        let item = trimmed.strip_prefix("* ").or_else(|| trimmed.strip_prefix("- "));
+        match entries.last_mut() {
+            Some((_, description)) if item.is_none() && line.len() - line.trim_start().len() > indent => {
+                *description = join(description, trimmed);
+            }
+            _ => entries.push((item.unwrap_or(trimmed), String::new())),
+        }
+    }
+    entries
+}
+
+/// `name (type): description` (Google), `name : type` (NumPy) or
+/// `` `name` - description `` (Rust).
+fn param_entry(head: &str, description: String) -> DocParam {
+    if let Some((name, rest)) = head.strip_prefix('`').and_then(|head| head.split_once('`')) {
+        let inline = rest.trim_start().trim_start_matches(['-', ':', '—']).trim();
+        return DocParam { name: name.to_string(), type_name: None, description: join(inline, &description) };
+    }
+    if let Some((name, type_name)) = head.split_once(" : ") {
+        return DocParam { name: name.trim().to_string(), type_name: Some(type_name.trim().to_string()), description };
+    }
+    let (name, inline) = head.split_once(':').unwrap_or((head, ""));
+    let (name, type_name) = match name.split_once('(') {
+        Some((name, type_name)) => (name.trim(), Some(type_name.trim_end().trim_end_matches(')').trim().to_string())),
+        None => (name.trim(), None),
+    };
+    DocParam { name: name.to_string(), type_name, description: join(inline.trim(), &description) }
+}
+
+/// `type: description` (Google) or a type line followed by an indented
+/// description (NumPy); anything else is a description.
+fn value_section(body: &[&str]) -> DocValue {
+    let entries = entries(body);
+    match entries.as_slice() {
+        [(head, description)] if !description.is_empty() && !head.contains(' ') => {
+            DocValue { type_name: Some(head.trim_end_matches(':').to_string()), description: description.clone() }
+        }
+        [(head, description)] => match head.split_once(": ").filter(|(type_name, _)| !type_name.contains(' ') || type_name.contains('[')) {
+            Some((type_name, inline)) => DocValue { type_name: Some(type_name.to_string()), description: join(inline, description) },
+            None => DocValue { type_name: None, description: join(head, description) },
+        },
+        _ => DocValue { type_name: None, description: one_line(body) },
+    }
+}
+
+/// `- description` or `description`, the dash JSDoc puts after names dropped.
+fn description_of(text: &str) -> String {
+    text.trim().strip_prefix("- ").unwrap_or(text.trim()).trim().to_string()
+}
+
+/// `{Type} rest`, braces nested.
+fn braced_type(text: &str) -> (Option<String>, &str) {
+    let text = text.trim_start();
+    if !text.starts_with('{') {
+        return (None, text);
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (Some(text[1..i].trim().to_string()), text[i + 1..].trim_start());
+                }
+            }
+            _ => {}
+        }
+    }
+    (None, text)
+}
+
+fn append(sections: &mut BTreeMap<String, String>, name: String, text: &str) {
+    let entry = sections.entry(name).or_default();
+    *entry = join(entry, text.trim());
+}
+
+fn join(first: &str, second: &str) -> String {
+    match (first.is_empty(), second.is_empty()) {
+        (true, _) => second.to_string(),
+        (_, true) => first.to_string(),
+        _ => format!("{} {}", first, second),
+    }
+}
+
+fn one_line(lines: &[&str]) -> String {
+    lines.iter().map(|line| line.trim()).filter(|line| !line.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+/// Lines without their common indentation, blank lines around them dropped.
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines.iter().filter(|line| !line.trim().is_empty()).map(|line| line.len() - line.trim_start().len()).min().unwrap_or(0);
+    let lines: Vec<&str> = lines.iter().map(|line| line.get(indent..).unwrap_or("").trim_end()).collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
@@ -0,0 +1,280 @@
+//! Code embedded in other files: fenced blocks in Markdown, code cells in
+//! Jupyter notebooks and `<script>` blocks in Vue and Svelte components.
+//!
+//! Each region is parsed on its own with its language's grammar. Its
+//! `line_offset` maps region lines back to the host file, so symbols point at
+//! the right line of the `.md`, `.vue` or `.svelte` file. Notebook cells map to
+//! the lines of their `source` array in the `.ipynb` JSON; when those cannot be
+//! found, cell lines are used as is.
+
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::analyzer::languages::registry;
+
+/// Files that contain embedded code, by extension.
+const HOSTS: &[(&str, HostFormat)] = &[
+    ("md", HostFormat::Markdown),
+    ("markdown", HostFormat::Markdown),
+    ("ipynb", HostFormat::Notebook),
+    ("vue", HostFormat::Vue),
+    ("svelte", HostFormat::Svelte),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostFormat {
+    Markdown,
+    Notebook,
+    Vue,
+    Svelte,
+}
+
+impl HostFormat {
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        HOSTS.iter().find(|(ext, _)| *ext == extension).map(|(_, format)| *format)
+    }
+
+    /// Name used in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            HostFormat::Markdown => "markdown",
+            HostFormat::Notebook => "jupyter",
+            HostFormat::Vue => "vue",
+            HostFormat::Svelte => "svelte",
+        }
+    }
+}
+
+/// Whether `path` is a file whose embedded code is indexed.
+pub fn is_host(path: &Path) -> bool {
+    HostFormat::of(path).is_some()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegionKind {
+    /// Fenced code block in Markdown.
+    Block,
+    /// Notebook code cell.
+    Cell,
+    /// `<script>` block of a component.
+    Script,
+}
+
+impl RegionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RegionKind::Block => "block",
+            RegionKind::Cell => "cell",
+            RegionKind::Script => "script",
+        }
+    }
+}
+
+/// Where a region sits in its host file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HostRegion {
+    pub kind: RegionKind,
+    /// 0-based position among the host's fenced blocks, cells or script blocks.
+    pub index: usize,
+    /// Host line (0-based) of the region's first line.
+    pub line_offset: usize,
+}
+
+/// A piece of code found in a host file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub host: HostRegion,
+    pub language: &'static str,
+    pub source: String,
+}
+
+/// Code regions of `content` in a supported language, in document order.
+pub fn regions(path: &Path, content: &str) -> Vec<Region> {
+    match HostFormat::of(path) {
+        Some(HostFormat::Markdown) => markdown_regions(content),
+        Some(HostFormat::Notebook) => notebook_regions(content),
+        Some(HostFormat::Vue | HostFormat::Svelte) => script_regions(content),
+        None => Vec::new(),
+    }
+}
+
+/// Language for a fence info string, `lang` attribute or kernel name:
+/// a language name, an extension or an interpreter (`py`, `python3`, `node`).
+pub fn language_named(name: &str) -> Option<&'static str> {
+    let name = name.trim().trim_start_matches(['{', '.']).trim_end_matches('}').to_ascii_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    let registry = registry();
+    registry
+        .get(&name)
+        .or_else(|| registry.for_extension(&name))
+        .or_else(|| registry.for_shebang(&format!("#!{}", name)))
+        .map(|language| language.name())
+}
+
+fn markdown_regions(content: &str) -> Vec<Region> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut regions = Vec::new();
+    let mut index = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        let Some((indent, fence, info)) = opening_fence(lines[i]) else {
+            i += 1;
+            continue;
+        };
+        let start = i + 1;
+        let mut end = start;
+        while end < lines.len() && !closes(lines[end], fence) {
+            end += 1;
+        }
+        if let Some(language) = info.split_whitespace().next().and_then(language_named) {
+            let mut source = String::new();
+            for line in &lines[start..end] {
+                let strip = line.len() - line.trim_start_matches(' ').len();
+                source.push_str(&line[strip.min(indent)..]);
+                source.push('\n');
+            }
+            regions.push(Region { host: HostRegion { kind: RegionKind::Block, index, line_offset: start }, language, source });
+        }
+        index += 1;
+        i = end + 1;
+    }
+    regions
+}
+
+/// Indentation, fence and info string of a line opening a fenced block.
+fn opening_fence(line: &str) -> Option<(usize, &str, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = trimmed.len() - trimmed.trim_start_matches(marker).len();
+    let info = &trimmed[length..];
+    if indent > 3 || length < 3 || (marker == '`' && info.contains('`')) {
+        return None;
+    }
+    Some((indent, &trimmed[..length], info))
+}
+
+fn closes(line: &str, fence: &str) -> bool {
+    let trimmed = line.trim();
+    let marker = &fence[..1];
+    trimmed.starts_with(fence) && trimmed.trim_start_matches(marker).is_empty()
+}
+
+fn notebook_regions(content: &str) -> Vec<Region> {
+    let Ok(notebook) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    let metadata = &notebook["metadata"];
+    let kernel = metadata["kernelspec"]["language"].as_str().or_else(|| metadata["language_info"]["name"].as_str());
+    let Some(language) = kernel.map_or(Some("python"), language_named) else {
+        return Vec::new();
+    };
+    let cells = notebook["cells"].as_array().cloned().unwrap_or_default();
+
+    let mut regions = Vec::new();
+    let mut cursor = 0;
+    for (index, cell) in cells.iter().enumerate() {
+        let lines: Vec<String> = match &cell["source"] {
+            Value::Array(parts) => parts.iter().filter_map(|part| part.as_str()).map(str::to_string).collect(),
+            Value::String(text) => text.split_inclusive('\n').map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        let line_offset = source_line(content, &mut cursor, &cell["source"], lines.first()).unwrap_or(0);
+        if cell["cell_type"] != "code" {
+            continue;
+        }
+        let mut source: String = lines
+            .iter()
+            .map(|line| {
+                // IPython magics and shell escapes are not Python.
+                let magic = language == "python" && line.trim_start().starts_with(['%', '!']);
+                if magic { format!("# {}", line) } else { line.clone() }
+            })
+            .collect();
+        if !source.ends_with('\n') {
+            source.push('\n');
+        }
+        regions.push(Region { host: HostRegion { kind: RegionKind::Cell, index, line_offset }, language, source });
+    }
+    regions
+}
+
+/// Line of the notebook JSON holding the first line of a cell's source,
+/// searching from `cursor` and moving it past the match.
+fn source_line(content: &str, cursor: &mut usize, source: &Value, first: Option<&String>) -> Option<usize> {
+    let expected = match (source, first) {
+        (Value::Array(_), Some(first)) => serde_json::to_string(first).ok()?,
+        (Value::String(text), _) => serde_json::to_string(text).ok()?,
+        _ => return None,
+    };
+    while let Some(found) = content[*cursor..].find("\"source\":") {
+        let key = *cursor + found;
+        *cursor = key + "\"source\":".len();
+        let rest = content[*cursor..].trim_start();
+        let rest = rest.strip_prefix('[').map_or(rest, str::trim_start);
+        if rest.starts_with(expected.as_str()) {
+            let value = content.len() - rest.len();
+            return Some(content[..value].matches('\n').count());
+        }
+    }
+    None
+}
+
+fn script_regions(content: &str) -> Vec<Region> {
+    let lower = content.to_ascii_lowercase();
+    let mut regions = Vec::new();
+    let mut cursor = 0;
+    let mut index = 0;
+    while let Some(found) = lower[cursor..].find("<script") {
+        let tag_start = cursor + found;
+        let Some(tag_end) = lower[tag_start..].find('>').map(|end| tag_start + end) else {
+            break;
+        };
+        let Some(close) = lower[tag_end..].find("</script").map(|end| tag_end + end) else {
+            break;
+        };
+        let attributes = &content[tag_start + "<script".len()..tag_end];
+        cursor = close + "</script".len();
+        index += 1;
+        if attributes.trim_end().ends_with('/') || attribute(attributes, "src").is_some() {
+            continue;
+        }
+        let language = match (attribute(attributes, "lang"), attribute(attributes, "type")) {
+            (Some(lang), _) => language_named(lang),
+            (None, Some("module" | "text/javascript" | "application/javascript")) | (None, None) => Some("javascript"),
+            (None, Some(mime)) => mime.strip_prefix("text/").and_then(language_named),
+        };
+        if let Some(language) = language {
+            let body = &content[tag_end + 1..close];
+            let line_offset = content[..tag_end].matches('\n').count();
+            regions.push(Region { host: HostRegion { kind: RegionKind::Script, index: index - 1, line_offset }, language, source: body.to_string() });
+        }
+    }
+    regions
+}
+
+/// Value of `name="value"` (or single-quoted, or bare) among tag attributes.
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+    while let Some(found) = rest.find(name) {
+        let before = rest[..found].chars().last();
+        let after = rest[found + name.len()..].trim_start();
+        rest = &rest[found + name.len()..];
+        if before.is_some_and(|c| !c.is_whitespace()) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else { continue };
+        let value = value.trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+            _ => value.split(|c: char| c.is_whitespace() || c == '>').next().unwrap_or(""),
+        });
+    }
+    None
+}
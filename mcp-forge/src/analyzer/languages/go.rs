@@ -0,0 +1,284 @@
+//! Go: packages, funcs, methods, types and consts.
+//!
+//! Imports inside the module declared by the nearest `go.mod` resolve to the
+//! files of the imported package. Go interfaces are satisfied implicitly, so
+//! `implements` edges are computed over the whole project by comparing method
+//! sets.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use tree_sitter::{Language, Node, Parser};
+
+use super::{extract_relationships, node_text, walk, LanguageSupport, Project};
+use crate::analyzer::signature::{field_text, list_items, named_children, Parameter, ParameterKind, Signature};
+use crate::analyzer::tags::Tag;
+use crate::knowledge::relationships::Relationship;
+
+pub struct Go;
+
+impl LanguageSupport for Go {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["go"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_go::language()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        include_str!("queries/go.scm")
+    }
+
+    /// Declaration up to the body, on one line.
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        if !matches!(node.kind(), "function_declaration" | "method_declaration") {
+            return None;
+        }
+        let end = node.child_by_field_name("body").map_or(node.end_byte(), |body| body.start_byte());
+        let header = source.get(node.start_byte()..end)?;
+        Some(header.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// The receiver, then one parameter per name (`a, b int` is two), and
+    /// the result type or list.
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        if !matches!(node.kind(), "function_declaration" | "method_declaration") {
+            return None;
+        }
+        let mut parameters = Vec::new();
+        for (field, kind) in [("receiver", ParameterKind::Receiver), ("parameters", ParameterKind::Positional)] {
+            for param in node.child_by_field_name(field).iter().flat_map(|params| named_children(params)) {
+                let kind = if param.kind() == "variadic_parameter_declaration" { ParameterKind::Variadic } else { kind };
+                let type_name = field_text(&param, "type", source);
+                let mut cursor = param.walk();
+                let names: Vec<&str> = param.children_by_field_name("name", &mut cursor).map(|name| node_text(&name, source)).collect();
+                if names.is_empty() {
+                    parameters.push(Parameter::new("", kind).typed(type_name));
+                    continue;
+                }
+                for name in names {
+                    parameters.push(Parameter::new(name, kind).typed(type_name.clone()));
+                }
+            }
+        }
+        Some(Signature {
+            parameters,
+            return_type: field_text(node, "result", source),
+            type_parameters: list_items(node, "type_parameters", source),
+            modifiers: Vec::new(),
+        })
+    }
+
+    /// The package directory, as in an import path: `svc/store.Memory.Save`.
+    fn module_path(&self, file_path: &str) -> String {
+        parent_dir(file_path).to_string()
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &["call_expression"]
+    }
+
+    /// Calls, plus an `imports` edge from each import of a package in the same
+    /// module to the package clause of every file in it.
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], project: &Project) -> Vec<Relationship> {
+        let mut relationships = extract_relationships(self, root, source, file_path, tags);
+        let Some(module) = GoModule::for_file(project, file_path) else {
+            return relationships;
+        };
+        walk(root, |node| {
+            if node.kind() != "import_spec" {
+                return;
+            }
+            let Some(import) = node.child_by_field_name("path").map(|path| unquote(node_text(&path, source))) else { return };
+            let Some(dir) = module.package_dir(&import) else { return };
+            for file in package_files(project, &dir) {
+                if let Some(package) = project.read(file).as_deref().and_then(package_name) {
+                    relationships.push(Relationship {
+                        from: format!("{}::{}", file_path, import),
+                        to: format!("{}::{}", file, package),
+                        kind: "imports".to_string(),
+                        strength: 1.0,
+                    });
+                }
+            }
+        });
+        relationships
+    }
+
+    /// `implements` edges from every named non-interface type to each interface
+    /// whose methods it has, across all Go files of the project.
+    fn project_relationships(&self, project: &Project) -> Vec<Relationship> {
+        let mut parser = Parser::new();
+        if parser.set_language(self.grammar()).is_err() {
+            return Vec::new();
+        }
+        let mut interfaces: Vec<(String, BTreeSet<String>)> = Vec::new();
+        // Types and method sets are per package directory: methods may live in another file.
+        let mut types: BTreeMap<(String, String), String> = BTreeMap::new();
+        let mut methods: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        for file in project.files.iter().filter(|file| file.ends_with(".go")) {
+            let Some(source) = project.read(file) else { continue };
+            let Some(tree) = parser.parse(&source, None) else { continue };
+            let dir = parent_dir(file).to_string();
+            walk(&tree.root_node(), |node| match node.kind() {
+                "type_spec" => {
+                    let (Some(name), Some(ty)) = (node.child_by_field_name("name"), node.child_by_field_name("type")) else {
+                        return;
+                    };
+                    let name = node_text(&name, &source).to_string();
+                    if ty.kind() == "interface_type" {
+                        let required: BTreeSet<String> = (0..ty.named_child_count())
+                            .filter_map(|i| ty.named_child(i))
+                            .filter(|elem| elem.kind() == "method_spec")
+                            .filter_map(|spec| spec.child_by_field_name("name"))
+                            .map(|name| node_text(&name, &source).to_string())
+                            .collect();
+                        // Embedded-only interfaces and constraints are not matched.
+                        if !required.is_empty() {
+                            interfaces.push((format!("{}::{}", file, name), required));
+                        }
+                    } else {
+                        types.insert((dir.clone(), name.clone()), format!("{}::{}", file, name));
+                    }
+                }
+                "method_declaration" => {
+                    if let (Some(receiver), Some(name)) = (receiver_type(&node, &source), node.child_by_field_name("name")) {
+                        methods.entry((dir.clone(), receiver)).or_default().insert(node_text(&name, &source).to_string());
+                    }
+                }
+                _ => {}
+            });
+        }
+
+        let mut relationships = Vec::new();
+        for (key, type_id) in &types {
+            let Some(method_set) = methods.get(key) else { continue };
+            for (interface_id, required) in &interfaces {
+                if required.is_subset(method_set) {
+                    relationships.push(Relationship {
+                        from: type_id.clone(),
+                        to: interface_id.clone(),
+                        kind: "implements".to_string(),
+                        strength: 1.0,
+                    });
+                }
+            }
+        }
+        relationships
+    }
+
+    fn pattern_confidence(&self, pattern: &str, code: &str) -> f32 {
+        match pattern {
+            "Singleton" if code.contains("sync.Once") && code.contains(".Do(") => 0.8,
+            "Factory" if has_constructor(code) => 0.7,
+            "Functional Options" => {
+                let option_type = code.lines().any(|line| line.trim_start().starts_with("type ") && line.contains(" func(*"));
+                let variadic = code.contains("...Option") || code.contains("opts ...");
+                match (option_type, variadic) {
+                    (true, true) => 0.9,
+                    (true, false) | (false, true) => 0.5,
+                    (false, false) => 0.0,
+                }
+            }
+            "Observer" => {
+                let has_subscribe = code.contains("Subscribe(") || code.contains("AddListener(");
+                let has_notify = code.contains("Publish(") || code.contains("Notify(");
+                if has_subscribe && has_notify { 0.8 } else { 0.0 }
+            }
+            "Builder" if code.contains(") Build()") && code.contains("return b") => 0.7,
+            "Repository" if code.contains(") Find") && code.contains(") Save(") => 0.7,
+            "MVC" => super::mvc_confidence(code),
+            "Dependency Injection" if code.contains("wire.Build(") || code.contains("fx.Provide(") => 0.8,
+            "Dependency Injection" if has_constructor(code) && code.contains("interface {") => 0.5,
+            "Decorator" if code.contains("next http.Handler") || code.contains("next http.HandlerFunc") => 0.7,
+            _ => 0.0,
+        }
+    }
+}
+
+/// The module declared by a `go.mod`.
+struct GoModule {
+    /// Directory of the `go.mod`, relative to the project root.
+    dir: String,
+    path: String,
+}
+
+impl GoModule {
+    /// The module of the nearest `go.mod` above `file_path`.
+    fn for_file(project: &Project, file_path: &str) -> Option<Self> {
+        let mut dir = Path::new(file_path).parent();
+        while let Some(current) = dir {
+            if let Ok(go_mod) = std::fs::read_to_string(project.root.join(current).join("go.mod")) {
+                let path = go_mod.lines().find_map(|line| line.trim().strip_prefix("module "))?;
+                let dir = current.to_string_lossy().replace('\\', "/");
+                return Some(Self { dir, path: unquote(path.trim()) });
+            }
+            dir = current.parent();
+        }
+        None
+    }
+
+    /// Directory of an import path inside this module.
+    fn package_dir(&self, import: &str) -> Option<String> {
+        let rest = import.strip_prefix(&self.path)?;
+        if !(rest.is_empty() || rest.starts_with('/')) {
+            return None;
+        }
+        let dir = format!("{}{}", self.dir, rest);
+        Some(dir.trim_start_matches('/').to_string())
+    }
+}
+
+/// Non-test Go files directly inside `dir`.
+fn package_files<'a>(project: &'a Project, dir: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+    project
+        .files
+        .iter()
+        .filter(move |file| file.ends_with(".go") && !file.ends_with("_test.go") && parent_dir(file) == dir)
+}
+
+fn parent_dir(file: &str) -> &str {
+    file.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Name in a file's `package` clause.
+fn package_name(source: &str) -> Option<String> {
+    source
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("package "))
+        .map(|name| name.split_whitespace().next().unwrap_or_default().to_string())
+}
+
+/// Receiver type name without pointer or type arguments: `Store` for `(s *Store[T])`.
+fn receiver_type(method: &Node, source: &str) -> Option<String> {
+    let receiver = method.child_by_field_name("receiver")?;
+    let mut ty = (0..receiver.named_child_count())
+        .filter_map(|i| receiver.named_child(i))
+        .find(|param| param.kind() == "parameter_declaration")?
+        .child_by_field_name("type")?;
+    loop {
+        match ty.kind() {
+            "pointer_type" => ty = ty.named_child(0)?,
+            "generic_type" => ty = ty.child_by_field_name("type")?,
+            _ => return Some(node_text(&ty, source).to_string()),
+        }
+    }
+}
+
+/// A `NewX` (or plain `New`) constructor function.
+fn has_constructor(code: &str) -> bool {
+    code.lines().any(|line| {
+        line.strip_prefix("func New")
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|next| next == '(' || next.is_ascii_uppercase())
+    })
+}
+
+fn unquote(path: &str) -> String {
+    path.trim_matches(|c| c == '"' || c == '`').to_string()
+}
@@ -0,0 +1,360 @@
+//! Java: types, members with their modifiers, packages and imports.
+//!
+//! Imports and supertypes resolve to other indexed files by the usual layout:
+//! `com.acme.model.User` lives in a file ending `com/acme/model/User.java`,
+//! whatever source root (`src/main/java`, ...) precedes it.
+
+use std::collections::HashSet;
+
+use tree_sitter::{Language, Node, Parser, Tree};
+
+use super::{extract_relationships, node_text, walk, LanguageSupport, Project};
+use crate::analyzer::signature::{field_text, keywords, list_items, named_children, one_line, Parameter, ParameterKind, Signature};
+use crate::analyzer::tags::Tag;
+use crate::knowledge::relationships::Relationship;
+
+/// Annotations that mark an injection point.
+const INJECT_ANNOTATIONS: &[&str] = &["Inject", "Autowired", "Resource"];
+
+/// Annotations that register a class with a DI container.
+const COMPONENT_ANNOTATIONS: &[&str] = &["Component", "Service", "Repository", "Controller", "RestController", "Configuration", "Named", "Singleton"];
+
+pub struct Java;
+
+impl LanguageSupport for Java {
+    fn name(&self) -> &'static str {
+        "java"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["java"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_java::language()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        include_str!("queries/java.scm")
+    }
+
+    /// Javadoc, not other block comments.
+    fn doc_comment(&self, comment: &Node, _definition: &Node, source: &str) -> bool {
+        node_text(comment, source).starts_with("/**")
+    }
+
+    /// Imports are named by their path, with `.*` for wildcards.
+    fn symbol_name(&self, node: &Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "import_declaration" => import_of(node, source).map(|import| import.name()),
+            _ => None,
+        }
+    }
+
+    /// Annotations, modifiers, type and name up to the body, on one line.
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        let end = match node.kind() {
+            "package_declaration" | "import_declaration" => return None,
+            "field_declaration" | "constant_declaration" => node.end_byte(),
+            _ => node.child_by_field_name("body").map_or(node.end_byte(), |body| body.start_byte()),
+        };
+        let header = source.get(node.start_byte()..end)?.trim_end().trim_end_matches(';');
+        Some(header.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Parameters, return type, type parameters and the keyword modifiers
+    /// other than visibility: `static`, `abstract`, `final`, `synchronized`, ...
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        if !matches!(node.kind(), "method_declaration" | "constructor_declaration") {
+            return None;
+        }
+        let modifiers = named_children(node)
+            .filter(|child| child.kind() == "modifiers")
+            .flat_map(|modifiers| keywords(&modifiers))
+            .filter(|keyword| !matches!(*keyword, "public" | "protected" | "private"))
+            .map(str::to_string)
+            .collect();
+        let parameters = node
+            .child_by_field_name("parameters")
+            .iter()
+            .flat_map(|params| named_children(params))
+            .filter_map(|param| match param.kind() {
+                "formal_parameter" => {
+                    let name = param.child_by_field_name("name").map_or("", |name| node_text(&name, source));
+                    let dimensions = param.child_by_field_name("dimensions").map_or("", |dimensions| node_text(&dimensions, source));
+                    let type_name = field_text(&param, "type", source).map(|type_name| format!("{}{}", type_name, dimensions));
+                    Some(Parameter::new(name, ParameterKind::Positional).typed(type_name))
+                }
+                "spread_parameter" => {
+                    let declarator = named_children(&param).find(|child| child.kind() == "variable_declarator");
+                    let name = declarator.and_then(|declarator| declarator.child_by_field_name("name")).map_or("", |name| node_text(&name, source));
+                    let type_name = named_children(&param).find(|child| !matches!(child.kind(), "modifiers" | "variable_declarator"));
+                    Some(Parameter::new(name, ParameterKind::Variadic).typed(type_name.map(|type_name| one_line(node_text(&type_name, source)))))
+                }
+                "receiver_parameter" => Some(Parameter::new("this", ParameterKind::Receiver)),
+                _ => None,
+            })
+            .collect();
+        Some(Signature {
+            parameters,
+            return_type: field_text(node, "type", source),
+            type_parameters: list_items(node, "type_parameters", source),
+            modifiers,
+        })
+    }
+
+    /// The package, from the directories below the source root: a type is
+    /// qualified by its own top-level declaration.
+    fn module_path(&self, file_path: &str) -> String {
+        let dir = format!("/{}/", file_path.rsplit_once('/').map_or("", |(dir, _)| dir));
+        let package = ["/java/", "/src/"].iter().find_map(|root| dir.split_once(root).map(|(_, package)| package)).unwrap_or(&dir);
+        package.trim_matches('/').replace('/', ".")
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &["method_invocation", "object_creation_expression"]
+    }
+
+    fn callee<'t>(&self, call: &Node<'t>) -> Option<Node<'t>> {
+        call.child_by_field_name("name").or_else(|| call.child_by_field_name("type")).map(|callee| base_type(&callee))
+    }
+
+    fn supertypes(&self, node: &Node, source: &str) -> Vec<(&'static str, String)> {
+        let mut supertypes = Vec::new();
+        let mut push = |kind: &'static str, list: Node| {
+            for i in 0..list.named_child_count() {
+                if let Some(ty) = list.named_child(i) {
+                    supertypes.push((kind, node_text(&base_type(&ty), source).to_string()));
+                }
+            }
+        };
+        for i in 0..node.named_child_count() {
+            let Some(child) = node.named_child(i) else { continue };
+            match child.kind() {
+                // `superclass` holds the type itself; the others wrap a `type_list`.
+                "superclass" => push("inherits", child),
+                "extends_interfaces" => child.named_child(0).into_iter().for_each(|list| push("inherits", list)),
+                "super_interfaces" => child.named_child(0).into_iter().for_each(|list| push("implements", list)),
+                _ => {}
+            }
+        }
+        supertypes
+    }
+
+    /// Calls and supertypes, with supertypes resolved through imports and the
+    /// package, plus an `imports` edge from each import to the types it names.
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], project: &Project) -> Vec<Relationship> {
+        let resolver = Resolver::new(root, source, file_path, &project.files);
+        let local = format!("{}::", file_path);
+        let mut relationships = extract_relationships(self, root, source, file_path, tags);
+        for relationship in &mut relationships {
+            if relationship.kind == "calls" {
+                continue;
+            }
+            if let Some(target) = relationship.to.strip_prefix(&local).and_then(|name| resolver.resolve(name)) {
+                relationship.to = target;
+            }
+        }
+        for import in &resolver.imports {
+            for target in resolver.import_targets(import) {
+                relationships.push(Relationship {
+                    from: format!("{}{}", local, import.name()),
+                    to: target,
+                    kind: "imports".to_string(),
+                    strength: 1.0,
+                });
+            }
+        }
+        relationships
+    }
+
+    fn pattern_confidence(&self, pattern: &str, code: &str) -> f32 {
+        match pattern {
+            "Singleton" if code.contains("private") && code.contains("static") && code.contains("getInstance(") => 0.9,
+            "Factory" if code.contains("public") && code.contains("create") && code.contains("return") => 0.7,
+            "Observer" => {
+                let has_add_observer = code.contains("addObserver(") || code.contains("addListener(");
+                let has_notify = code.contains("notifyObservers(") || code.contains("update(");
+                if has_add_observer && has_notify { 0.8 } else { 0.0 }
+            }
+            "Builder" if code.contains("return this") && code.contains("build(") => 0.7,
+            "Repository" if code.contains("find") && code.contains("save") => 0.7,
+            "MVC" => super::mvc_confidence(code),
+            "Dependency Injection" => {
+                let Some(tree) = parse(code) else { return 0.0 };
+                let annotations = annotation_names(&tree.root_node(), code);
+                if INJECT_ANNOTATIONS.iter().any(|name| annotations.contains(*name)) {
+                    0.8
+                } else if COMPONENT_ANNOTATIONS.iter().any(|name| annotations.contains(*name)) && has_constructor_parameters(&tree.root_node()) {
+                    // Constructor injection needs no annotation in Spring.
+                    0.6
+                } else {
+                    0.0
+                }
+            }
+            "Decorator" => {
+                let Some(tree) = parse(code) else { return 0.0 };
+                let annotations = annotation_names(&tree.root_node(), code);
+                if annotations.contains("Override") || annotations.contains("Decorator") { 0.7 } else { 0.0 }
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// One `import` statement.
+struct Import {
+    /// Dotted path without `.*`.
+    path: String,
+    wildcard: bool,
+    is_static: bool,
+}
+
+impl Import {
+    /// Symbol name of the import: the path as written.
+    fn name(&self) -> String {
+        if self.wildcard { format!("{}.*", self.path) } else { self.path.clone() }
+    }
+
+    /// The imported type: static imports name a member of it.
+    fn type_path(&self) -> &str {
+        match (self.is_static, self.wildcard) {
+            (true, false) => self.path.rsplit_once('.').map_or(self.path.as_str(), |(ty, _)| ty),
+            _ => &self.path,
+        }
+    }
+}
+
+/// Maps type names used in one file to the indexed files declaring them.
+struct Resolver<'a> {
+    file_path: &'a str,
+    imports: Vec<Import>,
+    files: &'a [String],
+}
+
+impl<'a> Resolver<'a> {
+    fn new(root: &Node, source: &str, file_path: &'a str, files: &'a [String]) -> Self {
+        let mut imports = Vec::new();
+        walk(root, |node| {
+            if node.kind() == "import_declaration" {
+                imports.extend(import_of(&node, source));
+            }
+        });
+        Self { file_path, imports, files }
+    }
+
+    /// `file::Type` for a type name, trying explicit imports, the file's own
+    /// package, then wildcard imports.
+    fn resolve(&self, name: &str) -> Option<String> {
+        if name.contains('.') {
+            return self.file_for(name).map(|file| symbol_id(file, name));
+        }
+        let explicit = self
+            .imports
+            .iter()
+            .filter(|import| !import.wildcard && !import.is_static)
+            .find(|import| import.path.rsplit('.').next() == Some(name))
+            .and_then(|import| self.file_for(&import.path));
+        let same_package = || {
+            let dir = self.file_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+            let candidate = if dir.is_empty() { format!("{}.java", name) } else { format!("{}/{}.java", dir, name) };
+            self.files.iter().find(|file| **file == candidate).map(String::as_str)
+        };
+        let wildcard = || {
+            self.imports
+                .iter()
+                .filter(|import| import.wildcard && !import.is_static)
+                .find_map(|import| self.file_for(&format!("{}.{}", import.path, name)))
+        };
+        explicit.or_else(same_package).or_else(wildcard).map(|file| symbol_id(file, name))
+    }
+
+    /// Types an import brings in, as symbol ids in indexed files.
+    fn import_targets(&self, import: &Import) -> Vec<String> {
+        if import.wildcard && !import.is_static {
+            let dir = import.path.replace('.', "/");
+            return self
+                .files
+                .iter()
+                .filter(|file| file.ends_with(".java"))
+                .filter(|file| {
+                    file.rsplit_once('/').is_some_and(|(parent, _)| parent == dir || parent.ends_with(&format!("/{}", dir)))
+                })
+                .map(|file| symbol_id(file, file.trim_end_matches(".java")))
+                .collect();
+        }
+        self.file_for(import.type_path()).map(|file| symbol_id(file, import.type_path())).into_iter().collect()
+    }
+
+    /// Indexed file declaring the dotted type `path`.
+    fn file_for(&self, path: &str) -> Option<&'a str> {
+        let suffix = format!("{}.java", path.replace('.', "/"));
+        self.files
+            .iter()
+            .find(|file| **file == suffix || file.ends_with(&format!("/{}", suffix)))
+            .map(String::as_str)
+    }
+}
+
+/// `file::Type`, keeping only the last segment of a dotted or path-like name.
+fn symbol_id(file: &str, name: &str) -> String {
+    let simple = name.rsplit(['.', '/']).next().unwrap_or(name);
+    format!("{}::{}", file, simple)
+}
+
+fn import_of(node: &Node, source: &str) -> Option<Import> {
+    let mut import = Import { path: String::new(), wildcard: false, is_static: false };
+    for i in 0..node.child_count() {
+        let Some(child) = node.child(i) else { continue };
+        match child.kind() {
+            "static" => import.is_static = true,
+            "asterisk" => import.wildcard = true,
+            "identifier" | "scoped_identifier" => import.path = node_text(&child, source).to_string(),
+            _ => {}
+        }
+    }
+    (!import.path.is_empty()).then_some(import)
+}
+
+/// The named type under generics and annotations: `List` for `List<User>`.
+fn base_type<'t>(ty: &Node<'t>) -> Node<'t> {
+    match ty.kind() {
+        "generic_type" | "annotated_type" => {
+            (0..ty.named_child_count())
+                .filter_map(|i| ty.named_child(i))
+                .find(|child| matches!(child.kind(), "type_identifier" | "scoped_type_identifier" | "generic_type"))
+                .map_or(*ty, |inner| base_type(&inner))
+        }
+        _ => *ty,
+    }
+}
+
+fn parse(code: &str) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(tree_sitter_java::language()).ok()?;
+    parser.parse(code, None)
+}
+
+/// Simple names of every annotation under `root`.
+fn annotation_names(root: &Node, source: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    walk(root, |node| {
+        if matches!(node.kind(), "annotation" | "marker_annotation") {
+            if let Some(name) = node.child_by_field_name("name") {
+                let name = node_text(&name, source);
+                names.insert(name.rsplit('.').next().unwrap_or(name).to_string());
+            }
+        }
+    });
+    names
+}
+
+fn has_constructor_parameters(root: &Node) -> bool {
+    let mut found = false;
+    walk(root, |node| {
+        if node.kind() == "constructor_declaration" {
+            found |= node.child_by_field_name("parameters").is_some_and(|params| params.named_child_count() > 0);
+        }
+    });
+    found
+}
@@ -0,0 +1,247 @@
+//! JavaScript and JSX: functions, classes, module-level variables and imports,
+//! plus React components and hooks.
+
+use tree_sitter::{Language, Node};
+
+use super::{extract_relationships, node_text, LanguageSupport, Project};
+use crate::analyzer::signature::{field_text, header, keywords, list_items, named_children, Parameter, ParameterKind, Signature};
+use crate::analyzer::tags::Tag;
+use crate::knowledge::relationships::Relationship;
+
+/// Node kinds of functions and methods, TypeScript's bodiless ones included.
+const CALLABLE_KINDS: &[&str] = &[
+    "function_declaration",
+    "generator_function_declaration",
+    "function_expression",
+    "function",
+    "generator_function",
+    "arrow_function",
+    "method_definition",
+    "function_signature",
+    "method_signature",
+    "abstract_method_signature",
+];
+
+/// Base classes of React class components.
+const COMPONENT_BASES: &[&str] = &["Component", "PureComponent", "React.Component", "React.PureComponent"];
+
+pub struct JavaScript;
+
+impl LanguageSupport for JavaScript {
+    fn name(&self) -> &'static str {
+        "javascript"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["js", "jsx", "mjs", "cjs"]
+    }
+
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["node", "nodejs"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_javascript::language()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        include_str!("queries/javascript.scm")
+    }
+
+    fn refine_kind(&self, node: &Node, name: &str, kind: &str, source: &str) -> Option<&'static str> {
+        refine_kind(node, name, kind, source)
+    }
+
+    fn doc_comment(&self, comment: &Node, _definition: &Node, source: &str) -> bool {
+        doc_comment(comment, source)
+    }
+
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        signature(node, source)
+    }
+
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        callable(node, source)
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &["call_expression"]
+    }
+
+    fn supertypes(&self, node: &Node, source: &str) -> Vec<(&'static str, String)> {
+        heritage(node, source)
+    }
+
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], _project: &Project) -> Vec<Relationship> {
+        relationships(self, root, source, file_path, tags)
+    }
+
+    fn pattern_confidence(&self, pattern: &str, code: &str) -> f32 {
+        pattern_confidence(pattern, code)
+    }
+}
+
+/// JSDoc and TSDoc blocks and `//` comments; other block comments, such as
+/// license headers, are not documentation.
+pub(super) fn doc_comment(comment: &Node, source: &str) -> bool {
+    let text = node_text(comment, source);
+    !text.starts_with("/*") || text.starts_with("/**")
+}
+
+/// Declaration up to the body. Functions assigned to a variable start at its
+/// name: `load = async (url) =>`.
+pub(super) fn signature(node: &Node, source: &str) -> Option<String> {
+    if !CALLABLE_KINDS.contains(&node.kind()) {
+        return None;
+    }
+    let start = match node.parent().filter(|parent| parent.kind() == "variable_declarator") {
+        Some(declarator) => declarator.start_byte(),
+        None => (0..node.child_count())
+            .filter_map(|i| node.child(i))
+            .find(|child| child.kind() != "decorator")
+            .map_or(node.start_byte(), |child| child.start_byte()),
+    };
+    header(node, start, source).map(|header| header.trim_end_matches(';').to_string())
+}
+
+/// Parameters, annotations and modifiers of JavaScript and TypeScript functions.
+pub(super) fn callable(node: &Node, source: &str) -> Option<Signature> {
+    if !CALLABLE_KINDS.contains(&node.kind()) {
+        return None;
+    }
+    let mut modifiers: Vec<String> = keywords(node)
+        .into_iter()
+        .filter_map(|keyword| match keyword {
+            "async" | "static" | "abstract" | "get" | "set" => Some(keyword),
+            "*" => Some("generator"),
+            _ => None,
+        })
+        .map(str::to_string)
+        .collect();
+    if node.kind().starts_with("generator_") && !modifiers.iter().any(|modifier| modifier == "generator") {
+        modifiers.push("generator".to_string());
+    }
+
+    let mut parameters = Vec::new();
+    // `x => x` has a bare `parameter`.
+    if let Some(param) = node.child_by_field_name("parameter") {
+        parameters.push(Parameter::new(node_text(&param, source), ParameterKind::Positional));
+    }
+    for param in node.child_by_field_name("parameters").iter().flat_map(|params| named_children(params)) {
+        let (pattern, kind) = match param.kind() {
+            "required_parameter" => (param.child_by_field_name("pattern").unwrap_or(param), ParameterKind::Positional),
+            "optional_parameter" => (param.child_by_field_name("pattern").unwrap_or(param), ParameterKind::Optional),
+            "assignment_pattern" => (param.child_by_field_name("left").unwrap_or(param), ParameterKind::Positional),
+            "comment" => continue,
+            _ => (param, ParameterKind::Positional),
+        };
+        let (name, kind) = match pattern.kind() {
+            "rest_pattern" => (pattern.named_child(0).unwrap_or(pattern), ParameterKind::Variadic),
+            "this" => (pattern, ParameterKind::Receiver),
+            _ => (pattern, kind),
+        };
+        let default = field_text(&param, "value", source).or_else(|| field_text(&param, "right", source));
+        parameters.push(Parameter::new(node_text(&name, source), kind).typed(field_text(&param, "type", source)).with_default(default));
+    }
+    Some(Signature {
+        parameters,
+        return_type: field_text(node, "return_type", source),
+        type_parameters: list_items(node, "type_parameters", source),
+        modifiers,
+    })
+}
+
+/// React kinds shared by JavaScript and TypeScript. Functions named like
+/// hooks (`useAuth`) are `hook`s; capitalised functions returning JSX and
+/// subclasses of `React.Component` are `component`s.
+pub(super) fn refine_kind(node: &Node, name: &str, kind: &str, source: &str) -> Option<&'static str> {
+    match kind {
+        "class" if heritage(node, source).iter().any(|(_, base)| COMPONENT_BASES.contains(&base.as_str())) => Some("component"),
+        "function" if is_hook_name(name) => Some("hook"),
+        "function" if name.starts_with(|c: char| c.is_ascii_uppercase()) && contains_jsx(node) => Some("component"),
+        _ => None,
+    }
+}
+
+/// Default relationships, with calls to hooks recorded as `uses_hook` edges.
+pub(super) fn relationships<L: LanguageSupport + ?Sized>(
+    language: &L,
+    root: &Node,
+    source: &str,
+    file_path: &str,
+    tags: &[Tag],
+) -> Vec<Relationship> {
+    let mut relationships = extract_relationships(language, root, source, file_path, tags);
+    for relationship in relationships.iter_mut().filter(|r| r.kind == "calls") {
+        let Some(callee) = relationship.to.rsplit("::").next() else { continue };
+        // `React.useState` and `useState` are the same hook.
+        let hook = callee.rsplit('.').next().unwrap_or(callee);
+        if is_hook_name(hook) {
+            relationship.to = format!("{}::{}", file_path, hook);
+            relationship.kind = "uses_hook".to_string();
+        }
+    }
+    relationships
+}
+
+/// `use` followed by a capital letter, as React's rules of hooks require.
+fn is_hook_name(name: &str) -> bool {
+    name.strip_prefix("use").is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+fn contains_jsx(node: &Node) -> bool {
+    (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .any(|child| child.kind().starts_with("jsx_") || contains_jsx(&child))
+}
+
+/// Names in a class's `extends` and, for TypeScript, `implements` clauses.
+pub(super) fn heritage(node: &Node, source: &str) -> Vec<(&'static str, String)> {
+    let mut names = Vec::new();
+    for i in 0..node.named_child_count() {
+        let Some(heritage) = node.named_child(i).filter(|child| child.kind() == "class_heritage") else {
+            continue;
+        };
+        for j in 0..heritage.named_child_count() {
+            let Some(clause) = heritage.named_child(j) else { continue };
+            let kind = match clause.kind() {
+                "extends_clause" => "inherits",
+                "implements_clause" => "implements",
+                // JavaScript puts the superclass expression directly in the heritage.
+                _ => {
+                    names.push(("inherits", node_text(&clause, source).to_string()));
+                    continue;
+                }
+            };
+            for k in 0..clause.named_child_count() {
+                if let Some(name) = clause.named_child(k).filter(|n| n.kind() != "type_arguments") {
+                    names.push((kind, node_text(&name, source).to_string()));
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Heuristics shared by JavaScript and TypeScript.
+pub(super) fn pattern_confidence(pattern: &str, code: &str) -> f32 {
+    match pattern {
+        "Singleton" => {
+            let has_instance = code.contains("static instance") || code.contains("let instance") || code.contains("var instance");
+            let has_get_instance = code.contains("getInstance(") || code.contains("get_instance(");
+            if has_instance && has_get_instance { 0.8 } else if has_get_instance { 0.5 } else { 0.0 }
+        }
+        "Factory" if code.contains("function create") && code.contains("return") => 0.7,
+        "Observer" => {
+            let has_subscribe = code.contains("subscribe(") || code.contains("on(");
+            let has_notify = code.contains("notify(") || code.contains("emit(");
+            if has_subscribe && has_notify { 0.8 } else { 0.0 }
+        }
+        "Builder" if code.contains("return this") && code.contains("build(") => 0.7,
+        "Repository" if code.contains("find(") && code.contains("save(") => 0.7,
+        "MVC" => super::mvc_confidence(code),
+        "Dependency Injection" if code.contains("constructor(") && code.contains("service") => 0.7,
+        "Decorator" if code.contains("@") && code.contains("function ") => 0.7,
+        _ => 0.0,
+    }
+}
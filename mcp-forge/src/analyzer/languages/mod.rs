@@ -0,0 +1,373 @@
+//! Per-language parsing, extraction and pattern rules.
+//!
+//! Each supported language is one module implementing [`LanguageSupport`];
+//! [`LanguageRegistry::builtin`] lists them. The parser, symbol extractor and
+//! pattern detector look languages up here instead of matching on names.
+//! Which nodes define symbols is described by each language's tag query in
+//! `queries/`, see [`crate::analyzer::tags`].
+
+pub mod go;
+pub mod java;
+pub mod javascript;
+pub mod python;
+pub mod rust;
+pub mod typescript;
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tree_sitter::{Language, Node};
+
+use crate::analyzer::signature::Signature;
+use crate::analyzer::tags::Tag;
+use crate::knowledge::relationships::Relationship;
+
+/// Bytes read from an extensionless file when looking for a `#!` line.
+const SHEBANG_LIMIT: u64 = 256;
+
+/// Symbol kinds that can enclose a call.
+const CALLER_KINDS: &[&str] = &["function", "method", "constructor", "class", "component", "hook"];
+
+/// Everything the analyzer needs to know about one language: how files are
+/// recognised, its tree-sitter grammar and tag query, and the hooks that turn
+/// tags into symbols. Each built-in language implements it, and
+/// [`LanguageRegistry::register`] accepts further ones.
+pub trait LanguageSupport: Send + Sync {
+    /// Name used in `indexing.languages` and reports.
+    fn name(&self) -> &'static str;
+
+    /// File extensions handled, without the dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Exact file names handled whatever their extension.
+    fn file_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Interpreters that select this language in a `#!` line, without a
+    /// version suffix: `python` also matches `python3.11`.
+    fn interpreters(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn grammar(&self) -> Language;
+
+    /// Built-in tag query (`.scm`) finding definitions, references and docs.
+    fn tags_query(&self) -> &'static str;
+
+    /// A more specific kind for a definition the query tagged as `kind`.
+    fn refine_kind(&self, _node: &Node, _name: &str, _kind: &str, _source: &str) -> Option<&'static str> {
+        None
+    }
+
+    /// Name for a tagged node when the `@name` capture cannot express it.
+    fn symbol_name(&self, _node: &Node, _source: &str) -> Option<String> {
+        None
+    }
+
+    /// Definition kinds that are not the parent of the definitions inside
+    /// them, such as Rust `impl` blocks, whose items belong to their type.
+    fn transparent_kinds(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Definition kinds that belong to the innermost enclosing definition
+    /// that is not a function, such as Python attributes assigned in
+    /// `__init__`, which are members of the class.
+    fn hoisted_kinds(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Qualified name of the module `file_path` defines, prefixed to the
+    /// qualified names of its symbols: `src/app/models.ts` is `src.app.models`.
+    fn module_path(&self, file_path: &str) -> String {
+        path_segments(file_path).join(".")
+    }
+
+    /// Joins the parts of a qualified name.
+    fn scope_separator(&self) -> &'static str {
+        "."
+    }
+
+    /// Declaration header recorded as the symbol's signature.
+    fn symbol_signature(&self, _node: &Node, _source: &str) -> Option<String> {
+        None
+    }
+
+    /// Parameters, return type, type parameters and modifiers of a function
+    /// or method; `None` for other definitions.
+    fn callable(&self, _node: &Node, _source: &str) -> Option<Signature> {
+        None
+    }
+
+    /// Whether `comment`, captured as `@doc` of `definition`, documents it;
+    /// for languages that tell doc comments apart from other comments.
+    fn doc_comment(&self, _comment: &Node, _definition: &Node, _source: &str) -> bool {
+        true
+    }
+
+    /// Visibility written on a definition, such as `pub(crate)`.
+    fn symbol_visibility(&self, _node: &Node, _source: &str) -> Option<String> {
+        None
+    }
+
+    /// Declared type of a field, attribute or constant, as written.
+    fn symbol_type(&self, _node: &Node, _source: &str) -> Option<String> {
+        None
+    }
+
+    /// Attributes or annotations on a definition, without their markers: `derive(Debug)`.
+    fn symbol_attributes(&self, _node: &Node, _source: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Node kinds of calls.
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Called expression of a call node: the `function` field, else the first child.
+    fn callee<'t>(&self, call: &Node<'t>) -> Option<Node<'t>> {
+        call.child_by_field_name("function").or_else(|| call.child(0))
+    }
+
+    /// Types `node` extends or implements, with the edge kind (`inherits` or `implements`).
+    fn supertypes(&self, _node: &Node, _source: &str) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Edges from the symbols of one file, given its tags.
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], _project: &Project) -> Vec<Relationship> {
+        extract_relationships(self, root, source, file_path, tags)
+    }
+
+    /// Edges that need every file of the language at once, computed after the per-file ones.
+    fn project_relationships(&self, _project: &Project) -> Vec<Relationship> {
+        Vec::new()
+    }
+
+    /// Confidence that `code` implements `pattern`; 0.0 without a heuristic.
+    fn pattern_confidence(&self, _pattern: &str, _code: &str) -> f32 {
+        0.0
+    }
+}
+
+/// The indexed source tree, for languages that resolve references across files.
+#[derive(Debug, Clone, Default)]
+pub struct Project {
+    pub root: PathBuf,
+    /// Indexed files relative to `root`, `/`-separated and sorted.
+    pub files: Vec<String>,
+}
+
+impl Project {
+    pub fn new(root: impl Into<PathBuf>, files: Vec<String>) -> Self {
+        Self { root: root.into(), files }
+    }
+
+    /// A project from discovered paths under `root`.
+    pub fn from_paths(root: &Path, paths: &[PathBuf]) -> Self {
+        let mut files: Vec<String> = paths
+            .iter()
+            .map(|path| path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/"))
+            .collect();
+        files.sort();
+        Self::new(root, files)
+    }
+
+    pub fn read(&self, file: &str) -> Option<String> {
+        std::fs::read_to_string(self.root.join(file)).ok()
+    }
+}
+
+/// The languages mcp-forge can parse.
+pub struct LanguageRegistry {
+    languages: Vec<Box<dyn LanguageSupport>>,
+}
+
+impl Default for LanguageRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl LanguageRegistry {
+    pub fn builtin() -> Self {
+        Self {
+            languages: vec![
+                Box::new(typescript::TypeScript),
+                Box::new(typescript::Tsx),
+                Box::new(javascript::JavaScript),
+                Box::new(python::Python),
+                Box::new(rust::Rust),
+                Box::new(java::Java),
+                Box::new(go::Go),
+            ],
+        }
+    }
+
+    /// Add a language; it takes precedence over earlier ones sharing an extension or file name.
+    pub fn register(&mut self, language: Box<dyn LanguageSupport>) {
+        self.languages.retain(|existing| existing.name() != language.name());
+        self.languages.insert(0, language);
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.languages.iter().map(|language| language.name()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn LanguageSupport> {
+        self.languages.iter().find(|language| language.name() == name).map(Box::as_ref)
+    }
+
+    /// [`LanguageSupport::project_relationships`] of every language.
+    pub fn project_relationships(&self, project: &Project) -> Vec<Relationship> {
+        self.languages.iter().flat_map(|language| language.project_relationships(project)).collect()
+    }
+
+    pub fn for_extension(&self, extension: &str) -> Option<&dyn LanguageSupport> {
+        self.languages
+            .iter()
+            .find(|language| language.extensions().contains(&extension))
+            .map(Box::as_ref)
+    }
+
+    /// Language of a file: exact file name first, then extension.
+    pub fn for_path(&self, path: &Path) -> Option<&dyn LanguageSupport> {
+        let file_name = path.file_name().and_then(|name| name.to_str());
+        if let Some(file_name) = file_name {
+            if let Some(language) = self.languages.iter().find(|language| language.file_names().contains(&file_name)) {
+                return Some(language.as_ref());
+            }
+        }
+        self.for_extension(path.extension().and_then(|ext| ext.to_str())?)
+    }
+
+    /// Language of a script from its `#!` line, looking through `env`.
+    pub fn for_shebang(&self, line: &str) -> Option<&dyn LanguageSupport> {
+        let mut words = line.strip_prefix("#!")?.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+        }
+        let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        self.languages.iter().find(|language| language.interpreters().contains(&program)).map(Box::as_ref)
+    }
+
+    /// Language of a file from its path, or from its `#!` line when it has no extension.
+    pub fn for_source(&self, path: &Path, source: &str) -> Option<&dyn LanguageSupport> {
+        self.for_path(path).or_else(|| {
+            if path.extension().is_some() {
+                return None;
+            }
+            self.for_shebang(source.lines().next()?)
+        })
+    }
+
+    /// Like [`Self::for_source`], reading the first line of an extensionless file from disk.
+    pub fn detect(&self, path: &Path) -> Option<&dyn LanguageSupport> {
+        self.for_path(path).or_else(|| {
+            if path.extension().is_some() {
+                return None;
+            }
+            let mut line = String::new();
+            BufReader::new(std::fs::File::open(path).ok()?.take(SHEBANG_LIMIT)).read_line(&mut line).ok()?;
+            self.for_shebang(&line)
+        })
+    }
+}
+
+/// MVC heuristic shared by languages whose names follow the pattern's vocabulary.
+pub(crate) fn mvc_confidence(code: &str) -> f32 {
+    if code.contains("Model") && code.contains("View") && code.contains("Controller") { 0.8 } else { 0.0 }
+}
+
+/// The built-in registry shared by parsers, extractors and detectors.
+pub fn registry() -> &'static LanguageRegistry {
+    static REGISTRY: OnceLock<LanguageRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(LanguageRegistry::builtin)
+}
+
+pub fn node_text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+/// Whether `inner` lies within the bytes of `outer`.
+pub(crate) fn encloses(outer: &Node, inner: &Node) -> bool {
+    outer.start_byte() <= inner.start_byte() && inner.end_byte() <= outer.end_byte()
+}
+
+/// Directories and stem of a `/`-separated path.
+pub(crate) fn path_segments(file_path: &str) -> Vec<&str> {
+    let (dirs, file) = file_path.rsplit_once('/').unwrap_or(("", file_path));
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    dirs.split('/').chain([stem]).filter(|segment| !segment.is_empty()).collect()
+}
+
+/// Pre-order walk over `root` and its descendants.
+pub fn walk<'t>(root: &Node<'t>, mut visit: impl FnMut(Node<'t>)) {
+    let mut to_visit = vec![*root];
+    while let Some(node) = to_visit.pop() {
+        visit(node);
+        for i in (0..node.child_count()).rev() {
+            if let Some(child) = node.child(i) {
+                to_visit.push(child);
+            }
+        }
+    }
+}
+
+/// Default [`LanguageSupport::relationships`]: calls are attributed to the
+/// innermost enclosing function or class, and `@reference` tags to the
+/// innermost enclosing definition; callees and supertypes resolve within the
+/// file, to the first definition of that name visible from the source: at the
+/// top level or in a definition enclosing it.
+pub fn extract_relationships<L: LanguageSupport + ?Sized>(
+    language: &L,
+    root: &Node,
+    source: &str,
+    file_path: &str,
+    tags: &[Tag],
+) -> Vec<Relationship> {
+    let mut relationships = Vec::new();
+    let target = |from: &Tag, name: &str| {
+        tags.iter()
+            .filter(|tag| tag.definition && tag.name == name)
+            .find(|tag| tag.parent.is_none_or(|parent| encloses(&tags[parent].node, &from.node)))
+            .map_or(name, |tag| tag.path.as_str())
+            .to_string()
+    };
+    let edge = |from: &Tag, to: &str, kind: &str| Relationship {
+        from: format!("{}::{}", file_path, from.path),
+        to: format!("{}::{}", file_path, target(from, to)),
+        kind: kind.to_string(),
+        strength: 1.0,
+    };
+    // Tags are sorted outer first, so the last match is the innermost.
+    let enclosing = |node: &Node, callers_only: bool| {
+        tags.iter()
+            .rev()
+            .filter(|tag| tag.definition && tag.node.id() != node.id())
+            .filter(|tag| !callers_only || CALLER_KINDS.contains(&tag.kind.as_str()))
+            .find(|tag| tag.node.start_byte() <= node.start_byte() && node.end_byte() <= tag.node.end_byte())
+    };
+    walk(root, |node| {
+        if language.call_kinds().contains(&node.kind()) {
+            if let (Some(callee), Some(caller)) = (language.callee(&node), enclosing(&node, true)) {
+                relationships.push(edge(caller, node_text(&callee, source), "calls"));
+            }
+        }
+    });
+    for tag in tags {
+        if tag.definition {
+            for (kind, supertype) in language.supertypes(&tag.node, source) {
+                relationships.push(edge(tag, &supertype, kind));
+            }
+        } else if let Some(from) = enclosing(&tag.node, false) {
+            let kind = if tag.kind == "call" { "calls" } else { "references" };
+            relationships.push(edge(from, &tag.name, kind));
+        }
+    }
+    relationships
+}
@@ -0,0 +1,318 @@
+//! Python: functions, classes and imports with their docstrings; superclasses
+//! become `inherits` edges. Functions record their parameters, annotations
+//! and defaults, and `async`, generators and the `staticmethod`,
+//! `classmethod` and `abstractmethod` decorators as modifiers.
+//!
+//! Decorators are a definition's attributes; `@property` methods and route
+//! handlers (`@app.get("/users")`) get their own kinds. Assignments in a
+//! class body are attributes, or fields in dataclasses and pydantic models;
+//! `self.x = ...` in `__init__` adds an attribute to the class, and upper
+//! case module-level names are constants. Visibility follows `__all__` when
+//! the module has one, the leading underscore convention otherwise.
+
+use tree_sitter::{Language, Node};
+
+use super::{node_text, path_segments, LanguageSupport};
+use crate::analyzer::signature::{field_text, header, keywords, list_items, named_children, one_line, yields, Parameter, ParameterKind, Signature};
+
+/// Decorators recorded as modifiers, by their last dotted segment.
+const MODIFIER_DECORATORS: &[(&str, &str)] = &[("staticmethod", "static"), ("classmethod", "classmethod"), ("abstractmethod", "abstract")];
+
+/// Decorators making a method a property, by their last dotted segment.
+const PROPERTY_DECORATORS: &[&str] = &["property", "cached_property", "setter", "getter", "deleter"];
+
+/// Methods of Flask, FastAPI, Starlette and similar apps and routers that
+/// register a route when called as a decorator: `@app.route("/")`, `@router.get("/")`.
+const ROUTE_DECORATORS: &[&str] = &["route", "get", "post", "put", "patch", "delete", "head", "options", "websocket", "api_route"];
+
+/// Class decorators whose annotated class attributes are fields: dataclasses and attrs.
+const DATACLASS_DECORATORS: &[&str] = &["dataclass", "define", "frozen", "mutable", "attrs", "s"];
+
+/// Base classes whose annotated class attributes are fields: pydantic models and typed records.
+const MODEL_BASES: &[&str] = &["BaseModel", "BaseSettings", "RootModel", "SQLModel", "TypedDict", "NamedTuple"];
+
+pub struct Python;
+
+impl LanguageSupport for Python {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py", "pyi"]
+    }
+
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["python"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_python::language()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        include_str!("queries/python.scm")
+    }
+
+    /// Dotted module path below a `src/` layout; a package is named by its
+    /// directory, not its `__init__.py`.
+    fn module_path(&self, file_path: &str) -> String {
+        let mut segments = path_segments(file_path);
+        if segments.first() == Some(&"src") {
+            segments.remove(0);
+        }
+        if segments.last() == Some(&"__init__") {
+            segments.pop();
+        }
+        segments.join(".")
+    }
+
+    /// `@property` methods are properties, route handlers routes, and
+    /// annotated attributes of dataclasses and models fields.
+    fn refine_kind(&self, node: &Node, _name: &str, kind: &str, source: &str) -> Option<&'static str> {
+        match kind {
+            "function" => {
+                let decorators = decorators(node);
+                if in_class(node) && decorators.iter().any(|decorator| PROPERTY_DECORATORS.contains(&last_segment(&callee_text(decorator, source)))) {
+                    Some("property")
+                } else if decorators.iter().any(|decorator| is_route(decorator, source)) {
+                    Some("route")
+                } else {
+                    None
+                }
+            }
+            "attribute" => {
+                let class = node.parent()?.parent()?.parent().filter(|class| class.kind() == "class_definition")?;
+                let annotated = field_text(node, "type", source).is_some_and(|ty| !ty.starts_with("ClassVar") && !ty.contains(".ClassVar"));
+                (annotated && has_fields(&class, source)).then_some("field")
+            }
+            _ => None,
+        }
+    }
+
+    fn hoisted_kinds(&self) -> &'static [&'static str] {
+        &["attribute"]
+    }
+
+    /// The `def` line, without decorators and the trailing colon, or an
+    /// assignment with its value when the value fits on one line.
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "function_definition" => header(node, node.start_byte(), source).map(|header| header.trim_end_matches(':').to_string()),
+            "assignment" => {
+                let end = node.child_by_field_name("type").map_or(node.child_by_field_name("left")?.end_byte(), |ty| ty.end_byte());
+                let mut signature = one_line(source.get(node.start_byte()..end)?);
+                if let Some(value) = node.child_by_field_name("right").filter(|value| value.start_position().row == value.end_position().row) {
+                    signature = format!("{} = {}", signature, node_text(&value, source));
+                }
+                Some(signature)
+            }
+            _ => None,
+        }
+    }
+
+    /// The annotation of an attribute, field or constant.
+    fn symbol_type(&self, node: &Node, source: &str) -> Option<String> {
+        (node.kind() == "assignment").then(|| field_text(node, "type", source)).flatten()
+    }
+
+    /// `public` for the exported surface of a module, the names listed in
+    /// `__all__` or else those without a leading underscore, and for members
+    /// without one; `private` otherwise. Local definitions and imports have none.
+    fn symbol_visibility(&self, node: &Node, source: &str) -> Option<String> {
+        let name = match node.kind() {
+            "function_definition" | "class_definition" => node_text(&node.child_by_field_name("name")?, source),
+            "assignment" => {
+                let left = node.child_by_field_name("left")?;
+                node_text(&left.child_by_field_name("attribute").unwrap_or(left), source)
+            }
+            _ => return None,
+        };
+        let statement = node.parent().filter(|parent| matches!(parent.kind(), "decorated_definition" | "expression_statement")).unwrap_or(*node);
+        let container = statement.parent()?;
+        let public = if container.kind() == "module" {
+            match exports(&container, source) {
+                Some(exports) => exports.iter().any(|export| export == name),
+                None => !name.starts_with('_'),
+            }
+        } else if in_class(node) || node.kind() == "assignment" {
+            !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"))
+        } else {
+            return None;
+        };
+        Some(if public { "public" } else { "private" }.to_string())
+    }
+
+    /// Decorators as written, without `@`: `app.get("/users")`, `dataclass(frozen=True)`.
+    fn symbol_attributes(&self, node: &Node, source: &str) -> Vec<String> {
+        decorators(node).iter().map(|decorator| one_line(node_text(decorator, source))).collect()
+    }
+
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        if node.kind() != "function_definition" {
+            return None;
+        }
+        let mut modifiers: Vec<String> = keywords(node).into_iter().filter(|keyword| *keyword == "async").map(str::to_string).collect();
+        for decorator in decorator_names(node, source) {
+            if let Some((_, modifier)) = MODIFIER_DECORATORS.iter().find(|(name, _)| decorator.rsplit('.').next() == Some(*name)) {
+                modifiers.push(modifier.to_string());
+            }
+        }
+        if yields(node, "yield", &["function_definition", "lambda", "class_definition"]) {
+            modifiers.push("generator".to_string());
+        }
+
+        let mut parameters = Vec::new();
+        let mut keyword_only = false;
+        let takes_receiver = in_class(node) && !modifiers.iter().any(|modifier| modifier == "static");
+        for param in node.child_by_field_name("parameters").iter().flat_map(|params| named_children(params)) {
+            let positional = if keyword_only { ParameterKind::KeywordOnly } else { ParameterKind::Positional };
+            let default = field_text(&param, "value", source);
+            let type_name = field_text(&param, "type", source);
+            // `typed_parameter` wraps the name, which may be a splat.
+            let target = match param.kind() {
+                "typed_parameter" => param.named_child(0).filter(|name| name.kind() != "type").unwrap_or(param),
+                "default_parameter" | "typed_default_parameter" => param.child_by_field_name("name").unwrap_or(param),
+                _ => param,
+            };
+            let parameter = match target.kind() {
+                "keyword_separator" => {
+                    keyword_only = true;
+                    continue;
+                }
+                "positional_separator" | "comment" => continue,
+                "list_splat_pattern" => {
+                    keyword_only = true;
+                    Parameter::new(splat_name(&target, source), ParameterKind::Variadic)
+                }
+                "dictionary_splat_pattern" => Parameter::new(splat_name(&target, source), ParameterKind::Keywords),
+                _ if parameters.is_empty() && takes_receiver => Parameter::new(node_text(&target, source), ParameterKind::Receiver),
+                _ => Parameter::new(node_text(&target, source), positional),
+            };
+            parameters.push(parameter.typed(type_name).with_default(default));
+        }
+        Some(Signature {
+            parameters,
+            return_type: field_text(node, "return_type", source),
+            type_parameters: list_items(node, "type_parameters", source),
+            modifiers,
+        })
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &["call"]
+    }
+
+    fn supertypes(&self, node: &Node, source: &str) -> Vec<(&'static str, String)> {
+        let Some(bases) = node.child_by_field_name("superclasses") else {
+            return Vec::new();
+        };
+        (0..bases.named_child_count())
+            .filter_map(|i| bases.named_child(i))
+            // Skip `metaclass=...` and other keyword arguments.
+            .filter(|base| matches!(base.kind(), "identifier" | "attribute"))
+            .map(|base| ("inherits", node_text(&base, source).to_string()))
+            .collect()
+    }
+
+    fn pattern_confidence(&self, pattern: &str, code: &str) -> f32 {
+        match pattern {
+            "Singleton" => {
+                let has_private_ctor = code.contains("def __init__(self)") && code.contains("raise Exception") && code.contains("_instance = None");
+                let has_get_instance = code.contains("@staticmethod") && code.contains("def get_instance(");
+                if has_private_ctor && has_get_instance { 0.9 } else if has_get_instance { 0.5 } else { 0.0 }
+            }
+            "Factory" if code.contains("def create_") && code.contains("return") => 0.7,
+            "Observer" => {
+                let has_subscribe = code.contains("def subscribe") || code.contains("def attach");
+                let has_notify = code.contains("def notify") || code.contains("def update");
+                if has_subscribe && has_notify { 0.8 } else { 0.0 }
+            }
+            "Builder" if code.contains("return self") && code.contains("def build") => 0.7,
+            "Repository" if code.contains("def find_") && code.contains("def save_") => 0.7,
+            "MVC" => super::mvc_confidence(code),
+            "Dependency Injection" if code.contains("def __init__(self") && code.contains("service") => 0.7,
+            "Decorator" if code.contains("@") && code.contains("def ") => 0.7,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Decorator expressions of a definition, outermost first.
+fn decorators<'t>(node: &Node<'t>) -> Vec<Node<'t>> {
+    let Some(decorated) = node.parent().filter(|parent| parent.kind() == "decorated_definition") else {
+        return Vec::new();
+    };
+    named_children(&decorated).filter(|decorator| decorator.kind() == "decorator").filter_map(|decorator| decorator.named_child(0)).collect()
+}
+
+/// Decorators of a definition, without `@` and call arguments: `app.route`.
+pub(super) fn decorator_names(node: &Node, source: &str) -> Vec<String> {
+    decorators(node).iter().map(|decorator| callee_text(decorator, source)).collect()
+}
+
+/// A decorator without its call arguments.
+fn callee_text(decorator: &Node, source: &str) -> String {
+    let callee = if decorator.kind() == "call" { decorator.child_by_field_name("function").unwrap_or(*decorator) } else { *decorator };
+    node_text(&callee, source).to_string()
+}
+
+fn last_segment(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// A call of a route method on an app or router, such as `app.get("/users")`.
+fn is_route(decorator: &Node, source: &str) -> bool {
+    decorator.kind() == "call"
+        && decorator
+            .child_by_field_name("function")
+            .filter(|function| function.kind() == "attribute")
+            .and_then(|function| function.child_by_field_name("attribute"))
+            .is_some_and(|method| ROUTE_DECORATORS.contains(&node_text(&method, source)))
+}
+
+/// Whether the annotated attributes of `class` are fields.
+fn has_fields(class: &Node, source: &str) -> bool {
+    decorator_names(class, source).iter().any(|name| DATACLASS_DECORATORS.contains(&last_segment(name)))
+        || class.child_by_field_name("superclasses").is_some_and(|bases| named_children(&bases).any(|base| MODEL_BASES.contains(&last_segment(node_text(&base, source)))))
+}
+
+/// Names listed in the module's `__all__`, assigned or extended with `+=`;
+/// `None` when it has none.
+fn exports(module: &Node, source: &str) -> Option<Vec<String>> {
+    let mut exports: Option<Vec<String>> = None;
+    for statement in named_children(module).filter(|statement| statement.kind() == "expression_statement") {
+        let Some(assignment) = statement.named_child(0).filter(|child| matches!(child.kind(), "assignment" | "augmented_assignment")) else {
+            continue;
+        };
+        if assignment.child_by_field_name("left").is_none_or(|left| node_text(&left, source) != "__all__") {
+            continue;
+        }
+        let Some(names) = assignment.child_by_field_name("right").filter(|right| matches!(right.kind(), "list" | "tuple")) else {
+            continue;
+        };
+        let listed = named_children(&names).filter(|name| name.kind() == "string").map(|name| string_value(&name, source));
+        let exports = exports.get_or_insert_with(Vec::new);
+        if assignment.kind() == "assignment" {
+            exports.clear();
+        }
+        exports.extend(listed);
+    }
+    exports
+}
+
+/// Contents of a string literal without its prefix and quotes.
+fn string_value(string: &Node, source: &str) -> String {
+    node_text(string, source).trim_start_matches(|c: char| c.is_ascii_alphabetic()).trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// Whether a function is defined directly in a class body.
+fn in_class(node: &Node) -> bool {
+    let definition = node.parent().filter(|parent| parent.kind() == "decorated_definition").unwrap_or(*node);
+    definition.parent().filter(|block| block.kind() == "block").and_then(|block| block.parent()).is_some_and(|class| class.kind() == "class_definition")
+}
+
+/// `args` for `*args`.
+fn splat_name(splat: &Node, source: &str) -> String {
+    splat.named_child(0).map_or_else(|| node_text(splat, source).trim_start_matches('*').to_string(), |name| node_text(&name, source).to_string())
+}
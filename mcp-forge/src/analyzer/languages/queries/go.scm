@@ -0,0 +1,57 @@
+; Go definitions. Comments directly above a declaration are its documentation;
+; methods are qualified by their receiver type.
+
+(package_clause
+  (package_identifier) @name) @definition.package
+
+(
+  (comment)* @doc
+  .
+  (function_declaration
+    name: (identifier) @name) @definition.function
+)
+
+(
+  (comment)* @doc
+  .
+  (method_declaration
+    receiver: (parameter_list
+      (parameter_declaration
+        type: [
+          (type_identifier) @scope
+          (pointer_type (type_identifier) @scope)
+          (generic_type type: (type_identifier) @scope)
+          (pointer_type (generic_type type: (type_identifier) @scope))
+        ]))
+    name: (field_identifier) @name) @definition.method
+)
+
+(
+  (comment)* @doc
+  .
+  (type_declaration
+    (type_spec
+      name: (type_identifier) @name
+      type: (struct_type)) @definition.struct)
+)
+
+(
+  (comment)* @doc
+  .
+  (type_declaration
+    (type_spec
+      name: (type_identifier) @name
+      type: (interface_type)) @definition.interface)
+)
+
+(type_spec
+  name: (type_identifier) @name) @definition.type
+
+(type_alias
+  name: (type_identifier) @name) @definition.type
+
+(const_spec
+  name: (identifier) @name) @definition.constant
+
+(import_spec
+  path: (_) @name) @definition.import
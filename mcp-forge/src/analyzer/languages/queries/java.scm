@@ -0,0 +1,65 @@
+; Java definitions. The Javadoc comment above a declaration is its documentation.
+
+(
+  (block_comment)? @doc
+  .
+  (class_declaration
+    name: (identifier) @name) @definition.class
+)
+
+(
+  (block_comment)? @doc
+  .
+  (interface_declaration
+    name: (identifier) @name) @definition.interface
+)
+
+(annotation_type_declaration
+  name: (identifier) @name) @definition.annotation
+
+(
+  (block_comment)? @doc
+  .
+  (enum_declaration
+    name: (identifier) @name) @definition.enum
+)
+
+(
+  (block_comment)? @doc
+  .
+  (record_declaration
+    name: (identifier) @name) @definition.record
+)
+
+(
+  (block_comment)? @doc
+  .
+  (method_declaration
+    name: (identifier) @name) @definition.method
+)
+
+(
+  (block_comment)? @doc
+  .
+  (constructor_declaration
+    name: (identifier) @name) @definition.constructor
+)
+
+(
+  (block_comment)? @doc
+  .
+  [
+    (field_declaration
+      declarator: (variable_declarator
+        name: (identifier) @name))
+    (constant_declaration
+      declarator: (variable_declarator
+        name: (identifier) @name))
+  ] @definition.field
+)
+
+(package_declaration
+  [(identifier) (scoped_identifier)] @name) @definition.package
+
+; Named by `Java::symbol_name`, which keeps a trailing `.*`.
+(import_declaration) @definition.import
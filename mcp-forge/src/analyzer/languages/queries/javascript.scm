@@ -0,0 +1,83 @@
+; JavaScript definitions, also used for TypeScript and TSX. Comments directly
+; above a declaration are its documentation.
+
+(
+  (comment)* @doc
+  .
+  (class_declaration
+    name: (_) @name) @definition.class
+)
+
+(
+  (comment)* @doc
+  .
+  (export_statement
+    declaration: (class_declaration
+      name: (_) @name) @definition.class)
+)
+
+(
+  (comment)* @doc
+  .
+  (function_declaration
+    name: (identifier) @name) @definition.function
+)
+
+(
+  (comment)* @doc
+  .
+  (export_statement
+    declaration: (function_declaration
+      name: (identifier) @name) @definition.function)
+)
+
+(
+  (comment)* @doc
+  .
+  (method_definition
+    name: (property_identifier) @name) @definition.function
+)
+
+(function_expression
+  name: (identifier) @name) @definition.function
+
+; Functions assigned to a variable take its name.
+(
+  (comment)* @doc
+  .
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @name
+        value: [(arrow_function) (function_expression)] @definition.function))
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @name
+        value: [(arrow_function) (function_expression)] @definition.function))
+    (export_statement
+      declaration: (lexical_declaration
+        (variable_declarator
+          name: (identifier) @name
+          value: [(arrow_function) (function_expression)] @definition.function)))
+  ]
+)
+
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)] @definition.function)
+
+; Module-level values.
+(program
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @name
+        value: [(number) (string) (template_string) (object) (array) (call_expression) (new_expression) (identifier) (member_expression) (true) (false) (null)]) @definition.variable)
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @name
+        value: [(number) (string) (template_string) (object) (array) (call_expression) (new_expression) (identifier) (member_expression) (true) (false) (null)]) @definition.variable)
+  ])
+
+(import_statement
+  source: (string) @name) @definition.import
@@ -0,0 +1,52 @@
+; Python definitions. Docstrings are the first statement of the body.
+
+(class_definition
+  name: (identifier) @name) @definition.class
+
+(class_definition
+  name: (identifier) @name
+  body: (block . (expression_statement (string) @doc))) @definition.class
+
+(function_definition
+  name: (identifier) @name) @definition.function
+
+(function_definition
+  name: (identifier) @name
+  body: (block . (expression_statement (string) @doc))) @definition.function
+
+(import_statement
+  name: [
+    (dotted_name) @name
+    (aliased_import name: (dotted_name) @name)
+  ]) @definition.import
+
+(import_from_statement
+  module_name: (_) @name) @definition.import
+
+; Class attributes, and dataclass or pydantic fields: assignments and
+; annotations in the class body.
+(class_definition
+  body: (block
+    (expression_statement
+      (assignment
+        left: (identifier) @name) @definition.attribute)))
+
+; Instance attributes assigned in `__init__`; `Python::hoisted_kinds` makes
+; them members of the class.
+(function_definition
+  name: (identifier) @_init
+  body: (block
+    (expression_statement
+      (assignment
+        left: (attribute
+          object: (identifier) @_self
+          attribute: (identifier) @name)) @definition.attribute))
+  (#eq? @_init "__init__")
+  (#eq? @_self "self"))
+
+; Module-level constants, named in upper case.
+(module
+  (expression_statement
+    (assignment
+      left: (identifier) @name) @definition.constant)
+  (#match? @name "^_*[A-Z][A-Z0-9_]*$"))
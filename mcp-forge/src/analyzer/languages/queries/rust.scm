@@ -0,0 +1,154 @@
+; Rust definitions. `///` comments above an item, or above its attributes, and
+; `//!` comments opening a `mod` block are its documentation; `Rust::doc_comment`
+; drops other comments captured with them.
+; Items of an `impl` block are named after its type (`Server.run`) by
+; `Rust::symbol_name`, so they become members of the type.
+
+(
+  (line_comment)* @doc
+  .
+  (function_item
+    name: (identifier) @name) @definition.function
+)
+
+(
+  (line_comment)* @doc
+  .
+  (function_signature_item
+    name: (identifier) @name) @definition.function
+)
+
+(
+  (line_comment)* @doc
+  .
+  (struct_item
+    name: (type_identifier) @name) @definition.struct
+)
+
+(
+  (line_comment)* @doc
+  .
+  (union_item
+    name: (type_identifier) @name) @definition.union
+)
+
+(
+  (line_comment)* @doc
+  .
+  (enum_item
+    name: (type_identifier) @name) @definition.enum
+)
+
+(
+  (line_comment)* @doc
+  .
+  (enum_variant
+    name: (identifier) @name) @definition.variant
+)
+
+(
+  (line_comment)* @doc
+  .
+  (trait_item
+    name: (type_identifier) @name) @definition.trait
+)
+
+(
+  (line_comment)* @doc
+  .
+  (impl_item) @definition.impl
+)
+
+(
+  (line_comment)* @doc
+  .
+  (mod_item
+    name: (identifier) @name) @definition.module
+)
+
+(mod_item
+  name: (identifier) @name
+  body: (declaration_list
+    .
+    (line_comment)+ @doc)) @definition.module
+
+(
+  (line_comment)* @doc
+  .
+  (macro_definition
+    name: (identifier) @name) @definition.macro
+)
+
+(
+  (line_comment)* @doc
+  .
+  (const_item
+    name: (identifier) @name) @definition.constant
+)
+
+(
+  (line_comment)* @doc
+  .
+  (static_item
+    name: (identifier) @name) @definition.static
+)
+
+(
+  (line_comment)* @doc
+  .
+  [
+    (type_item
+      name: (type_identifier) @name)
+    (associated_type
+      name: (type_identifier) @name)
+  ] @definition.type
+)
+
+(let_declaration
+  pattern: (identifier) @name) @definition.variable
+
+(
+  (line_comment)* @doc
+  .
+  (field_declaration
+    name: (field_identifier) @name) @definition.field
+)
+
+; Items with attributes between their documentation and themselves.
+(
+  (line_comment)* @doc
+  .
+  (attribute_item)+
+  .
+  [
+    (function_item
+      name: (identifier) @name) @definition.function
+    (function_signature_item
+      name: (identifier) @name) @definition.function
+    (struct_item
+      name: (type_identifier) @name) @definition.struct
+    (union_item
+      name: (type_identifier) @name) @definition.union
+    (enum_item
+      name: (type_identifier) @name) @definition.enum
+    (enum_variant
+      name: (identifier) @name) @definition.variant
+    (trait_item
+      name: (type_identifier) @name) @definition.trait
+    (impl_item) @definition.impl
+    (mod_item
+      name: (identifier) @name) @definition.module
+    (macro_definition
+      name: (identifier) @name) @definition.macro
+    (const_item
+      name: (identifier) @name) @definition.constant
+    (static_item
+      name: (identifier) @name) @definition.static
+    (type_item
+      name: (type_identifier) @name) @definition.type
+    (associated_type
+      name: (type_identifier) @name) @definition.type
+    (field_declaration
+      name: (field_identifier) @name) @definition.field
+  ]
+)
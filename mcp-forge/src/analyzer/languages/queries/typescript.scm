@@ -0,0 +1,29 @@
+; TypeScript additions to the JavaScript query.
+
+(
+  (comment)* @doc
+  .
+  (abstract_class_declaration
+    name: (type_identifier) @name) @definition.class
+)
+
+(
+  (comment)* @doc
+  .
+  (interface_declaration
+    name: (type_identifier) @name) @definition.interface
+)
+
+(
+  (comment)* @doc
+  .
+  (export_statement
+    declaration: (interface_declaration
+      name: (type_identifier) @name) @definition.interface)
+)
+
+(type_alias_declaration
+  name: (type_identifier) @name) @definition.interface
+
+(enum_declaration
+  name: (identifier) @name) @definition.enum
@@ -0,0 +1,211 @@
+//! Rust: items with their visibility and attributes, enum variants, trait and
+//! `impl` items, `macro_rules!`, `let` bindings and struct fields.
+//!
+//! Items of `impl Type` and `impl Trait for Type` blocks are members of
+//! `Type`; the block itself is a symbol named after its header
+//! (`impl Display for Server`), and a trait implementation becomes an
+//! `implements` edge from the type to the trait.
+
+use tree_sitter::{Language, Node};
+
+use super::{encloses, extract_relationships, node_text, path_segments, LanguageSupport, Project};
+use crate::analyzer::signature::{field_text, header, keywords, list_items, named_children, one_line, Parameter, ParameterKind, Signature};
+use crate::analyzer::tags::Tag;
+use crate::knowledge::relationships::Relationship;
+
+/// Item kinds that can appear in an `impl` block.
+const IMPL_ITEMS: &[&str] = &["function_item", "function_signature_item", "const_item", "type_item"];
+
+pub struct Rust;
+
+impl LanguageSupport for Rust {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rs"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_rust::language()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        include_str!("queries/rust.scm")
+    }
+
+    /// Functions in an `impl` or trait are methods.
+    fn refine_kind(&self, node: &Node, _name: &str, kind: &str, _source: &str) -> Option<&'static str> {
+        (kind == "function" && container(node).is_some()).then_some("method")
+    }
+
+    /// `impl` blocks are named after their header, their items after the type.
+    fn symbol_name(&self, node: &Node, source: &str) -> Option<String> {
+        if node.kind() == "impl_item" {
+            let ty = one_line(node_text(&node.child_by_field_name("type")?, source));
+            return Some(match node.child_by_field_name("trait") {
+                Some(tr) => format!("impl {} for {}", one_line(node_text(&tr, source)), ty),
+                None => format!("impl {}", ty),
+            });
+        }
+        if !IMPL_ITEMS.contains(&node.kind()) {
+            return None;
+        }
+        let block = container(node).filter(|block| block.kind() == "impl_item")?;
+        let ty = type_name(&block.child_by_field_name("type")?, source);
+        Some(format!("{}.{}", ty, node_text(&node.child_by_field_name("name")?, source)))
+    }
+
+    /// `///` above an item, or `//!` at the start of a `mod` block.
+    fn doc_comment(&self, comment: &Node, definition: &Node, source: &str) -> bool {
+        let text = node_text(comment, source);
+        if encloses(definition, comment) {
+            text.starts_with("//!") || text.starts_with("/*!")
+        } else {
+            (text.starts_with("///") && !text.starts_with("////")) || (text.starts_with("/**") && !text.starts_with("/***"))
+        }
+    }
+
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        if !matches!(node.kind(), "function_item" | "function_signature_item") {
+            return None;
+        }
+        header(node, node.start_byte(), source).map(|header| header.trim_end_matches(';').to_string())
+    }
+
+    /// Parameters, return type, generics (lifetimes included) and the
+    /// `const`, `async`, `unsafe` and `extern` qualifiers.
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        if !matches!(node.kind(), "function_item" | "function_signature_item") {
+            return None;
+        }
+        let modifiers = named_children(node)
+            .filter(|child| child.kind() == "function_modifiers")
+            .flat_map(|qualifiers| {
+                let mut modifiers: Vec<String> = keywords(&qualifiers).into_iter().map(str::to_string).collect();
+                modifiers.extend(named_children(&qualifiers).map(|abi| one_line(node_text(&abi, source))));
+                modifiers
+            })
+            .collect();
+        let parameters = node
+            .child_by_field_name("parameters")
+            .iter()
+            .flat_map(|params| named_children(params))
+            .filter_map(|param| match param.kind() {
+                "self_parameter" => Some(Parameter::new(one_line(node_text(&param, source)), ParameterKind::Receiver)),
+                "parameter" => {
+                    let name = param.child_by_field_name("pattern").map_or(String::new(), |pattern| node_text(&pattern, source).to_string());
+                    Some(Parameter::new(name, ParameterKind::Positional).typed(field_text(&param, "type", source)))
+                }
+                "variadic_parameter" => Some(Parameter::new("...", ParameterKind::Variadic)),
+                "attribute_item" | "line_comment" | "block_comment" => None,
+                // A bare type, as in an edition 2015 trait method.
+                _ => Some(Parameter::new("", ParameterKind::Positional).typed(Some(one_line(node_text(&param, source))))),
+            })
+            .collect();
+        Some(Signature {
+            parameters,
+            return_type: field_text(node, "return_type", source),
+            type_parameters: list_items(node, "type_parameters", source),
+            modifiers,
+        })
+    }
+
+    fn symbol_visibility(&self, node: &Node, source: &str) -> Option<String> {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .find(|child| child.kind() == "visibility_modifier")
+            .map(|visibility| one_line(node_text(&visibility, source)))
+    }
+
+    /// Outer attributes above the item, comments in between skipped.
+    fn symbol_attributes(&self, node: &Node, source: &str) -> Vec<String> {
+        let mut attributes = Vec::new();
+        let mut sibling = node.prev_named_sibling();
+        while let Some(item) = sibling {
+            match item.kind() {
+                "attribute_item" => {
+                    if let Some(attribute) = item.named_child(0) {
+                        attributes.push(one_line(node_text(&attribute, source)));
+                    }
+                }
+                "line_comment" | "block_comment" => {}
+                _ => break,
+            }
+            sibling = item.prev_named_sibling();
+        }
+        attributes.reverse();
+        attributes
+    }
+
+    fn transparent_kinds(&self) -> &'static [&'static str] {
+        &["impl"]
+    }
+
+    /// `crate::` and the module path below `src/`: `src/lib.rs` is the crate
+    /// root and `mod.rs` names its directory.
+    fn module_path(&self, file_path: &str) -> String {
+        let segments = path_segments(file_path);
+        let below_src = segments.iter().rposition(|segment| *segment == "src").map_or(&segments[..], |src| &segments[src + 1..]);
+        let mut path = vec!["crate"];
+        path.extend(below_src.iter().copied().filter(|segment| !matches!(*segment, "lib" | "main" | "mod")));
+        path.join("::")
+    }
+
+    fn scope_separator(&self) -> &'static str {
+        "::"
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &["call_expression"]
+    }
+
+    /// Supertraits of a trait.
+    fn supertypes(&self, node: &Node, source: &str) -> Vec<(&'static str, String)> {
+        let Some(bounds) = node.child_by_field_name("bounds").filter(|_| node.kind() == "trait_item") else {
+            return Vec::new();
+        };
+        (0..bounds.named_child_count())
+            .filter_map(|i| bounds.named_child(i))
+            .filter(|bound| matches!(bound.kind(), "type_identifier" | "generic_type" | "scoped_type_identifier"))
+            .map(|bound| ("inherits", type_name(&bound, source)))
+            .collect()
+    }
+
+    /// Default relationships, plus an `implements` edge from the type of each
+    /// `impl Trait for Type` block to the trait.
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], _project: &Project) -> Vec<Relationship> {
+        let mut relationships = extract_relationships(self, root, source, file_path, tags);
+        let path_of = |name: String| tags.iter().find(|tag| tag.definition && tag.name == name).map_or(name, |tag| tag.path.clone());
+        for tag in tags.iter().filter(|tag| tag.definition && tag.kind == "impl") {
+            let (Some(tr), Some(ty)) = (tag.node.child_by_field_name("trait"), tag.node.child_by_field_name("type")) else {
+                continue;
+            };
+            relationships.push(Relationship {
+                from: format!("{}::{}", file_path, path_of(type_name(&ty, source))),
+                to: format!("{}::{}", file_path, path_of(type_name(&tr, source))),
+                kind: "implements".to_string(),
+                strength: 1.0,
+            });
+        }
+        relationships
+    }
+}
+
+/// The `impl` or trait whose body declares `node`.
+fn container<'t>(node: &Node<'t>) -> Option<Node<'t>> {
+    node.parent()
+        .filter(|list| list.kind() == "declaration_list")
+        .and_then(|list| list.parent())
+        .filter(|block| matches!(block.kind(), "impl_item" | "trait_item"))
+}
+
+/// The named type under references, generics and paths: `Server` for `&mut server::Server<T>`.
+fn type_name(ty: &Node, source: &str) -> String {
+    match ty.kind() {
+        "generic_type" | "reference_type" | "pointer_type" => ty.child_by_field_name("type").map_or_else(|| node_text(ty, source).to_string(), |inner| type_name(&inner, source)),
+        "scoped_type_identifier" => ty.child_by_field_name("name").map_or_else(|| node_text(ty, source).to_string(), |name| node_text(&name, source).to_string()),
+        _ => one_line(node_text(ty, source)),
+    }
+}
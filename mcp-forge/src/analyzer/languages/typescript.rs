@@ -0,0 +1,117 @@
+//! TypeScript and TSX: the JavaScript rules plus interfaces, type aliases and enums.
+
+use tree_sitter::{Language, Node};
+
+use super::{javascript, LanguageSupport, Project};
+use crate::analyzer::signature::Signature;
+use crate::analyzer::tags::Tag;
+use crate::knowledge::relationships::Relationship;
+
+pub struct TypeScript;
+
+impl LanguageSupport for TypeScript {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ts", "mts", "cts"]
+    }
+
+    fn interpreters(&self) -> &'static [&'static str] {
+        &["ts-node"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_typescript::language_typescript()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        concat!(include_str!("queries/javascript.scm"), include_str!("queries/typescript.scm"))
+    }
+
+    fn refine_kind(&self, node: &Node, name: &str, kind: &str, source: &str) -> Option<&'static str> {
+        javascript::refine_kind(node, name, kind, source)
+    }
+
+    fn doc_comment(&self, comment: &Node, _definition: &Node, source: &str) -> bool {
+        javascript::doc_comment(comment, source)
+    }
+
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        javascript::signature(node, source)
+    }
+
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        javascript::callable(node, source)
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        &["call_expression"]
+    }
+
+    fn supertypes(&self, node: &Node, source: &str) -> Vec<(&'static str, String)> {
+        javascript::heritage(node, source)
+    }
+
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], _project: &Project) -> Vec<Relationship> {
+        javascript::relationships(self, root, source, file_path, tags)
+    }
+
+    fn pattern_confidence(&self, pattern: &str, code: &str) -> f32 {
+        javascript::pattern_confidence(pattern, code)
+    }
+}
+
+/// TypeScript with JSX, parsed with the TSX grammar.
+pub struct Tsx;
+
+impl LanguageSupport for Tsx {
+    fn name(&self) -> &'static str {
+        "tsx"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tsx"]
+    }
+
+    fn grammar(&self) -> Language {
+        tree_sitter_typescript::language_tsx()
+    }
+
+    fn tags_query(&self) -> &'static str {
+        TypeScript.tags_query()
+    }
+
+    fn refine_kind(&self, node: &Node, name: &str, kind: &str, source: &str) -> Option<&'static str> {
+        TypeScript.refine_kind(node, name, kind, source)
+    }
+
+    fn doc_comment(&self, comment: &Node, definition: &Node, source: &str) -> bool {
+        TypeScript.doc_comment(comment, definition, source)
+    }
+
+    fn symbol_signature(&self, node: &Node, source: &str) -> Option<String> {
+        TypeScript.symbol_signature(node, source)
+    }
+
+    fn callable(&self, node: &Node, source: &str) -> Option<Signature> {
+        TypeScript.callable(node, source)
+    }
+
+    fn call_kinds(&self) -> &'static [&'static str] {
+        TypeScript.call_kinds()
+    }
+
+    fn supertypes(&self, node: &Node, source: &str) -> Vec<(&'static str, String)> {
+        TypeScript.supertypes(node, source)
+    }
+
+    fn relationships(&self, root: &Node, source: &str, file_path: &str, tags: &[Tag], _project: &Project) -> Vec<Relationship> {
+        javascript::relationships(self, root, source, file_path, tags)
+    }
+
+    fn pattern_confidence(&self, pattern: &str, code: &str) -> f32 {
+        TypeScript.pattern_confidence(pattern, code)
+    }
+}
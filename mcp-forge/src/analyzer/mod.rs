@@ -1,28 +1,104 @@
-mod.rs
-
+pub mod content;
+pub mod diagnostics;
+pub mod docs;
+pub mod embedded;
+pub mod languages;
 pub mod parser;
 pub mod symbols;
 pub mod patterns;
+pub mod report;
+pub mod signature;
+pub mod tags;
 
 use walkdir::WalkDir;
-use ignore::gitignore::GitignoreBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::{debug, warn};
 use std::path::{Path, PathBuf};
 
+use crate::config::IndexingConfig;
+use content::SkipReason;
+use parser::CodeParser;
+
+/// Files found under a source root.
+#[derive(Debug, Default)]
+pub struct Discovery {
+	pub files: Vec<PathBuf>,
+	/// Source files left out before being read, with the reason.
+	pub skipped: Vec<(PathBuf, SkipReason)>,
+}
+
 /// Discover source files, respecting .gitignore
 pub fn discover_files(source: &Path) -> Vec<PathBuf> {
-	let mut files = Vec::new();
-	let mut builder = GitignoreBuilder::new(source);
-	builder.add(".gitignore");
-	let gitignore = builder.build().unwrap();
+	discover_files_with(source, &IndexingConfig::default())
+}
+
+/// Discover source files, respecting .gitignore and the indexing config:
+/// exclude patterns, the size limit and the enabled languages.
+pub fn discover_files_with(source: &Path, indexing: &IndexingConfig) -> Vec<PathBuf> {
+	discover(source, indexing).files
+}
+
+/// The `.gitignore` and `indexing.exclude_patterns` rules of a source root,
+/// shared by [`discover`] and the watcher so both leave out the same files.
+pub struct IgnoreMatcher {
+	gitignore: Gitignore,
+}
+
+impl IgnoreMatcher {
+	pub fn new(source: &Path, indexing: &IndexingConfig) -> Self {
+		let mut builder = GitignoreBuilder::new(source);
+		builder.add(source.join(".gitignore"));
+		for pattern in &indexing.exclude_patterns {
+			if let Err(e) = builder.add_line(None, pattern) {
+				warn!("Ignoring invalid exclude pattern {:?}: {}", pattern, e);
+			}
+		}
+		Self { gitignore: builder.build().unwrap_or_else(|_| Gitignore::empty()) }
+	}
 
-	for entry in WalkDir::new(source).into_iter().filter_map(|e| e.ok()) {
+	fn matched(&self, path: &Path, is_dir: bool) -> bool {
+		self.gitignore.matched(path, is_dir).is_ignore()
+	}
+
+	/// Whether [`discover`] leaves out the file at `path`: it is outside the
+	/// source root, or it or a directory above it is ignored or is `.git`.
+	pub fn is_ignored(&self, path: &Path) -> bool {
+		let Ok(relative) = path.strip_prefix(self.gitignore.path()) else {
+			return true;
+		};
+		relative.components().any(|component| component.as_os_str() == ".git")
+			|| self.gitignore.matched_path_or_any_parents(relative, false).is_ignore()
+	}
+}
+
+/// Like [`discover_files_with`], keeping the source files over the size limit.
+pub fn discover(source: &Path, indexing: &IndexingConfig) -> Discovery {
+	let mut discovery = Discovery::default();
+	let ignore = IgnoreMatcher::new(source, indexing);
+
+	// Sorted so every run discovers, and reports, files in the same order.
+	let walker = WalkDir::new(source).sort_by_file_name().into_iter().filter_entry(|entry| {
+		entry.depth() == 0
+			|| !(entry.file_type().is_dir() && (entry.file_name() == ".git" || ignore.matched(entry.path(), true)))
+	});
+	for entry in walker.filter_map(|e| e.ok()) {
 		let path = entry.path();
-		if path.is_file() {
-			let is_ignored = gitignore.matched(path, false).is_ignore();
-			if !is_ignored {
-				files.push(path.to_path_buf());
+		if !entry.file_type().is_file() || ignore.matched(path, false) {
+			continue;
+		}
+		if let Some(language) = CodeParser::language_for(path) {
+			if !indexing.includes_language(language) {
+				continue;
+			}
+		}
+		if let Some(reason) = entry.metadata().ok().and_then(|m| content::check_size(m.len(), indexing)) {
+			debug!("Skipping {:?}: {}", path, reason);
+			if CodeParser::language_for(path).is_some() || embedded::is_host(path) {
+				discovery.skipped.push((path.to_path_buf(), reason));
 			}
+			continue;
 		}
+		discovery.files.push(path.to_path_buf());
 	}
-	files
-}
\ No newline at end of file
+	discovery
+}
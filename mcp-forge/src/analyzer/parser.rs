@@ -3,58 +3,273 @@ pub trait CodeParse {
     fn parse_file(&mut self, path: &std::path::Path) -> crate::error::McpResult<tree_sitter::Tree>;
 }
 
-use std::path::Path;
-use tree_sitter::{Language, Parser, Tree};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use tree_sitter::{Parser, Tree};
+use crate::analyzer::content::{self, Encoding, SkipReason, SourceText};
+use crate::analyzer::diagnostics::ParseDiagnostics;
+use crate::analyzer::embedded::{self, HostRegion};
+use crate::analyzer::languages::registry;
+use crate::config::IndexingConfig;
 use crate::error::{McpError, McpResult};
 
 pub struct CodeParser {
-    parsers: std::collections::HashMap<String, Parser>,
+    /// One parser per language, created on first use.
+    parsers: HashMap<&'static str, Parser>,
+    /// Languages this parser accepts; empty means every registered language.
+    enabled: Vec<String>,
+    /// Parse time limit per file in milliseconds; 0 means none.
+    timeout_ms: u64,
+}
+
+/// A source file read and parsed by a [`ParserPool`].
+pub struct ParsedFile {
+    pub path: PathBuf,
+    pub language: &'static str,
+    pub source: String,
+    /// `None` when the parse timed out.
+    pub tree: Option<Tree>,
+    /// SHA-256 of `source`, hex-encoded.
+    pub hash: String,
+    pub diagnostics: ParseDiagnostics,
+    /// Set for code embedded in `path`; `source`, `tree` and `hash` are the region's.
+    pub region: Option<HostRegion>,
+    /// Encoding `path` was decoded from.
+    pub encoding: Encoding,
+}
+
+impl ParsedFile {
+    pub fn file_path(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+}
+
+/// Parsers shared by the threads of a rayon pool.
+///
+/// tree-sitter parsers are not `Sync`, so each rayon worker gets its own
+/// [`CodeParser`], picked by thread index; the slot is only contended when
+/// the pool is used from more threads than it was created for.
+pub struct ParserPool {
+    parsers: Vec<Mutex<CodeParser>>,
+    indexing: IndexingConfig,
+}
+
+impl ParserPool {
+    /// One parser per thread of the current rayon pool, plus one for callers outside it.
+    pub fn new(indexing: &IndexingConfig) -> Self {
+        let parsers = (0..=rayon::current_num_threads()).map(|_| Mutex::new(CodeParser::for_indexing(indexing))).collect();
+        Self { parsers, indexing: indexing.clone() }
+    }
+
+    /// Run `f` with the calling thread's parser.
+    pub fn with<R>(&self, f: impl FnOnce(&mut CodeParser) -> R) -> R {
+        let slot = rayon::current_thread_index().map_or(0, |index| index + 1) % self.parsers.len();
+        let mut parser = self.parsers[slot].lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut parser)
+    }
+
+    /// Read `path` for indexing, or say why it is left out.
+    pub fn read(&self, path: &Path) -> Result<SourceText, SkipReason> {
+        content::read_source(path, &self.indexing)
+    }
+
+    pub fn parse_file(&self, path: &Path) -> McpResult<ParsedFile> {
+        let language = CodeParser::language_for(path)
+            .ok_or(McpError::Transport("Unsupported file type".to_string()))?;
+        let source = self.read(path).map_err(|reason| McpError::Transport(reason.to_string()))?;
+        self.parse_source(path, language, source)
+    }
+
+    /// Parse each code region of a Markdown, notebook or component file in
+    /// an enabled language. Diagnostic lines are mapped to the host file.
+    pub fn parse_embedded(&self, path: &Path) -> McpResult<Vec<ParsedFile>> {
+        self.parse_path(path).map_err(|reason| McpError::Transport(reason.to_string()))
+    }
+
+    /// Parse `source`, already read from `path`, as `language`.
+    pub fn parse_source(&self, path: &Path, language: &'static str, source: SourceText) -> McpResult<ParsedFile> {
+        let mut parsed = self.parse_text(path, language, source.text, None)?;
+        parsed.encoding = source.encoding;
+        Ok(parsed)
+    }
+
+    /// Parse a source file, or each code region of a file with embedded
+    /// code, or say why it is left out.
+    pub fn parse_path(&self, path: &Path) -> Result<Vec<ParsedFile>, SkipReason> {
+        let source = self.read(path)?;
+        let failed = |e: McpError| SkipReason::Failed(e.to_string());
+        let mut parsed = if embedded::is_host(path) {
+            self.parse_regions(path, &source.text).map_err(failed)?
+        } else {
+            let language = CodeParser::language_for(path).ok_or(SkipReason::Failed("unsupported file type".to_string()))?;
+            vec![self.parse_text(path, language, source.text, None).map_err(failed)?]
+        };
+        for file in &mut parsed {
+            file.encoding = source.encoding;
+        }
+        Ok(parsed)
+    }
+
+    /// Like [`Self::parse_embedded`] for content already read.
+    pub fn parse_regions(&self, path: &Path, content: &str) -> McpResult<Vec<ParsedFile>> {
+        embedded::regions(path, content)
+            .into_iter()
+            .filter(|region| self.with(|parser| parser.accepts(region.language)))
+            .map(|region| {
+                let mut parsed = self.parse_text(path, region.language, region.source, Some(region.host))?;
+                for error in &mut parsed.diagnostics.errors {
+                    error.start_line += region.host.line_offset;
+                    error.end_line += region.host.line_offset;
+                }
+                Ok(parsed)
+            })
+            .collect()
+    }
+
+    fn parse_text(&self, path: &Path, language: &'static str, source: String, region: Option<HostRegion>) -> McpResult<ParsedFile> {
+        let result = self.with(|parser| parser.parse(language, &source))?;
+        Ok(ParsedFile {
+            path: path.to_path_buf(),
+            language,
+            hash: format!("{:x}", Sha256::digest(source.as_bytes())),
+            source,
+            tree: result.tree,
+            diagnostics: result.diagnostics,
+            region,
+            encoding: Encoding::Utf8,
+        })
+    }
+
+    /// Parse `paths` in parallel; results follow the sorted paths whatever
+    /// the order the threads finish in. A file with embedded code gives one
+    /// result per region, a file left out its [`SkipReason`].
+    pub fn parse_files(&self, paths: &[PathBuf]) -> Vec<(PathBuf, Result<ParsedFile, SkipReason>)> {
+        let mut paths = paths.to_vec();
+        paths.sort();
+        paths
+            .into_par_iter()
+            .flat_map_iter(|path| {
+                let parsed = match self.parse_path(&path) {
+                    Ok(files) => files.into_iter().map(Ok).collect(),
+                    Err(reason) => vec![Err(reason)],
+                };
+                parsed.into_iter().map(move |parsed| (path.clone(), parsed))
+            })
+            .collect()
+    }
+}
+
+/// A tree with the syntax errors tree-sitter recovered from.
+pub struct ParseResult {
+    /// `None` when the parse timed out.
+    pub tree: Option<Tree>,
+    pub diagnostics: ParseDiagnostics,
 }
 
 impl CodeParse for CodeParser {
+    fn parse_file(&mut self, path: &Path) -> McpResult<Tree> {
+        CodeParser::parse_file(self, path)
+    }
+}
+
+impl Default for CodeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Language names accepted by `parse_source` and the `languages` config option.
+pub fn supported_languages() -> Vec<&'static str> {
+    registry().names()
 }
 
 impl CodeParser {
     pub fn new() -> Self {
-        let mut parsers = std::collections::HashMap::new();
-        parsers.insert("typescript".to_string(), Parser::new());
-        parsers.insert("javascript".to_string(), Parser::new());
-        parsers.insert("python".to_string(), Parser::new());
-        parsers.insert("rust".to_string(), Parser::new());
+        Self { parsers: HashMap::new(), enabled: Vec::new(), timeout_ms: 0 }
+    }
+
+    /// A parser limited to the languages enabled by `indexing.languages`,
+    /// giving up on a file after `indexing.parse_timeout_ms`.
+    pub fn for_indexing(indexing: &IndexingConfig) -> Self {
+        Self { parsers: HashMap::new(), enabled: indexing.languages.clone(), timeout_ms: indexing.parse_timeout_ms }
+    }
+
+    /// Whether `language` is enabled for this parser.
+    pub fn accepts(&self, language: &str) -> bool {
+        self.enabled.is_empty() || self.enabled.iter().any(|name| name == language)
+    }
 
-        Self { parsers }
+    /// Language name for a file, or `None` if no parser handles it.
+    /// Extensionless files are recognised by their `#!` line.
+    pub fn language_for(path: &Path) -> Option<&'static str> {
+        registry().detect(path).map(|language| language.name())
     }
 
     pub fn parse_file(&mut self, path: &Path) -> McpResult<Tree> {
-        let extension = path.extension()
-            .and_then(std::ffi::OsStr::to_str)
+        let language = Self::language_for(path)
             .ok_or(McpError::Transport("Unsupported file type".to_string()))?;
 
-        let language = match extension {
-            "ts" => "typescript",
-            "js" => "javascript",
-            "py" => "python",
-            "rs" => "rust",
-            _ => return Err(McpError::Transport("Unsupported file type".to_string())),
-        };
+        let bytes = std::fs::read(path)
+            .map_err(|e| McpError::Transport(e.to_string()))?;
 
-        let parser = self.parsers.get_mut(language)
-            .ok_or(McpError::Dispatch("Parser not found".to_string()))?;
+        self.parse_source(language, &content::decode(&bytes).text)
+    }
 
-        let source_code = std::fs::read_to_string(path)
-            .map_err(|e| McpError::Transport(e.to_string()))?;
+    /// Parse in-memory source with the parser registered for `language`.
+    pub fn parse_source(&mut self, language: &str, source_code: &str) -> McpResult<Tree> {
+        let result = self.parse_with(language, source_code, None)?;
+        self.tree_of(result)
+    }
 
-        let lang = match language {
-            "typescript" => tree_sitter_typescript::language_typescript(),
-            "javascript" => tree_sitter_javascript::language(),
-            "python" => tree_sitter_python::language(),
-            "rust" => tree_sitter_rust::language(),
-            _ => return Err(McpError::Transport("Unsupported language".to_string())),
-        };
-    parser.set_language(lang).map_err(|_| McpError::Dispatch("Failed to set parser language".to_string()))?;
-        let tree = parser.parse(&source_code, None)
-            .ok_or(McpError::Dispatch("Failed to parse file".to_string()))?;
+    /// Like [`Self::parse_source`], keeping the syntax errors and timeouts
+    /// instead of failing on a timeout.
+    pub fn parse(&mut self, language: &str, source_code: &str) -> McpResult<ParseResult> {
+        self.parse_with(language, source_code, None)
+    }
 
-    Ok(tree)
+    /// Parse a new version of a file, reusing the unchanged parts of `old`.
+    /// `old` must already have been `edit`ed to match `source_code`.
+    pub fn reparse_source(&mut self, language: &str, source_code: &str, old: &Tree) -> McpResult<Tree> {
+        let result = self.parse_with(language, source_code, Some(old))?;
+        self.tree_of(result)
     }
-}
\ No newline at end of file
+
+    fn tree_of(&self, result: ParseResult) -> McpResult<Tree> {
+        result
+            .tree
+            .ok_or_else(|| McpError::Dispatch(format!("Parsing timed out after {}ms", self.timeout_ms)))
+    }
+
+    fn parse_with(&mut self, language: &str, source_code: &str, old: Option<&Tree>) -> McpResult<ParseResult> {
+        let support = registry().get(language)
+            .ok_or(McpError::Transport("Unsupported language".to_string()))?;
+        if !self.accepts(language) {
+            return Err(McpError::Config(format!("language {:?} is not enabled in indexing.languages", language)));
+        }
+
+        let parser = match self.parsers.entry(support.name()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let mut parser = Parser::new();
+                parser.set_language(support.grammar())
+                    .map_err(|_| McpError::Dispatch("Failed to set parser language".to_string()))?;
+                parser.set_timeout_micros(self.timeout_ms.saturating_mul(1000));
+                entry.insert(parser)
+            }
+        };
+        match parser.parse(source_code, old) {
+            Some(tree) => {
+                let diagnostics = ParseDiagnostics::from_tree(&tree, source_code);
+                Ok(ParseResult { tree: Some(tree), diagnostics })
+            }
+            // Without a cancellation flag, only the timeout stops a parse.
+            None => {
+                parser.reset();
+                Ok(ParseResult { tree: None, diagnostics: ParseDiagnostics::timed_out(source_code) })
+            }
+        }
+    }
+}
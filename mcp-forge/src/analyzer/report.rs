@@ -0,0 +1,370 @@
+//! Codebase statistics for `mcp-forge analyze`, computed without writing a server.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use log::warn;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::analyzer::content::{self, SkipReason, SkippedFile, SKIPPED_HEADERS};
+use crate::analyzer::diagnostics::{ParseDiagnostics, ParseHealth, HEALTH_HEADERS};
+use crate::analyzer::discover;
+use crate::analyzer::embedded::HostFormat;
+use crate::analyzer::languages::registry;
+use crate::analyzer::parser::{CodeParser, ParserPool};
+use crate::analyzer::patterns::{PatternDetect, PatternDetector};
+use crate::analyzer::symbols::SymbolExtractor;
+use crate::analyzer::tags::TagQueries;
+use crate::config::ForgeConfig;
+use crate::table;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Json,
+    Markdown,
+}
+
+const TOP_MODULES: usize = 10;
+
+#[derive(Debug, Default, Serialize)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub lines: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ModuleStats {
+    pub name: String,
+    pub files: usize,
+    pub lines: usize,
+    pub symbols: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PatternLocation {
+    pub file: String,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PatternStats {
+    pub name: String,
+    pub occurrences: usize,
+    pub average_confidence: f32,
+    pub max_confidence: f32,
+    pub locations: Vec<PatternLocation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParseFailure {
+    pub file: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AnalysisReport {
+    pub source: String,
+    pub files_analyzed: usize,
+    /// Files without a registered parser.
+    pub files_skipped: usize,
+    pub total_lines: usize,
+    pub total_symbols: usize,
+    pub languages: BTreeMap<String, LanguageStats>,
+    pub symbols_by_kind: BTreeMap<String, usize>,
+    /// Largest modules by line count.
+    pub top_modules: Vec<ModuleStats>,
+    pub patterns: Vec<PatternStats>,
+    pub parse_failures: Vec<ParseFailure>,
+    /// Syntax errors, and files left out because of them.
+    pub parse_health: ParseHealth,
+    /// Source files left out for their size or content.
+    pub skipped_files: Vec<SkippedFile>,
+}
+
+struct FileAnalysis {
+    path: String,
+    language: &'static str,
+    module: String,
+    lines: usize,
+    symbol_kinds: Vec<String>,
+    patterns: Vec<(String, f32)>,
+    diagnostics: ParseDiagnostics,
+}
+
+enum FileOutcome {
+    Skipped,
+    Failed(ParseFailure),
+    /// Too broken to index, with the reason.
+    Unparsable(String, ParseDiagnostics, String),
+    /// Too large, binary, minified or generated.
+    Excluded(SkippedFile),
+    Analyzed(FileAnalysis),
+}
+
+/// Parse every discovered file and aggregate the statistics.
+pub fn analyze(source: &Path) -> AnalysisReport {
+    analyze_with(source, &ForgeConfig::default())
+}
+
+/// Like [`analyze`], honouring the indexing and analysis settings of `config`.
+pub fn analyze_with(source: &Path, config: &ForgeConfig) -> AnalysisReport {
+    let discovery = discover(source, &config.indexing);
+    let files = discovery.files;
+    let detect_patterns = config.analysis.patterns;
+    let max_error_ratio = config.indexing.max_error_ratio;
+    let queries = TagQueries::for_indexing(&config.indexing).unwrap_or_else(|e| {
+        warn!("Using the built-in tag queries: {}", e);
+        TagQueries::builtin()
+    });
+    let parsers = ParserPool::new(&config.indexing);
+    let extractor = SymbolExtractor::with_queries(queries);
+    let detector = PatternDetector::new();
+    let detector = if detect_patterns { Some(&detector) } else { None };
+    let outcomes: Vec<FileOutcome> = files
+        .par_iter()
+        .map(|path| analyze_file(&parsers, &extractor, detector, max_error_ratio, source, path))
+        .collect();
+
+    let mut report = AnalysisReport { source: source.display().to_string(), ..Default::default() };
+    for (path, reason) in &discovery.skipped {
+        let relative = path.strip_prefix(source).unwrap_or(path);
+        report.skipped_files.push(SkippedFile::new(relative.to_string_lossy().replace('\\', "/"), reason));
+    }
+    let mut modules: BTreeMap<String, ModuleStats> = BTreeMap::new();
+    let mut patterns: BTreeMap<String, Vec<PatternLocation>> = BTreeMap::new();
+    for outcome in outcomes {
+        let file = match outcome {
+            FileOutcome::Skipped => {
+                report.files_skipped += 1;
+                continue;
+            }
+            FileOutcome::Failed(failure) => {
+                report.parse_failures.push(failure);
+                continue;
+            }
+            FileOutcome::Unparsable(path, diagnostics, reason) => {
+                report.parse_health.record(&path, &diagnostics, Some(reason));
+                continue;
+            }
+            FileOutcome::Excluded(skipped) => {
+                report.skipped_files.push(skipped);
+                continue;
+            }
+            FileOutcome::Analyzed(file) => file,
+        };
+        report.parse_health.record(&file.path, &file.diagnostics, None);
+        report.files_analyzed += 1;
+        report.total_lines += file.lines;
+        report.total_symbols += file.symbol_kinds.len();
+
+        let language = report.languages.entry(file.language.to_string()).or_default();
+        language.files += 1;
+        language.lines += file.lines;
+
+        let module = modules.entry(file.module.clone()).or_insert_with(|| ModuleStats { name: file.module.clone(), ..Default::default() });
+        module.files += 1;
+        module.lines += file.lines;
+        module.symbols += file.symbol_kinds.len();
+
+        for kind in file.symbol_kinds {
+            *report.symbols_by_kind.entry(kind).or_insert(0) += 1;
+        }
+        for (name, confidence) in file.patterns {
+            patterns.entry(name).or_default().push(PatternLocation { file: file.path.clone(), confidence });
+        }
+    }
+
+    let mut modules: Vec<ModuleStats> = modules.into_values().collect();
+    modules.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.name.cmp(&b.name)));
+    modules.truncate(TOP_MODULES);
+    report.top_modules = modules;
+
+    report.patterns = patterns
+        .into_iter()
+        .map(|(name, mut locations)| {
+            locations.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.file.cmp(&b.file)));
+            let total: f32 = locations.iter().map(|l| l.confidence).sum();
+            PatternStats {
+                name,
+                occurrences: locations.len(),
+                average_confidence: total / locations.len() as f32,
+                max_confidence: locations.first().map(|l| l.confidence).unwrap_or(0.0),
+                locations,
+            }
+        })
+        .collect();
+    report.parse_health.finish();
+    report.skipped_files.sort_by(|a, b| a.file.cmp(&b.file));
+    report
+}
+
+fn analyze_file(
+    parsers: &ParserPool,
+    extractor: &SymbolExtractor,
+    detector: Option<&PatternDetector>,
+    max_error_ratio: f64,
+    root: &Path,
+    path: &Path,
+) -> FileOutcome {
+    let host = HostFormat::of(path);
+    let Some(language) = CodeParser::language_for(path).or(host.map(HostFormat::name)) else {
+        return FileOutcome::Skipped;
+    };
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let display = relative.to_string_lossy().replace('\\', "/");
+    let failed = |error: String| FileOutcome::Failed(ParseFailure { file: display.clone(), error });
+    let source = match parsers.read(path) {
+        Ok(source) => source,
+        Err(SkipReason::Unreadable(error)) => return failed(error),
+        Err(reason) => return FileOutcome::Excluded(SkippedFile::new(display.clone(), &reason)),
+    };
+    let module = if relative.components().count() > 1 {
+        relative.components().next().map(|c| c.as_os_str().to_string_lossy().to_string()).unwrap_or_default()
+    } else {
+        "<root>".to_string()
+    };
+
+    if host.is_some() {
+        let code = source.text;
+        let regions = match parsers.parse_regions(path, &code) {
+            Ok(regions) => regions,
+            Err(e) => return failed(e.to_string()),
+        };
+        let mut diagnostics = ParseDiagnostics::default();
+        let mut symbol_kinds = Vec::new();
+        let mut patterns: Vec<(String, f32)> = Vec::new();
+        for region in &regions {
+            diagnostics.merge(&region.diagnostics);
+            // A broken block or cell is left out on its own.
+            if region.diagnostics.skip_reason(max_error_ratio).is_some() {
+                continue;
+            }
+            symbol_kinds.extend(extractor.extract_parsed(region, &display).into_iter().map(|s| s.kind));
+            let ext = registry().get(region.language).and_then(|l| l.extensions().first().copied()).unwrap_or("");
+            for (name, confidence) in detector.map(|d| PatternDetect::detect_patterns(d, &region.source, ext)).unwrap_or_default() {
+                match patterns.iter_mut().find(|(existing, _)| *existing == name) {
+                    Some((_, best)) => *best = best.max(confidence),
+                    None => patterns.push((name, confidence)),
+                }
+            }
+        }
+        return FileOutcome::Analyzed(FileAnalysis {
+            language,
+            module,
+            lines: code.lines().count(),
+            symbol_kinds,
+            patterns,
+            diagnostics,
+            path: display,
+        });
+    }
+
+    let parsed = match parsers.parse_source(path, language, source) {
+        Ok(parsed) => parsed,
+        Err(e) => return failed(e.to_string()),
+    };
+    let (tree, diagnostics, code) = match (parsed.tree, parsed.diagnostics.skip_reason(max_error_ratio)) {
+        (Some(tree), None) => (tree, parsed.diagnostics, parsed.source),
+        (_, reason) => {
+            let reason = reason.unwrap_or_else(|| "parse timed out".to_string());
+            return FileOutcome::Unparsable(display, parsed.diagnostics, reason);
+        }
+    };
+
+    let symbols = extractor.extract_symbols(&tree.root_node(), &code, &display);
+    // Scripts recognised by their `#!` line use their language's main extension.
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .or_else(|| registry().get(language).and_then(|l| l.extensions().first().copied()))
+        .unwrap_or("");
+
+    FileOutcome::Analyzed(FileAnalysis {
+        language,
+        module,
+        lines: code.lines().count(),
+        symbol_kinds: symbols.into_iter().map(|s| s.kind).collect(),
+        patterns: detector.map(|d| PatternDetect::detect_patterns(d, &code, ext)).unwrap_or_default(),
+        diagnostics,
+        path: display,
+    })
+}
+
+impl AnalysisReport {
+    fn language_rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.languages.iter().map(|(name, s)| vec![name.clone(), s.files.to_string(), s.lines.to_string()])
+    }
+
+    fn kind_rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.symbols_by_kind.iter().map(|(kind, count)| vec![kind.clone(), count.to_string()])
+    }
+
+    fn module_rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.top_modules.iter().map(|m| vec![m.name.clone(), m.files.to_string(), m.lines.to_string(), m.symbols.to_string()])
+    }
+
+    fn pattern_rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.patterns.iter().map(|p| {
+            vec![
+                p.name.clone(),
+                p.occurrences.to_string(),
+                format!("{:.2}", p.average_confidence),
+                format!("{:.2}", p.max_confidence),
+            ]
+        })
+    }
+
+    fn failure_rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.parse_failures.iter().map(|f| vec![f.file.clone(), f.error.clone()])
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "Files analyzed: {} ({} skipped, {} left out), lines: {}, symbols: {}, parse failures: {}",
+            self.files_analyzed,
+            self.files_skipped,
+            self.skipped_files.len(),
+            self.total_lines,
+            self.total_symbols,
+            self.parse_failures.len(),
+        )
+    }
+}
+
+const LANGUAGE_HEADERS: &[&str] = &["LANGUAGE", "FILES", "LINES"];
+const KIND_HEADERS: &[&str] = &["KIND", "COUNT"];
+const MODULE_HEADERS: &[&str] = &["MODULE", "FILES", "LINES", "SYMBOLS"];
+const PATTERN_HEADERS: &[&str] = &["PATTERN", "OCCURRENCES", "AVG CONFIDENCE", "MAX CONFIDENCE"];
+const FAILURE_HEADERS: &[&str] = &["FILE", "ERROR"];
+
+impl AnalysisReport {
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default() + "\n",
+            ReportFormat::Table => [
+                format!("Codebase analysis: {}\n{}\n", self.source, self.summary()),
+                format!("Languages\n{}", table::render(LANGUAGE_HEADERS, self.language_rows())),
+                format!("Symbols by kind\n{}", table::render(KIND_HEADERS, self.kind_rows())),
+                format!("Top modules\n{}", table::render(MODULE_HEADERS, self.module_rows())),
+                format!("Patterns\n{}", table::render(PATTERN_HEADERS, self.pattern_rows())),
+                format!("Parse failures\n{}", table::render(FAILURE_HEADERS, self.failure_rows())),
+                format!("Parse health\n{}\n{}", self.parse_health.summary(), table::render(HEALTH_HEADERS, self.parse_health.rows())),
+                format!("Skipped files\n{}", table::render(SKIPPED_HEADERS, content::skipped_rows(&self.skipped_files))),
+            ]
+            .join("\n"),
+            ReportFormat::Markdown => [
+                format!("# Codebase analysis: `{}`\n\n{}\n", self.source, self.summary()),
+                format!("## Languages\n\n{}", table::render_markdown(LANGUAGE_HEADERS, self.language_rows())),
+                format!("## Symbols by kind\n\n{}", table::render_markdown(KIND_HEADERS, self.kind_rows())),
+                format!("## Top modules\n\n{}", table::render_markdown(MODULE_HEADERS, self.module_rows())),
+                format!("## Patterns\n\n{}", table::render_markdown(PATTERN_HEADERS, self.pattern_rows())),
+                format!("## Parse failures\n\n{}", table::render_markdown(FAILURE_HEADERS, self.failure_rows())),
+                format!("## Parse health\n\n{}\n\n{}", self.parse_health.summary(), table::render_markdown(HEALTH_HEADERS, self.parse_health.rows())),
+                format!("## Skipped files\n\n{}", table::render_markdown(SKIPPED_HEADERS, content::skipped_rows(&self.skipped_files))),
+            ]
+            .join("\n"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Structured signatures of functions and methods: parameters with their
+//! types and defaults, return type, type parameters and modifiers.
+//!
+//! Each language fills a [`Signature`] from its own syntax in
+//! [`LanguageSupport::callable`](crate::analyzer::languages::LanguageSupport::callable);
+//! the helpers here cover what the grammars share.
+
+use tree_sitter::Node;
+
+use crate::analyzer::languages::node_text;
+pub use crate::database::records::{Parameter, ParameterKind, Signature};
+
+/// Declaration text from `start` up to the body of `node`, on one line.
+pub fn header(node: &Node, start: usize, source: &str) -> Option<String> {
+    let end = node.child_by_field_name("body").map_or(node.end_byte(), |body| body.start_byte());
+    Some(one_line(source.get(start..end)?))
+}
+
+pub fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Text of `field` on one line, without the `:` of a type annotation.
+pub fn field_text(node: &Node, field: &str, source: &str) -> Option<String> {
+    let text = node_text(&node.child_by_field_name(field)?, source);
+    Some(one_line(text.strip_prefix(':').unwrap_or(text).trim()))
+}
+
+/// Each named child of the list in `field`, such as type parameters, on one line.
+pub fn list_items(node: &Node, field: &str, source: &str) -> Vec<String> {
+    let Some(list) = node.child_by_field_name(field) else {
+        return Vec::new();
+    };
+    named_children(&list).filter(|item| !item.kind().contains("comment")).map(|item| one_line(node_text(&item, source))).collect()
+}
+
+pub fn named_children<'a, 't>(node: &'a Node<'t>) -> impl Iterator<Item = Node<'t>> + 'a {
+    (0..node.named_child_count()).filter_map(|i| node.named_child(i))
+}
+
+/// Anonymous children of `node`, i.e. its keywords and punctuation: `async`, `static`, `*`.
+pub fn keywords(node: &Node) -> Vec<&'static str> {
+    (0..node.child_count()).filter_map(|i| node.child(i)).filter(|child| !child.is_named()).map(|child| child.kind()).collect()
+}
+
+/// Whether the body of `node` yields, not counting nested functions and classes.
+pub fn yields(node: &Node, yield_kind: &str, nested: &[&str]) -> bool {
+    fn search(node: &Node, yield_kind: &str, nested: &[&str]) -> bool {
+        named_children(node).any(|child| child.kind() == yield_kind || (!nested.contains(&child.kind()) && search(&child, yield_kind, nested)))
+    }
+    node.child_by_field_name("body").is_some_and(|body| search(&body, yield_kind, nested))
+}
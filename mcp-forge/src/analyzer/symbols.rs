@@ -1,232 +1,175 @@
 /// Trait for symbol extraction, following SDK standards.
 pub trait SymbolExtract {
-    fn extract_symbols(&self, ast: &tree_sitter::Node, file_path: &str) -> Vec<Symbol>;
+    fn extract_symbols(&self, ast: &tree_sitter::Node, source: &str, file_path: &str) -> Vec<Symbol>;
 }
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::analyzer::docs::{self, DocComment};
+use crate::analyzer::embedded::HostRegion;
+use crate::analyzer::languages::{registry, LanguageSupport, Project};
+use crate::analyzer::parser::ParsedFile;
+use crate::analyzer::signature::Signature;
+use crate::analyzer::tags::{Tag, TagQueries};
+use crate::knowledge::relationships::Relationship;
+
 pub struct SymbolExtractor {
-    // Optionally store module context, etc.
+    queries: Arc<TagQueries>,
 }
 
 impl SymbolExtract for SymbolExtractor {
+    fn extract_symbols(&self, ast: &tree_sitter::Node, source: &str, file_path: &str) -> Vec<Symbol> {
+        SymbolExtractor::extract_symbols(self, ast, source, file_path)
+    }
+}
+
+impl Default for SymbolExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SymbolExtractor {
+    /// An extractor using the built-in tag queries.
     pub fn new() -> Self {
-        // Initialize the SymbolExtractor
-        SymbolExtractor {
-            // Initialize fields
+        Self::with_queries(TagQueries::builtin())
+    }
+
+    /// An extractor using `queries`, e.g. from [`TagQueries::for_indexing`].
+    pub fn with_queries(queries: Arc<TagQueries>) -> Self {
+        SymbolExtractor { queries }
+    }
+
+    /// Definitions and references in `ast`, found by the tag query of the
+    /// language registered for `file_path`.
+    pub fn extract_tags<'t>(&self, ast: &tree_sitter::Node<'t>, source: &str, file_path: &str) -> Vec<Tag<'t>> {
+        match registry().for_source(Path::new(file_path), source) {
+            Some(language) => self.queries.tags(language, ast, source),
+            None => Vec::new(),
         }
     }
 
-    pub fn extract_symbols(&self, ast: &tree_sitter::Node, file_path: &str) -> Vec<Symbol> {
-        let mut symbols = Vec::new();
-        let mut to_visit = vec![*ast];
-        let ext = std::path::Path::new(file_path)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("");
-        let module = file_path.split('/').nth(1).unwrap_or(""); // crude module detection
-        while let Some(node) = to_visit.pop() {
-            let kind = node.kind();
-            match kind {
-                // Python
-                "function_definition" | "def" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "function".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "class_definition" | "class_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "class".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                // JavaScript/TypeScript
-                "function_declaration" | "method_definition" | "arrow_function" | "function_expression" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "function".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "variable_declaration" | "const_declaration" | "let_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "variable".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "interface_declaration" | "type_alias_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "interface".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                // Java
-                "method_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "method".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "field_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "field".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "interface_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let start_line = node.start_position().row + 1;
-                    let end_line = node.end_position().row + 1;
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "interface".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line,
-                        end_line,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "package_declaration" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: "package".to_string(),
-                        file_path: file_path.to_string(),
-                        start_line: node.start_position().row + 1,
-                        end_line: node.end_position().row + 1,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                "import_declaration" | "import_statement" | "export_statement" => {
-                    let name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    symbols.push(Symbol {
-                        id: format!("{}::{}", file_path, name),
-                        name,
-                        kind: kind.to_string(),
-                        file_path: file_path.to_string(),
-                        start_line: node.start_position().row + 1,
-                        end_line: node.end_position().row + 1,
-                        scope: module.to_string(),
-                        documentation: None,
-                        signature: None,
-                    });
-                }
-                _ => {}
-            }
-            for i in 0..node.child_count() {
-                if let Some(child) = node.child(i) {
-                    to_visit.push(child);
-                }
-            }
+    /// Symbols defined in `ast`, using the rules of the language registered for `file_path`.
+    pub fn extract_symbols(&self, ast: &tree_sitter::Node, source: &str, file_path: &str) -> Vec<Symbol> {
+        self.extract_symbols_in(ast, source, file_path, 0..source.len())
+    }
+
+    /// Like [`Self::extract_symbols`], limited to definitions overlapping `bytes`.
+    pub fn extract_symbols_in(&self, ast: &tree_sitter::Node, source: &str, file_path: &str, bytes: Range<usize>) -> Vec<Symbol> {
+        let Some(language) = registry().for_source(Path::new(file_path), source) else {
+            return Vec::new();
+        };
+        self.symbols(language, ast, source, file_path, file_path, bytes)
+    }
+
+    /// Symbols of a file from a [`ParserPool`](crate::analyzer::parser::ParserPool).
+    /// Symbols of embedded code get host file lines, and ids qualified by
+    /// their region (`docs/guide.md#2::connect`) since blocks and cells may
+    /// redefine the same names.
+    pub fn extract_parsed(&self, parsed: &ParsedFile, file_path: &str) -> Vec<Symbol> {
+        let (Some(tree), Some(language)) = (&parsed.tree, registry().get(parsed.language)) else {
+            return Vec::new();
+        };
+        let Some(region) = parsed.region else {
+            return self.symbols(language, &tree.root_node(), &parsed.source, file_path, file_path, 0..parsed.source.len());
+        };
+        let prefix = format!("{}#{}", file_path, region.index);
+        let mut symbols = self.symbols(language, &tree.root_node(), &parsed.source, file_path, &prefix, 0..parsed.source.len());
+        for symbol in &mut symbols {
+            symbol.start_line += region.line_offset;
+            symbol.end_line += region.line_offset;
+            symbol.region = Some(region);
         }
         symbols
     }
+
+    /// Relationships between the symbols [`Self::extract_parsed`] returns for the same file.
+    pub fn extract_parsed_relationships(&self, parsed: &ParsedFile, file_path: &str, project: &Project) -> Vec<Relationship> {
+        let (Some(tree), Some(language)) = (&parsed.tree, registry().get(parsed.language)) else {
+            return Vec::new();
+        };
+        let id_prefix = match parsed.region {
+            Some(region) => format!("{}#{}", file_path, region.index),
+            None => file_path.to_string(),
+        };
+        let tags = self.queries.tags(language, &tree.root_node(), &parsed.source);
+        language.relationships(&tree.root_node(), &parsed.source, &id_prefix, &tags, project)
+    }
+
+    /// Symbols with ids `<id_prefix>::<path>`, see [`Tag::path`].
+    fn symbols(&self, language: &dyn LanguageSupport, ast: &tree_sitter::Node, source: &str, file_path: &str, id_prefix: &str, bytes: Range<usize>) -> Vec<Symbol> {
+        let module_path = language.module_path(file_path);
+        let tags = self.queries.tags_in(language, ast, source, bytes);
+        tags.iter()
+            .enumerate()
+            .filter(|(_, tag)| tag.definition)
+            .map(|(i, tag)| Symbol {
+                id: format!("{}::{}", id_prefix, tag.path),
+                qualified_name: qualified_name(&tags, i, &module_path, language.scope_separator()),
+                parent_id: tag.parent.map(|parent| format!("{}::{}", id_prefix, tags[parent].path)),
+                signature: language.symbol_signature(&tag.node, source),
+                callable: language.callable(&tag.node, source),
+                type_name: language.symbol_type(&tag.node, source),
+                visibility: language.symbol_visibility(&tag.node, source),
+                attributes: language.symbol_attributes(&tag.node, source),
+                byte_range: tag.node.byte_range(),
+                start_line: tag.node.start_position().row + 1,
+                end_line: tag.node.end_position().row + 1,
+                name: tag.name.clone(),
+                kind: tag.kind.clone(),
+                file_path: file_path.to_string(),
+                scope: module_path.clone(),
+                doc_tags: tag.documentation.as_deref().and_then(docs::parse),
+                documentation: tag.documentation.clone(),
+                region: None,
+            })
+            .collect()
+    }
+}
+
+/// `module_path`, the names of the definitions enclosing `tags[index]` and
+/// its own, joined with `separator`. Overloads share their qualified name.
+fn qualified_name(tags: &[Tag], index: usize, module_path: &str, separator: &str) -> String {
+    let tag = &tags[index];
+    let Some(parent) = tag.parent else {
+        let name = tag.name.replace('.', separator);
+        return if module_path.is_empty() { name } else { format!("{}{}{}", module_path, separator, name) };
+    };
+    // A name qualified by `@scope` repeats its parent's.
+    let name = tag.name.strip_prefix(&format!("{}.", tags[parent].name)).unwrap_or(&tag.name);
+    format!("{}{}{}", qualified_name(tags, parent, module_path, separator), separator, name.replace('.', separator))
 }
 
+#[derive(Debug, Clone)]
 pub struct Symbol {
+    /// `file_path::path`, unique in the index; see [`Tag::path`].
     pub id: String,
     pub name: String,
+    /// Module path and enclosing definitions: `app.models.User.save`, `crate::db::Pool::get`.
+    pub qualified_name: String,
+    /// Id of the enclosing definition; `None` at the top level of a file.
+    pub parent_id: Option<String>,
     pub kind: String,
     pub file_path: String,
+    /// Bytes of the defining node in the source.
+    pub byte_range: Range<usize>,
     pub start_line: usize,
     pub end_line: usize,
+    /// Module the symbol is defined in, the start of `qualified_name`: `app.models`, `crate::db`.
     pub scope: String,
     pub documentation: Option<String>,
+    /// `documentation` parsed into parameters, return value, exceptions and sections.
+    pub doc_tags: Option<DocComment>,
     pub signature: Option<String>,
+    /// Structured form of `signature` for functions and methods.
+    pub callable: Option<Signature>,
+    /// Declared type of a field, attribute or constant.
+    pub type_name: Option<String>,
+    /// Visibility as written (`pub(crate)`), or by convention (Python's `public` and `private`).
+    pub visibility: Option<String>,
+    /// Attributes, annotations or decorators as written, without `#[]` or `@`.
+    pub attributes: Vec<String>,
+    /// Set for code embedded in a Markdown, notebook or component file.
+    pub region: Option<HostRegion>,
 }
\ No newline at end of file
@@ -0,0 +1,318 @@
+//! Symbol definitions and references found by tree-sitter tag queries.
+//!
+//! Each language ships a query in `languages/queries/`. Captures follow the
+//! tree-sitter tags conventions:
+//!
+//! - `@definition.<kind>` marks a node defining a symbol of that kind,
+//! - `@reference.<kind>` marks a use of one (`@reference.call` becomes a
+//!   `calls` edge, any other kind a `references` edge),
+//! - `@name` is the symbol's name and `@scope` an optional qualifier joined
+//!   with a dot (`Server.Run`),
+//! - `@doc` captures its documentation.
+//!
+//! Definitions nested in another one, or named after one with `@scope`, get
+//! a path through their enclosing definitions (`UserService.find`) that is
+//! unique in the file: later definitions with a path already taken, such as
+//! overloads, get `~2`, `~3`, ...
+//!
+//! A project can add patterns to a language's query, or replace it, with
+//! `indexing.queries`. Added patterns take precedence: when several patterns
+//! tag the same node with the same name, the first pattern wins.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use tree_sitter::{Node, Query, QueryCursor};
+
+use crate::analyzer::languages::{encloses, node_text, registry, LanguageSupport};
+use crate::config::{IndexingConfig, QueryOverride};
+use crate::error::{McpError, McpResult};
+
+/// A definition or reference found by a tag query.
+#[derive(Debug, Clone)]
+pub struct Tag<'t> {
+    pub node: Node<'t>,
+    /// Symbol kind for definitions (`function`), reference kind otherwise (`call`).
+    pub kind: String,
+    pub name: String,
+    pub definition: bool,
+    pub documentation: Option<String>,
+    /// Definitions: unique path in the file, see the module docs. References: the name.
+    pub path: String,
+    /// Index of the enclosing definition among the tags.
+    pub parent: Option<usize>,
+    /// Query pattern that produced the tag; lower wins on duplicates.
+    pattern: usize,
+}
+
+enum Role {
+    Definition(String),
+    Reference(String),
+    Name,
+    Scope,
+    Doc,
+    Ignored,
+}
+
+struct TagQuery {
+    query: Query,
+    roles: Vec<Role>,
+}
+
+impl TagQuery {
+    fn new(language: &dyn LanguageSupport, source: &str) -> Result<Self, String> {
+        let query = Query::new(language.grammar(), source).map_err(|e| e.to_string())?;
+        let roles = query
+            .capture_names()
+            .iter()
+            .map(|name| match name.as_str() {
+                "name" => Role::Name,
+                "scope" => Role::Scope,
+                "doc" => Role::Doc,
+                other => match (other.strip_prefix("definition."), other.strip_prefix("reference.")) {
+                    (Some(kind), _) => Role::Definition(kind.to_string()),
+                    (_, Some(kind)) => Role::Reference(kind.to_string()),
+                    _ => Role::Ignored,
+                },
+            })
+            .collect();
+        Ok(Self { query, roles })
+    }
+}
+
+/// Compiled tag queries for every registered language.
+pub struct TagQueries {
+    queries: HashMap<&'static str, TagQuery>,
+}
+
+impl TagQueries {
+    /// The built-in queries, compiled once per process.
+    pub fn builtin() -> Arc<TagQueries> {
+        static BUILTIN: OnceLock<Arc<TagQueries>> = OnceLock::new();
+        BUILTIN
+            .get_or_init(|| Arc::new(Self::with_overrides(&BTreeMap::new()).expect("built-in tag queries compile")))
+            .clone()
+    }
+
+    /// The built-in queries with the project's `indexing.queries` applied.
+    pub fn for_indexing(indexing: &IndexingConfig) -> McpResult<Arc<TagQueries>> {
+        if indexing.queries.is_empty() {
+            return Ok(Self::builtin());
+        }
+        Self::with_overrides(&indexing.queries).map(Arc::new)
+    }
+
+    fn with_overrides(overrides: &BTreeMap<String, QueryOverride>) -> McpResult<Self> {
+        let mut queries = HashMap::new();
+        for language in registry().names().into_iter().filter_map(|name| registry().get(name)) {
+            let builtin = language.tags_query();
+            let source = match overrides.get(language.name()) {
+                Some(query) => {
+                    let custom = read_query(&query.path)?;
+                    if query.replace { custom } else { format!("{}\n{}", custom, builtin) }
+                }
+                None => builtin.to_string(),
+            };
+            let query = TagQuery::new(language, &source).map_err(|e| match overrides.get(language.name()) {
+                Some(query) => McpError::Config(format!("indexing.queries.{}: {}: {}", language.name(), query.path.display(), e)),
+                None => McpError::Config(format!("built-in {} tag query: {}", language.name(), e)),
+            })?;
+            queries.insert(language.name(), query);
+        }
+        Ok(Self { queries })
+    }
+
+    pub fn tags<'t>(&self, language: &dyn LanguageSupport, root: &Node<'t>, source: &str) -> Vec<Tag<'t>> {
+        self.tags_in(language, root, source, 0..source.len())
+    }
+
+    /// Tags whose nodes overlap `bytes`, in document order, outer definitions
+    /// before the ones they contain.
+    pub fn tags_in<'t>(&self, language: &dyn LanguageSupport, root: &Node<'t>, source: &str, bytes: Range<usize>) -> Vec<Tag<'t>> {
+        let Some(tag_query) = self.queries.get(language.name()) else {
+            return Vec::new();
+        };
+        let mut tags: Vec<Tag<'t>> = Vec::new();
+        let mut seen: HashMap<(usize, bool, String), usize> = HashMap::new();
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(bytes);
+        for found in cursor.matches(&tag_query.query, *root, source.as_bytes()) {
+            let mut target = None;
+            let (mut name, mut scope, mut comments) = (None, None, Vec::new());
+            for capture in found.captures {
+                match &tag_query.roles[capture.index as usize] {
+                    Role::Definition(kind) => target = Some((capture.node, kind, true)),
+                    Role::Reference(kind) => target = Some((capture.node, kind, false)),
+                    Role::Name => name = Some(capture.node),
+                    Role::Scope => scope = Some(capture.node),
+                    Role::Doc => comments.push(capture.node),
+                    Role::Ignored => {}
+                }
+            }
+            let Some((node, kind, definition)) = target else { continue };
+            let docs: Vec<&str> = comments
+                .iter()
+                .filter(|comment| language.doc_comment(comment, &node, source))
+                .map(|comment| node_text(comment, source))
+                .collect();
+            let name = language
+                .symbol_name(&node, source)
+                .or_else(|| {
+                    let name = unquote(node_text(&name?, source));
+                    Some(match scope {
+                        Some(scope) => format!("{}.{}", node_text(&scope, source), name),
+                        None => name.to_string(),
+                    })
+                })
+                .unwrap_or_else(|| "<unknown>".to_string());
+            let kind = if definition {
+                language.refine_kind(&node, &name, kind, source).map_or_else(|| kind.clone(), str::to_string)
+            } else {
+                kind.clone()
+            };
+            let tag = Tag { node, kind, name, definition, documentation: clean_doc(&docs), path: String::new(), parent: None, pattern: found.pattern_index };
+
+            match seen.get(&(node.id(), definition, tag.name.clone())) {
+                Some(&index) => {
+                    let existing = &mut tags[index];
+                    if tag.pattern < existing.pattern {
+                        let documentation = existing.documentation.take();
+                        *existing = tag;
+                        existing.documentation = existing.documentation.take().or(documentation);
+                    } else if existing.documentation.is_none() {
+                        existing.documentation = tag.documentation;
+                    }
+                }
+                None => {
+                    seen.insert((node.id(), definition, tag.name.clone()), tags.len());
+                    tags.push(tag);
+                }
+            }
+        }
+        tags.sort_by_key(|tag| (tag.node.start_byte(), std::cmp::Reverse(tag.node.end_byte())));
+        assign_scopes(&mut tags, language.transparent_kinds(), language.hoisted_kinds());
+        tags
+    }
+}
+
+/// Kinds that are never members of another definition, whatever their name.
+const UNSCOPED_KINDS: &[&str] = &["import", "package", "module"];
+
+/// Kinds a definition of a `hoisted` kind skips when looking for its parent.
+const FUNCTION_KINDS: &[&str] = &["function", "method"];
+
+/// Set [`Tag::path`] and [`Tag::parent`] of tags sorted outer first.
+/// Definitions of a `transparent` kind enclose nothing, and those of a
+/// `hoisted` kind are not members of the functions around them.
+fn assign_scopes(tags: &mut [Tag], transparent: &[&str], hoisted: &[&str]) {
+    let mut open: Vec<usize> = Vec::new();
+    let mut taken: HashMap<String, usize> = HashMap::new();
+    for i in 0..tags.len() {
+        if !tags[i].definition {
+            tags[i].path = tags[i].name.clone();
+            continue;
+        }
+        let node = tags[i].node;
+        while open.last().is_some_and(|&outer| !encloses(&tags[outer].node, &node)) {
+            open.pop();
+        }
+        // Several tags may share a node; none of them encloses the others.
+        let skip_functions = hoisted.contains(&tags[i].kind.as_str());
+        tags[i].parent = open
+            .iter()
+            .rev()
+            .copied()
+            .find(|&outer| tags[outer].node.id() != node.id() && !(skip_functions && FUNCTION_KINDS.contains(&tags[outer].kind.as_str())));
+        if !transparent.contains(&tags[i].kind.as_str()) {
+            open.push(i);
+        }
+        let path = match tags[i].parent {
+            Some(parent) => format!("{}.{}", tags[parent].path, tags[i].name),
+            None => tags[i].name.clone(),
+        };
+        tags[i].path = unique(&mut taken, path);
+    }
+    // `Server.Run` belongs to `Server` wherever the two are defined in the same scope.
+    let paths: HashMap<String, usize> = tags.iter().enumerate().filter(|(_, tag)| tag.definition).map(|(i, tag)| (tag.path.clone(), i)).collect();
+    for i in 0..tags.len() {
+        if !tags[i].definition || UNSCOPED_KINDS.contains(&tags[i].kind.as_str()) {
+            continue;
+        }
+        let Some((scope, _)) = tags[i].name.rsplit_once('.') else { continue };
+        let scope = match tags[i].parent {
+            Some(parent) => format!("{}.{}", tags[parent].path, scope),
+            None => scope.to_string(),
+        };
+        if let Some(&parent) = paths.get(&scope).filter(|&&parent| parent != i) {
+            tags[i].parent = Some(parent);
+        }
+    }
+}
+
+/// `path`, or `path~n` with the first `n` from 2 not taken yet.
+fn unique(taken: &mut HashMap<String, usize>, path: String) -> String {
+    let count = taken.entry(path.clone()).or_insert(0);
+    *count += 1;
+    match *count {
+        1 => path,
+        n => {
+            let numbered = format!("{}~{}", path, n);
+            taken.insert(numbered.clone(), 1);
+            numbered
+        }
+    }
+}
+
+fn read_query(path: &Path) -> McpResult<String> {
+    std::fs::read_to_string(path).map_err(|e| McpError::Config(format!("cannot read tag query {}: {}", path.display(), e)))
+}
+
+fn unquote(text: &str) -> &str {
+    text.trim_matches(|c| matches!(c, '"' | '\'' | '`'))
+}
+
+/// Documentation text without comment markers or docstring quotes. Lines
+/// keep their indentation relative to each other, which docstring sections
+/// such as `Args:` rely on.
+fn clean_doc(docs: &[&str]) -> Option<String> {
+    let mut lines = Vec::new();
+    for doc in docs {
+        let doc = doc.trim();
+        let docstring = ["\"\"\"", "'''", "\""].iter().find_map(|quote| {
+            doc.trim_start_matches(['r', 'R', 'u', 'U'])
+                .strip_prefix(quote)
+                .and_then(|inner| inner.strip_suffix(quote))
+        });
+        let doc_lines: Vec<&str> = match docstring {
+            Some(docstring) => docstring.lines().map(str::trim_end).collect(),
+            None => doc
+                .lines()
+                .map(|line| {
+                    let line = line.trim_end();
+                    let line = line.strip_suffix("*/").unwrap_or(line).trim_end();
+                    let marked = line.trim_start();
+                    ["///", "//!", "//", "/**", "/*!", "/*", "*", "#"]
+                        .iter()
+                        .find_map(|marker| marked.strip_prefix(marker))
+                        .unwrap_or(line)
+                })
+                .collect(),
+        };
+        // The first line of a docstring starts right after its quotes.
+        let indent = doc_lines
+            .iter()
+            .skip(usize::from(doc_lines.len() > 1))
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        for (i, line) in doc_lines.iter().enumerate() {
+            let line = if i == 0 && doc_lines.len() > 1 { line.trim_start() } else { line.get(indent..).unwrap_or_else(|| line.trim_start()) };
+            lines.push(line);
+        }
+    }
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
@@ -0,0 +1,457 @@
+//! Project configuration loaded from `mcp-forge.yaml`.
+//!
+//! Values are layered: built-in defaults, then the config file found in the
+//! source root, then `MCP_FORGE_*` environment variables, then CLI flags.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::analyzer::parser::supported_languages;
+use crate::analyzer::tags::TagQueries;
+use crate::error::{McpError, McpResult};
+use crate::server::templates::ServerTarget;
+
+/// File names searched for in the source root, in order.
+pub const CONFIG_FILE_NAMES: &[&str] = &["mcp-forge.yaml", "mcp-forge.yml", ".mcpforge.yaml", ".mcpforge.yml"];
+
+/// Schema version written by `mcp-forge init`; files without `version` are version 0.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// A layer of settings applied on top of the parsed config file.
+///
+/// [`ForgeConfig::load`] applies [`EnvOverrides`] and then [`ConfigOverrides`]
+/// (CLI flags), so later sources win.
+pub trait ConfigSource {
+    fn apply(&self, config: &mut ForgeConfig) -> McpResult<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForgeConfig {
+    #[serde(default)]
+    pub version: u32,
+    pub project: ProjectConfig,
+    pub indexing: IndexingConfig,
+    pub analysis: AnalysisConfig,
+    pub server: ServerConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexingConfig {
+    /// Gitignore-style patterns excluded on top of `.gitignore`.
+    pub exclude_patterns: Vec<String>,
+    pub max_file_size: ByteSize,
+    /// Languages to index; empty means every supported language.
+    pub languages: Vec<String>,
+    /// Time limit for parsing one file, in milliseconds; 0 disables it.
+    pub parse_timeout_ms: u64,
+    /// Files whose share of unparsable text exceeds this are not indexed.
+    pub max_error_ratio: f64,
+    /// Leave out files whose header says they are generated, and protobuf output.
+    pub skip_generated: bool,
+    /// Leave out minified bundles.
+    pub skip_minified: bool,
+    /// Tag queries added to, or replacing, a language's built-in one.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub queries: BTreeMap<String, QueryOverride>,
+}
+
+/// A user-supplied tree-sitter tag query (`.scm`) for one language.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryOverride {
+    /// Query file; relative to the config file.
+    pub path: PathBuf,
+    /// Use only this query instead of adding its patterns to the built-in one.
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalysisConfig {
+    pub patterns: bool,
+    pub embeddings: bool,
+    pub complexity_threshold: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub port: u16,
+    pub auto_reload: bool,
+    /// Language of the server project generated by `build`.
+    pub target: ServerTarget,
+    /// Custom templates laid over the built-in ones; relative to the config file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_dir: Option<PathBuf>,
+}
+
+impl Default for ForgeConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            project: ProjectConfig::default(),
+            indexing: IndexingConfig::default(),
+            analysis: AnalysisConfig::default(),
+            server: ServerConfig::default(),
+        }
+    }
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        Self { name: "MyProject".to_string(), description: "AI expert for my codebase".to_string() }
+    }
+}
+
+impl Default for IndexingConfig {
+    fn default() -> Self {
+        Self {
+            exclude_patterns: Vec::new(),
+            max_file_size: ByteSize(5 * 1024 * 1024),
+            languages: Vec::new(),
+            parse_timeout_ms: 5000,
+            max_error_ratio: 0.5,
+            skip_generated: true,
+            skip_minified: true,
+            queries: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self { patterns: true, embeddings: true, complexity_threshold: 10 }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { port: 3000, auto_reload: true, target: ServerTarget::default(), template_dir: None }
+    }
+}
+
+impl IndexingConfig {
+    /// Whether files of `language` should be indexed.
+    pub fn includes_language(&self, language: &str) -> bool {
+        self.languages.is_empty() || self.languages.iter().any(|l| l == language)
+    }
+}
+
+/// A size in bytes, written as `5MB`, `512KB`, `1.5GB` or a plain number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let trimmed = raw.trim();
+        let split = trimmed.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(trimmed.len());
+        let (number, unit) = trimmed.split_at(split);
+        let value: f64 = number
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid size {:?}; expected e.g. \"5MB\"", raw))?;
+        let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+            "" | "B" => 1u64,
+            "K" | "KB" | "KIB" => 1 << 10,
+            "M" | "MB" | "MIB" => 1 << 20,
+            "G" | "GB" | "GIB" => 1 << 30,
+            other => return Err(format!("unknown size unit {:?} in {:?}", other, raw)),
+        };
+        if value < 0.0 {
+            return Err(format!("size must not be negative: {:?}", raw));
+        }
+        Ok(ByteSize((value * multiplier as f64) as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: &[(u64, &str)] = &[(1 << 30, "GB"), (1 << 20, "MB"), (1 << 10, "KB")];
+        for (size, unit) in UNITS {
+            if self.0 >= *size && self.0.is_multiple_of(*size) {
+                return write!(f, "{}{}", self.0 / size, unit);
+            }
+        }
+        write!(f, "{}B", self.0)
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Bytes(u64),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Bytes(bytes) => Ok(ByteSize(bytes)),
+            Raw::Text(text) => ByteSize::parse(&text).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Values given on the command line; `None` keeps the configured value.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub project_name: Option<String>,
+    pub exclude_patterns: Vec<String>,
+    pub languages: Option<Vec<String>>,
+    pub patterns: Option<bool>,
+    pub embeddings: Option<bool>,
+    pub port: Option<u16>,
+    pub target: Option<ServerTarget>,
+    pub template_dir: Option<PathBuf>,
+}
+
+impl ConfigSource for ConfigOverrides {
+    fn apply(&self, config: &mut ForgeConfig) -> McpResult<()> {
+        if let Some(name) = &self.project_name {
+            config.project.name = name.clone();
+        }
+        config.indexing.exclude_patterns.extend(self.exclude_patterns.iter().cloned());
+        if let Some(languages) = &self.languages {
+            config.indexing.languages = languages.clone();
+        }
+        if let Some(patterns) = self.patterns {
+            config.analysis.patterns = patterns;
+        }
+        if let Some(embeddings) = self.embeddings {
+            config.analysis.embeddings = embeddings;
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(target) = self.target {
+            config.server.target = target;
+        }
+        if let Some(dir) = &self.template_dir {
+            config.server.template_dir = Some(dir.clone());
+        }
+        Ok(())
+    }
+}
+
+/// `MCP_FORGE_*` environment variables.
+pub struct EnvOverrides<F: Fn(&str) -> Option<String>> {
+    lookup: F,
+}
+
+impl EnvOverrides<fn(&str) -> Option<String>> {
+    pub fn from_process() -> Self {
+        Self { lookup: |key| std::env::var(key).ok() }
+    }
+}
+
+impl<F: Fn(&str) -> Option<String>> EnvOverrides<F> {
+    pub fn new(lookup: F) -> Self {
+        Self { lookup }
+    }
+
+    fn parsed<T: std::str::FromStr>(&self, key: &str) -> McpResult<Option<T>> {
+        match (self.lookup)(key) {
+            Some(raw) => raw
+                .trim()
+                .parse()
+                .map(Some)
+                .map_err(|_| McpError::Config(format!("{}={:?} is not a valid value", key, raw))),
+            None => Ok(None),
+        }
+    }
+
+    fn list(&self, key: &str) -> Option<Vec<String>> {
+        (self.lookup)(key).map(|raw| {
+            raw.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+        })
+    }
+}
+
+impl<F: Fn(&str) -> Option<String>> ConfigSource for EnvOverrides<F> {
+    fn apply(&self, config: &mut ForgeConfig) -> McpResult<()> {
+        if let Some(name) = (self.lookup)("MCP_FORGE_PROJECT_NAME") {
+            config.project.name = name;
+        }
+        if let Some(description) = (self.lookup)("MCP_FORGE_PROJECT_DESCRIPTION") {
+            config.project.description = description;
+        }
+        if let Some(patterns) = self.list("MCP_FORGE_EXCLUDE_PATTERNS") {
+            config.indexing.exclude_patterns.extend(patterns);
+        }
+        if let Some(raw) = (self.lookup)("MCP_FORGE_MAX_FILE_SIZE") {
+            config.indexing.max_file_size = ByteSize::parse(&raw)
+                .map_err(|e| McpError::Config(format!("MCP_FORGE_MAX_FILE_SIZE: {}", e)))?;
+        }
+        if let Some(languages) = self.list("MCP_FORGE_LANGUAGES") {
+            config.indexing.languages = languages;
+        }
+        if let Some(timeout) = self.parsed("MCP_FORGE_PARSE_TIMEOUT_MS")? {
+            config.indexing.parse_timeout_ms = timeout;
+        }
+        if let Some(ratio) = self.parsed("MCP_FORGE_MAX_ERROR_RATIO")? {
+            config.indexing.max_error_ratio = ratio;
+        }
+        if let Some(skip) = self.parsed("MCP_FORGE_SKIP_GENERATED")? {
+            config.indexing.skip_generated = skip;
+        }
+        if let Some(skip) = self.parsed("MCP_FORGE_SKIP_MINIFIED")? {
+            config.indexing.skip_minified = skip;
+        }
+        if let Some(patterns) = self.parsed("MCP_FORGE_PATTERNS")? {
+            config.analysis.patterns = patterns;
+        }
+        if let Some(embeddings) = self.parsed("MCP_FORGE_EMBEDDINGS")? {
+            config.analysis.embeddings = embeddings;
+        }
+        if let Some(threshold) = self.parsed("MCP_FORGE_COMPLEXITY_THRESHOLD")? {
+            config.analysis.complexity_threshold = threshold;
+        }
+        if let Some(port) = self.parsed("MCP_FORGE_PORT")? {
+            config.server.port = port;
+        }
+        if let Some(auto_reload) = self.parsed("MCP_FORGE_AUTO_RELOAD")? {
+            config.server.auto_reload = auto_reload;
+        }
+        if let Some(target) = self.parsed("MCP_FORGE_TARGET")? {
+            config.server.target = target;
+        }
+        if let Some(dir) = (self.lookup)("MCP_FORGE_TEMPLATE_DIR") {
+            config.server.template_dir = Some(PathBuf::from(dir));
+        }
+        Ok(())
+    }
+}
+
+impl ForgeConfig {
+    /// Find the config file in `source`, if any.
+    pub fn discover(source: &Path) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES.iter().map(|name| source.join(name)).find(|path| path.is_file())
+    }
+
+    pub fn from_file(path: &Path) -> McpResult<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| McpError::Config(format!("Cannot read {}: {}", path.display(), e)))?;
+        Self::from_yaml(&raw).map_err(|e| match e {
+            McpError::Config(message) => McpError::Config(format!("{}: {}", path.display(), message)),
+            other => other,
+        })
+    }
+
+    pub fn from_yaml(raw: &str) -> McpResult<Self> {
+        if raw.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(raw).map_err(|e| McpError::Config(e.to_string()))
+    }
+
+    pub fn to_yaml(&self) -> McpResult<String> {
+        serde_yaml::to_string(self).map_err(|e| McpError::Config(e.to_string()))
+    }
+
+    /// Whether the file predates the current schema and `init` should offer an upgrade.
+    pub fn is_outdated(&self) -> bool {
+        self.version < CONFIG_VERSION
+    }
+
+    /// Resolve the effective configuration for `source`: defaults, then the
+    /// explicit or discovered config file, then environment, then CLI overrides.
+    pub fn load(source: &Path, explicit: Option<&Path>, overrides: &ConfigOverrides) -> McpResult<Self> {
+        let mut config = match explicit.map(Path::to_path_buf).or_else(|| Self::discover(source)) {
+            Some(path) => {
+                log::info!("Loading configuration from {}", path.display());
+                let mut config = Self::from_file(&path)?;
+                if let Some(base) = path.parent() {
+                    if let Some(dir) = &config.server.template_dir {
+                        config.server.template_dir = Some(base.join(dir));
+                    }
+                    for query in config.indexing.queries.values_mut() {
+                        query.path = base.join(&query.path);
+                    }
+                }
+                config
+            }
+            None => Self::default(),
+        };
+        EnvOverrides::from_process().apply(&mut config)?;
+        overrides.apply(&mut config)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> McpResult<()> {
+        let mut problems = Vec::new();
+        if self.version > CONFIG_VERSION {
+            problems.push(format!(
+                "version {} is newer than this mcp-forge supports ({}); upgrade mcp-forge",
+                self.version, CONFIG_VERSION
+            ));
+        }
+        if self.project.name.trim().is_empty() {
+            problems.push("project.name must not be empty".to_string());
+        }
+        let supported = supported_languages();
+        for language in &self.indexing.languages {
+            if !supported.contains(&language.as_str()) {
+                problems.push(format!(
+                    "indexing.languages: unsupported language {:?} (supported: {})",
+                    language,
+                    supported.join(", ")
+                ));
+            }
+        }
+        for language in self.indexing.queries.keys() {
+            if !supported.contains(&language.as_str()) {
+                problems.push(format!(
+                    "indexing.queries: unsupported language {:?} (supported: {})",
+                    language,
+                    supported.join(", ")
+                ));
+            }
+        }
+        if let Err(McpError::Config(problem)) = TagQueries::for_indexing(&self.indexing) {
+            problems.push(problem);
+        }
+        if !(0.0..=1.0).contains(&self.indexing.max_error_ratio) {
+            problems.push("indexing.max_error_ratio must be between 0.0 and 1.0".to_string());
+        }
+        if self.indexing.max_file_size.0 == 0 {
+            problems.push("indexing.max_file_size must be greater than zero".to_string());
+        }
+        let mut excludes = ignore::gitignore::GitignoreBuilder::new("");
+        for pattern in &self.indexing.exclude_patterns {
+            if let Err(e) = excludes.add_line(None, pattern) {
+                problems.push(format!("indexing.exclude_patterns: invalid pattern {:?}: {}", pattern, e));
+            }
+        }
+        if self.analysis.complexity_threshold == 0 {
+            problems.push("analysis.complexity_threshold must be greater than zero".to_string());
+        }
+        if self.server.port == 0 {
+            problems.push("server.port must be between 1 and 65535".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(McpError::Config(format!("Invalid configuration:\n  - {}", problems.join("\n  - "))))
+        }
+    }
+}
@@ -1,4 +1,93 @@
-mod.rs
-
 pub mod schema;
-pub mod queries;
\ No newline at end of file
+pub mod queries;
+pub mod records;
+
+use std::path::{Path, PathBuf};
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+use crate::error::{McpError, McpResult};
+
+/// Open the `database.sqlite` of a build output directory read-only.
+pub async fn open_index(output_dir: &Path) -> McpResult<SqlitePool> {
+    connect(output_dir, true).await
+}
+
+/// Open the `database.sqlite` of a build output directory for updates, as watch mode does.
+pub async fn open_index_writable(output_dir: &Path) -> McpResult<SqlitePool> {
+    connect(output_dir, false).await
+}
+
+async fn connect(output_dir: &Path, read_only: bool) -> McpResult<SqlitePool> {
+    let db_path = output_dir.join("database.sqlite");
+    if !db_path.is_file() {
+        return Err(McpError::Database(format!(
+            "No database found at {:?}; run `mcp-forge build` first",
+            db_path
+        )));
+    }
+    let options = SqliteConnectOptions::new().filename(&db_path).read_only(read_only);
+    SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Create an empty `database.sqlite` in a build output directory, replacing an existing one.
+pub async fn create_index(output_dir: &Path) -> McpResult<SqlitePool> {
+    let db_path = output_dir.join("database.sqlite");
+    std::fs::create_dir_all(output_dir).map_err(|e| McpError::Database(e.to_string()))?;
+    if db_path.exists() {
+        std::fs::remove_file(&db_path).map_err(|e| McpError::Database(e.to_string()))?;
+    }
+    let options = SqliteConnectOptions::new().filename(&db_path).create_if_missing(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    schema::create_schema(&pool).await?;
+    Ok(pool)
+}
+
+/// Project name recorded in the output directory's `config.json`.
+pub fn project_name(output_dir: &Path) -> String {
+    std::fs::read_to_string(output_dir.join("config.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|config| config["project"]["name"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "Project".to_string())
+}
+
+/// `analysis.complexity_threshold` the index was built with, as recorded in `config.json`.
+pub fn complexity_threshold(output_dir: &Path) -> i64 {
+    std::fs::read_to_string(output_dir.join("config.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|config| config["analysis"]["complexity_threshold"].as_i64())
+        .unwrap_or(10)
+}
+
+/// What the tools report about an index besides its database.
+#[derive(Debug, Clone)]
+pub struct IndexInfo {
+    pub project_name: String,
+    /// Default threshold of `analyze_complexity`.
+    pub complexity_threshold: i64,
+}
+
+impl IndexInfo {
+    pub fn new(project_name: impl Into<String>) -> Self {
+        Self { project_name: project_name.into(), complexity_threshold: 10 }
+    }
+
+    /// Read from the output directory's `config.json`.
+    pub fn load(output_dir: &Path) -> Self {
+        Self { project_name: project_name(output_dir), complexity_threshold: complexity_threshold(output_dir) }
+    }
+}
+
+/// Project root the index was built from, as recorded in `config.json`.
+pub fn source_root(output_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_to_string(output_dir.join("config.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|config| config["project"]["root"].as_str().map(PathBuf::from))
+}
@@ -1,6 +1,14 @@
 /// Trait for database queries, following SDK standards.
 pub trait DatabaseQueries {
+    #[allow(clippy::too_many_arguments)]
     fn insert_symbol(&self, pool: &sqlx::SqlitePool, id: &str, name: &str, kind: &str, file_path: &str, start_line: i32, end_line: i32, scope: &str, documentation: Option<&str>, signature: Option<&str>) -> crate::error::McpResult<()>;
+    fn set_symbol_region(&self, pool: &sqlx::SqlitePool, id: &str, kind: &str, index: i64) -> crate::error::McpResult<()>;
+    fn set_symbol_scope(&self, pool: &sqlx::SqlitePool, id: &str, qualified_name: &str, parent_id: Option<&str>) -> crate::error::McpResult<()>;
+    fn set_symbol_callable(&self, pool: &sqlx::SqlitePool, id: &str, signature: &crate::database::records::Signature) -> crate::error::McpResult<()>;
+    fn get_parameters(&self, pool: &sqlx::SqlitePool, symbol_id: &str) -> crate::error::McpResult<Vec<Parameter>>;
+    fn set_symbol_doc_tags(&self, pool: &sqlx::SqlitePool, id: &str, doc_tags: &crate::database::records::DocComment) -> crate::error::McpResult<()>;
+    fn set_symbol_type(&self, pool: &sqlx::SqlitePool, id: &str, type_name: &str) -> crate::error::McpResult<()>;
+    fn set_symbol_attributes(&self, pool: &sqlx::SqlitePool, id: &str, visibility: Option<&str>, attributes: &[String]) -> crate::error::McpResult<()>;
     fn get_symbol(&self, pool: &sqlx::SqlitePool, id: &str) -> crate::error::McpResult<Option<Symbol>>;
     fn insert_relationship(&self, pool: &sqlx::SqlitePool, from_symbol_id: &str, to_symbol_id: &str, kind: &str, strength: f64) -> crate::error::McpResult<()>;
     fn get_relationships(&self, pool: &sqlx::SqlitePool, symbol_id: &str) -> crate::error::McpResult<Vec<Relationship>>;
@@ -8,11 +16,156 @@ pub trait DatabaseQueries {
     fn get_embeddings(&self, pool: &sqlx::SqlitePool, symbol_id: &str) -> crate::error::McpResult<Vec<Embedding>>;
     fn insert_pattern(&self, pool: &sqlx::SqlitePool, name: &str, pattern_type: &str, occurrences: i32, locations: &str) -> crate::error::McpResult<()>;
     fn get_patterns(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<Pattern>>;
+    fn find_symbols(&self, pool: &sqlx::SqlitePool, name: &str, kind: Option<&str>, limit: i64) -> crate::error::McpResult<Vec<Symbol>>;
+    fn get_dependents(&self, pool: &sqlx::SqlitePool, symbol_id: &str) -> crate::error::McpResult<Vec<Relationship>>;
+    fn get_all_symbols(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<Symbol>>;
+    fn count_symbols(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<i64>;
+    fn get_symbols_page(&self, pool: &sqlx::SqlitePool, offset: i64, limit: i64) -> crate::error::McpResult<Vec<Symbol>>;
+    fn get_symbols_in_file(&self, pool: &sqlx::SqlitePool, file_path: &str) -> crate::error::McpResult<Vec<Symbol>>;
+    fn get_child_symbols(&self, pool: &sqlx::SqlitePool, parent_id: &str) -> crate::error::McpResult<Vec<Symbol>>;
+    fn get_all_relationships(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<Relationship>>;
+    fn get_all_embeddings(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<Embedding>>;
+    fn count_symbols_by_kind(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<(String, i64)>>;
+    fn get_dangling_relationships(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<Relationship>>;
+    fn count_orphan_embeddings(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<i64>;
+    fn insert_file(&self, pool: &sqlx::SqlitePool, file: &FileRecord) -> crate::error::McpResult<()>;
+    fn insert_parse_diagnostics(&self, pool: &sqlx::SqlitePool, file_path: &str, errors: &[crate::database::records::SyntaxError]) -> crate::error::McpResult<()>;
+    fn delete_file_symbols(&self, pool: &sqlx::SqlitePool, file_path: &str) -> crate::error::McpResult<()>;
+    fn delete_orphans(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<()>;
+    fn delete_file(&self, pool: &sqlx::SqlitePool, path: &str) -> crate::error::McpResult<()>;
+    fn get_files(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<Vec<FileRecord>>;
+    fn get_file(&self, pool: &sqlx::SqlitePool, path: &str) -> crate::error::McpResult<Option<FileRecord>>;
+    fn count_indexed_files(&self, pool: &sqlx::SqlitePool) -> crate::error::McpResult<i64>;
+    fn get_indexed_files_page(&self, pool: &sqlx::SqlitePool, offset: i64, limit: i64) -> crate::error::McpResult<Vec<(String, i64)>>;
+    fn get_parse_diagnostics(&self, pool: &sqlx::SqlitePool, file_path: &str) -> crate::error::McpResult<Vec<ParseDiagnostic>>;
 }
-use sqlx::{query, query_as, SqlitePool};
+use serde::Serialize;
+use sqlx::{query, query_as, Acquire, FromRow, Sqlite, SqlitePool};
+use crate::database::records::{DocComment, Signature, SyntaxError};
 use crate::error::{McpError, McpResult};
 
-pub async fn insert_symbol(pool: &SqlitePool, id: &str, name: &str, kind: &str, file_path: &str, start_line: i32, end_line: i32, scope: &str, documentation: Option<&str>, signature: Option<&str>) -> McpResult<()> {
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Symbol {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub start_line: Option<i64>,
+    pub end_line: Option<i64>,
+    pub scope: Option<String>,
+    pub documentation: Option<String>,
+    /// JSON of the parsed documentation, see [`DocComment`].
+    #[serde(serialize_with = "json_text")]
+    pub doc_tags: Option<String>,
+    pub signature: Option<String>,
+    /// `block`, `cell` or `script` for code embedded in `file_path`.
+    pub region_kind: Option<String>,
+    pub region_index: Option<i64>,
+    /// Module path and enclosing definitions, e.g. `app.models.User.save`.
+    pub qualified_name: Option<String>,
+    /// Id of the enclosing definition; `None` at the top level of a file.
+    pub parent_id: Option<String>,
+    /// Visibility as written, e.g. `pub(crate)`.
+    pub visibility: Option<String>,
+    /// JSON array of attributes, e.g. `["derive(Debug)"]`; `None` when there are none.
+    #[serde(serialize_with = "json_text")]
+    pub attributes: Option<String>,
+    /// Return type of a function or method as written.
+    pub return_type: Option<String>,
+    /// JSON array of type parameters, e.g. `["T: Clone"]`; `None` when there are none.
+    #[serde(serialize_with = "json_text")]
+    pub type_parameters: Option<String>,
+    /// JSON array of modifiers, e.g. `["async", "static"]`; `None` when there are none.
+    #[serde(serialize_with = "json_text")]
+    pub modifiers: Option<String>,
+    /// Declared type of a field, attribute or constant.
+    pub type_name: Option<String>,
+}
+
+/// Serialize a JSON column as the value it holds rather than as a string.
+fn json_text<S: serde::Serializer>(text: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    let value = text.as_deref().and_then(|text| serde_json::from_str::<serde_json::Value>(text).ok());
+    value.serialize(serializer)
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Relationship {
+    pub id: i64,
+    pub from_symbol_id: Option<String>,
+    pub to_symbol_id: Option<String>,
+    pub kind: Option<String>,
+    pub strength: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Embedding {
+    pub id: i64,
+    pub symbol_id: Option<String>,
+    #[serde(skip)]
+    pub embedding: Option<Vec<u8>>,
+    pub content: Option<String>,
+    pub model_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Pattern {
+    pub id: i64,
+    pub name: Option<String>,
+    pub pattern_type: Option<String>,
+    pub occurrences: Option<i64>,
+    pub locations: Option<String>,
+}
+
+/// A discovered file and how well it parsed.
+#[derive(Debug, Clone, Default, Serialize, FromRow)]
+pub struct FileRecord {
+    pub path: String,
+    pub language: Option<String>,
+    pub lines: Option<i64>,
+    pub error_count: i64,
+    pub error_ratio: f64,
+    pub timed_out: bool,
+    /// Why the file is not indexed; `None` when its symbols are.
+    pub skip_reason: Option<String>,
+    /// `syntax_errors`, `timed_out`, `too_large`, `binary`, `minified`, `generated`, `unreadable` or `failed`.
+    pub skip_kind: Option<String>,
+    /// Encoding the file was decoded from; `lossy` when invalid bytes were replaced.
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ParseDiagnostic {
+    pub id: i64,
+    pub file_path: String,
+    pub kind: String,
+    pub start_line: Option<i64>,
+    pub start_column: Option<i64>,
+    pub end_line: Option<i64>,
+    pub end_column: Option<i64>,
+    pub message: Option<String>,
+}
+
+/// A parameter of a function or method, in declaration order.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Parameter {
+    #[serde(skip)]
+    pub id: i64,
+    #[serde(skip)]
+    pub symbol_id: String,
+    pub position: i64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    #[serde(rename = "default")]
+    pub default_value: Option<String>,
+    /// `positional`, `optional`, `receiver`, `variadic`, `keywords` or `keyword_only`.
+    pub kind: String,
+}
+
+/// Like every write below, takes a pool or, to group writes, a transaction (`&mut *tx`).
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_symbol(conn: impl Acquire<'_, Database = Sqlite>, id: &str, name: &str, kind: &str, file_path: &str, start_line: i32, end_line: i32, scope: &str, documentation: Option<&str>, signature: Option<&str>) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
     query("INSERT INTO symbols (id, name, kind, file_path, start_line, end_line, scope, documentation, signature) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)")
         .bind(id)
         .bind(name)
@@ -23,7 +176,117 @@ pub async fn insert_symbol(pool: &SqlitePool, id: &str, name: &str, kind: &str,
         .bind(scope)
         .bind(documentation)
         .bind(signature)
-        .execute(pool)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Mark a symbol as coming from a code block, notebook cell or script block of its file.
+pub async fn set_symbol_region(conn: impl Acquire<'_, Database = Sqlite>, id: &str, kind: &str, index: i64) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    query("UPDATE symbols SET region_kind = ?, region_index = ? WHERE id = ?")
+        .bind(kind)
+        .bind(index)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record where a symbol sits in the hierarchy of its file.
+pub async fn set_symbol_scope(conn: impl Acquire<'_, Database = Sqlite>, id: &str, qualified_name: &str, parent_id: Option<&str>) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    query("UPDATE symbols SET qualified_name = ?, parent_id = ? WHERE id = ?")
+        .bind(qualified_name)
+        .bind(parent_id)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record the structured signature of a function or method, replacing its parameters.
+pub async fn set_symbol_callable(conn: impl Acquire<'_, Database = Sqlite>, id: &str, signature: &Signature) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    let json_list = |items: &[String]| -> McpResult<Option<String>> {
+        if items.is_empty() {
+            return Ok(None);
+        }
+        serde_json::to_string(items).map(Some).map_err(|e| McpError::Database(e.to_string()))
+    };
+    query("UPDATE symbols SET return_type = ?, type_parameters = ?, modifiers = ? WHERE id = ?")
+        .bind(signature.return_type.as_deref())
+        .bind(json_list(&signature.type_parameters)?)
+        .bind(json_list(&signature.modifiers)?)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    query("DELETE FROM parameters WHERE symbol_id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    for (position, parameter) in signature.parameters.iter().enumerate() {
+        query("INSERT INTO parameters (symbol_id, position, name, type_name, default_value, kind) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(id)
+            .bind(position as i64)
+            .bind(&parameter.name)
+            .bind(parameter.type_name.as_deref())
+            .bind(parameter.default.as_deref())
+            .bind(parameter.kind.as_str())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| McpError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+pub async fn get_parameters(pool: &SqlitePool, symbol_id: &str) -> McpResult<Vec<Parameter>> {
+    query_as::<_, Parameter>("SELECT * FROM parameters WHERE symbol_id = ? ORDER BY position")
+        .bind(symbol_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Record the parsed documentation of a symbol.
+pub async fn set_symbol_doc_tags(conn: impl Acquire<'_, Database = Sqlite>, id: &str, doc_tags: &DocComment) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    let doc_tags = serde_json::to_string(doc_tags).map_err(|e| McpError::Database(e.to_string()))?;
+    query("UPDATE symbols SET doc_tags = ? WHERE id = ?")
+        .bind(doc_tags)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record a symbol's visibility and attributes.
+pub async fn set_symbol_attributes(conn: impl Acquire<'_, Database = Sqlite>, id: &str, visibility: Option<&str>, attributes: &[String]) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    let attributes = if attributes.is_empty() { None } else { Some(serde_json::to_string(attributes).map_err(|e| McpError::Database(e.to_string()))?) };
+    query("UPDATE symbols SET visibility = ?, attributes = ? WHERE id = ?")
+        .bind(visibility)
+        .bind(attributes)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record the declared type of a field, attribute or constant.
+pub async fn set_symbol_type(conn: impl Acquire<'_, Database = Sqlite>, id: &str, type_name: &str) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    query("UPDATE symbols SET type_name = ? WHERE id = ?")
+        .bind(type_name)
+        .bind(id)
+        .execute(&mut *conn)
         .await
         .map_err(|e| McpError::Database(e.to_string()))?;
     Ok(())
@@ -37,13 +300,14 @@ pub async fn get_symbol(pool: &SqlitePool, id: &str) -> McpResult<Option<Symbol>
         .map_err(|e| McpError::Database(e.to_string()))
 }
 
-pub async fn insert_relationship(pool: &SqlitePool, from_symbol_id: &str, to_symbol_id: &str, kind: &str, strength: f64) -> McpResult<()> {
+pub async fn insert_relationship(conn: impl Acquire<'_, Database = Sqlite>, from_symbol_id: &str, to_symbol_id: &str, kind: &str, strength: f64) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
     query("INSERT INTO relationships (from_symbol_id, to_symbol_id, kind, strength) VALUES (?, ?, ?, ?)")
         .bind(from_symbol_id)
         .bind(to_symbol_id)
         .bind(kind)
         .bind(strength)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| McpError::Database(e.to_string()))?;
     Ok(())
@@ -57,13 +321,14 @@ pub async fn get_relationships(pool: &SqlitePool, symbol_id: &str) -> McpResult<
         .map_err(|e| McpError::Database(e.to_string()))
 }
 
-pub async fn insert_embedding(pool: &SqlitePool, symbol_id: &str, embedding: Vec<u8>, content: &str, model_name: &str) -> McpResult<()> {
+pub async fn insert_embedding(conn: impl Acquire<'_, Database = Sqlite>, symbol_id: &str, embedding: Vec<u8>, content: &str, model_name: &str) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
     query("INSERT INTO embeddings (symbol_id, embedding, content, model_name) VALUES (?, ?, ?, ?)")
         .bind(symbol_id)
         .bind(embedding)
         .bind(content)
         .bind(model_name)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| McpError::Database(e.to_string()))?;
     Ok(())
@@ -77,13 +342,14 @@ pub async fn get_embeddings(pool: &SqlitePool, symbol_id: &str) -> McpResult<Vec
         .map_err(|e| McpError::Database(e.to_string()))
 }
 
-pub async fn insert_pattern(pool: &SqlitePool, name: &str, pattern_type: &str, occurrences: i32, locations: &str) -> McpResult<()> {
+pub async fn insert_pattern(conn: impl Acquire<'_, Database = Sqlite>, name: &str, pattern_type: &str, occurrences: i32, locations: &str) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
     query("INSERT INTO patterns (name, pattern_type, occurrences, locations) VALUES (?, ?, ?, ?)")
         .bind(name)
         .bind(pattern_type)
         .bind(occurrences)
         .bind(locations)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| McpError::Database(e.to_string()))?;
     Ok(())
@@ -94,4 +360,240 @@ pub async fn get_patterns(pool: &SqlitePool) -> McpResult<Vec<Pattern>> {
         .fetch_all(pool)
         .await
         .map_err(|e| McpError::Database(e.to_string()))
-}
\ No newline at end of file
+}
+
+/// Find symbols whose name or qualified name contains `name`, optionally
+/// restricted to one kind. Exact matches come first.
+pub async fn find_symbols(pool: &SqlitePool, name: &str, kind: Option<&str>, limit: i64) -> McpResult<Vec<Symbol>> {
+    query_as::<_, Symbol>("SELECT * FROM symbols WHERE (name LIKE ? OR qualified_name LIKE ?) AND (? IS NULL OR kind = ?) ORDER BY name = ? OR qualified_name = ? DESC, length(name), file_path LIMIT ?")
+        .bind(format!("%{}%", name))
+        .bind(format!("%{}%", name))
+        .bind(kind)
+        .bind(kind)
+        .bind(name)
+        .bind(name)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Relationships pointing at `symbol_id` (who depends on this symbol).
+pub async fn get_dependents(pool: &SqlitePool, symbol_id: &str) -> McpResult<Vec<Relationship>> {
+    query_as::<_, Relationship>("SELECT * FROM relationships WHERE to_symbol_id = ?")
+        .bind(symbol_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn get_all_symbols(pool: &SqlitePool) -> McpResult<Vec<Symbol>> {
+    query_as::<_, Symbol>("SELECT * FROM symbols ORDER BY file_path, start_line")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn count_symbols(pool: &SqlitePool) -> McpResult<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM symbols")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// `limit` symbols after the first `offset`, in the order of [`get_all_symbols`].
+pub async fn get_symbols_page(pool: &SqlitePool, offset: i64, limit: i64) -> McpResult<Vec<Symbol>> {
+    query_as::<_, Symbol>("SELECT * FROM symbols ORDER BY file_path, start_line, id LIMIT ? OFFSET ?")
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Symbols defined in one file, in source order.
+pub async fn get_symbols_in_file(pool: &SqlitePool, file_path: &str) -> McpResult<Vec<Symbol>> {
+    query_as::<_, Symbol>("SELECT * FROM symbols WHERE file_path = ? ORDER BY start_line")
+        .bind(file_path)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Symbols directly enclosed by `parent_id`, in source order.
+pub async fn get_child_symbols(pool: &SqlitePool, parent_id: &str) -> McpResult<Vec<Symbol>> {
+    query_as::<_, Symbol>("SELECT * FROM symbols WHERE parent_id = ? ORDER BY start_line")
+        .bind(parent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn get_all_relationships(pool: &SqlitePool) -> McpResult<Vec<Relationship>> {
+    query_as::<_, Relationship>("SELECT * FROM relationships")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn get_all_embeddings(pool: &SqlitePool) -> McpResult<Vec<Embedding>> {
+    query_as::<_, Embedding>("SELECT * FROM embeddings")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn count_symbols_by_kind(pool: &SqlitePool) -> McpResult<Vec<(String, i64)>> {
+    query_as::<_, (String, i64)>("SELECT kind, COUNT(*) FROM symbols GROUP BY kind ORDER BY COUNT(*) DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Relationships whose `to_symbol_id` names no indexed symbol.
+pub async fn get_dangling_relationships(pool: &SqlitePool) -> McpResult<Vec<Relationship>> {
+    query_as::<_, Relationship>("SELECT * FROM relationships WHERE to_symbol_id IS NOT NULL AND to_symbol_id NOT IN (SELECT id FROM symbols) ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Embeddings whose symbol is no longer indexed.
+pub async fn count_orphan_embeddings(pool: &SqlitePool) -> McpResult<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM embeddings WHERE symbol_id IS NULL OR symbol_id NOT IN (SELECT id FROM symbols)")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn insert_file(conn: impl Acquire<'_, Database = Sqlite>, file: &FileRecord) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    query("INSERT OR REPLACE INTO files (path, language, lines, error_count, error_ratio, timed_out, skip_reason, skip_kind, encoding) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)")
+        .bind(&file.path)
+        .bind(&file.language)
+        .bind(file.lines)
+        .bind(file.error_count)
+        .bind(file.error_ratio)
+        .bind(file.timed_out)
+        .bind(&file.skip_reason)
+        .bind(&file.skip_kind)
+        .bind(&file.encoding)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Record the syntax errors of one file, replacing earlier ones.
+pub async fn insert_parse_diagnostics(conn: impl Acquire<'_, Database = Sqlite>, file_path: &str, errors: &[SyntaxError]) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    query("DELETE FROM parse_diagnostics WHERE file_path = ?")
+        .bind(file_path)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    for error in errors {
+        query("INSERT INTO parse_diagnostics (file_path, kind, start_line, start_column, end_line, end_column, message) VALUES (?, ?, ?, ?, ?, ?, ?)")
+            .bind(file_path)
+            .bind(error.kind.as_str())
+            .bind(error.start_line as i64)
+            .bind(error.start_column as i64)
+            .bind(error.end_line as i64)
+            .bind(error.end_column as i64)
+            .bind(&error.message)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| McpError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Remove the symbols of one file with their parameters and outgoing relationships.
+/// Other rows may still point at them: use a transaction with `PRAGMA defer_foreign_keys`
+/// and [`delete_orphans`] before committing.
+pub async fn delete_file_symbols(conn: impl Acquire<'_, Database = Sqlite>, file_path: &str) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    for statement in [
+        "DELETE FROM parameters WHERE symbol_id IN (SELECT id FROM symbols WHERE file_path = ?)",
+        "DELETE FROM relationships WHERE from_symbol_id IN (SELECT id FROM symbols WHERE file_path = ?)",
+        "DELETE FROM symbols WHERE file_path = ?",
+    ] {
+        query(statement)
+            .bind(file_path)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| McpError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Remove relationships and embeddings whose symbols no longer exist.
+pub async fn delete_orphans(conn: impl Acquire<'_, Database = Sqlite>) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    for statement in [
+        "DELETE FROM relationships WHERE from_symbol_id NOT IN (SELECT id FROM symbols) OR to_symbol_id NOT IN (SELECT id FROM symbols)",
+        "DELETE FROM embeddings WHERE symbol_id NOT IN (SELECT id FROM symbols)",
+    ] {
+        query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| McpError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Forget a deleted file and its syntax errors.
+pub async fn delete_file(conn: impl Acquire<'_, Database = Sqlite>, path: &str) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    for statement in ["DELETE FROM parse_diagnostics WHERE file_path = ?", "DELETE FROM files WHERE path = ?"] {
+        query(statement)
+            .bind(path)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| McpError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Every recorded file, those with syntax errors or skipped first.
+pub async fn get_files(pool: &SqlitePool) -> McpResult<Vec<FileRecord>> {
+    query_as::<_, FileRecord>("SELECT * FROM files ORDER BY skip_reason IS NULL, error_ratio DESC, path")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+pub async fn get_file(pool: &SqlitePool, path: &str) -> McpResult<Option<FileRecord>> {
+    query_as::<_, FileRecord>("SELECT * FROM files WHERE path = ?")
+        .bind(path)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Files whose symbols are indexed, that is, not skipped.
+pub async fn count_indexed_files(pool: &SqlitePool) -> McpResult<i64> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM files WHERE skip_reason IS NULL")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// `limit` indexed files after the first `offset`, by path, each with its symbol count.
+pub async fn get_indexed_files_page(pool: &SqlitePool, offset: i64, limit: i64) -> McpResult<Vec<(String, i64)>> {
+    query_as::<_, (String, i64)>("SELECT path, (SELECT COUNT(*) FROM symbols WHERE symbols.file_path = files.path) FROM files WHERE skip_reason IS NULL ORDER BY path LIMIT ? OFFSET ?")
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Syntax errors of one file, in source order.
+pub async fn get_parse_diagnostics(pool: &SqlitePool, file_path: &str) -> McpResult<Vec<ParseDiagnostic>> {
+    query_as::<_, ParseDiagnostic>("SELECT * FROM parse_diagnostics WHERE file_path = ? ORDER BY start_line, start_column")
+        .bind(file_path)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
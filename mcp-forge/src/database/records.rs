@@ -0,0 +1,142 @@
+//! Plain records the analyzer produces and the database stores.
+//!
+//! They carry no tree-sitter types, so the generated Rust server can ship
+//! the database module without the analyzer.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyntaxErrorKind {
+    /// Text that could not be parsed (`ERROR` node).
+    Error,
+    /// A token the parser assumed to recover (`MISSING` node).
+    Missing,
+}
+
+impl SyntaxErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SyntaxErrorKind::Error => "error",
+            SyntaxErrorKind::Missing => "missing",
+        }
+    }
+}
+
+/// One `ERROR` or `MISSING` node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntaxError {
+    pub kind: SyntaxErrorKind,
+    /// 1-based, like symbol lines.
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    #[serde(skip)]
+    pub byte_range: Range<usize>,
+    /// What was missing, or the start of the unparsable text.
+    pub message: String,
+}
+
+/// Documentation of a symbol as parsed by the analyzer, stored as `doc_tags`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocComment {
+    /// First paragraph, on one line.
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<DocParam>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returns: Option<DocValue>,
+    /// Exceptions or errors thrown, with the type they are documented as.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throws: Vec<DocValue>,
+    /// Other sections and tags by lowercase name: `errors`, `panics`,
+    /// `examples`, `deprecated`, `since`, ...
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sections: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocParam {
+    pub name: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocValue {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    pub description: String,
+}
+
+/// Signature of a function or method; its parameters are stored as rows of `parameters`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Signature {
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+    /// Generic parameters as written, bounds included: `T: Clone`, `K extends string`.
+    pub type_parameters: Vec<String>,
+    /// `async`, `static`, `abstract`, `const`, `unsafe`, `generator` and the
+    /// like, in source order.
+    pub modifiers: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    /// Default value as written.
+    pub default: Option<String>,
+    pub kind: ParameterKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterKind {
+    Positional,
+    /// TypeScript `name?: T`.
+    Optional,
+    /// `self`, `this`, or a Go method receiver.
+    Receiver,
+    /// `*args`, `...rest`, `String... names`, `names ...string`.
+    Variadic,
+    /// Python `**kwargs`.
+    Keywords,
+    /// Python parameters after `*` or `*args`.
+    KeywordOnly,
+}
+
+impl ParameterKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ParameterKind::Positional => "positional",
+            ParameterKind::Optional => "optional",
+            ParameterKind::Receiver => "receiver",
+            ParameterKind::Variadic => "variadic",
+            ParameterKind::Keywords => "keywords",
+            ParameterKind::KeywordOnly => "keyword_only",
+        }
+    }
+}
+
+impl Parameter {
+    pub fn new(name: impl Into<String>, kind: ParameterKind) -> Self {
+        Self { name: name.into(), type_name: None, default: None, kind }
+    }
+
+    pub fn typed(mut self, type_name: Option<String>) -> Self {
+        self.type_name = type_name;
+        self
+    }
+
+    pub fn with_default(mut self, default: Option<String>) -> Self {
+        self.default = default;
+        self
+    }
+}
@@ -1,4 +1,16 @@
-CREATE TABLE symbols (
+//! SQLite schema for the MCP-Forge knowledge base.
+
+use sqlx::SqlitePool;
+use crate::error::{McpError, McpResult};
+
+/// Stored in `PRAGMA user_version`; bump when the tables below change.
+pub const SCHEMA_VERSION: i64 = 9;
+
+/// Tables every index must contain.
+pub const TABLES: &[&str] = &["symbols", "relationships", "embeddings", "patterns", "files", "parse_diagnostics", "parameters"];
+
+pub const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS symbols (
     id TEXT PRIMARY KEY,
     name TEXT NOT NULL,
     kind TEXT NOT NULL,
@@ -7,10 +19,37 @@ CREATE TABLE symbols (
     end_line INTEGER,
     scope TEXT,
     documentation TEXT,
-    signature TEXT
+    doc_tags TEXT,
+    signature TEXT,
+    region_kind TEXT,
+    region_index INTEGER,
+    qualified_name TEXT,
+    parent_id TEXT,
+    visibility TEXT,
+    attributes TEXT,
+    return_type TEXT,
+    type_parameters TEXT,
+    modifiers TEXT,
+    type_name TEXT
 );
 
-CREATE TABLE relationships (
+CREATE INDEX IF NOT EXISTS idx_symbols_parent ON symbols(parent_id);
+CREATE INDEX IF NOT EXISTS idx_symbols_file ON symbols(file_path);
+
+CREATE TABLE IF NOT EXISTS parameters (
+    id INTEGER PRIMARY KEY,
+    symbol_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    type_name TEXT,
+    default_value TEXT,
+    kind TEXT NOT NULL,
+    FOREIGN KEY (symbol_id) REFERENCES symbols(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_parameters_symbol ON parameters(symbol_id);
+
+CREATE TABLE IF NOT EXISTS relationships (
     id INTEGER PRIMARY KEY,
     from_symbol_id TEXT,
     to_symbol_id TEXT,
@@ -20,7 +59,7 @@ CREATE TABLE relationships (
     FOREIGN KEY (to_symbol_id) REFERENCES symbols(id)
 );
 
-CREATE TABLE embeddings (
+CREATE TABLE IF NOT EXISTS embeddings (
     id INTEGER PRIMARY KEY,
     symbol_id TEXT,
     embedding BLOB,
@@ -29,10 +68,65 @@ CREATE TABLE embeddings (
     FOREIGN KEY (symbol_id) REFERENCES symbols(id)
 );
 
-CREATE TABLE patterns (
+CREATE TABLE IF NOT EXISTS patterns (
     id INTEGER PRIMARY KEY,
     name TEXT,
     pattern_type TEXT,
     occurrences INTEGER,
     locations TEXT
-);
\ No newline at end of file
+);
+
+CREATE TABLE IF NOT EXISTS files (
+    path TEXT PRIMARY KEY,
+    language TEXT,
+    lines INTEGER,
+    error_count INTEGER NOT NULL DEFAULT 0,
+    error_ratio REAL NOT NULL DEFAULT 0,
+    timed_out INTEGER NOT NULL DEFAULT 0,
+    skip_reason TEXT,
+    skip_kind TEXT,
+    encoding TEXT
+);
+
+CREATE TABLE IF NOT EXISTS parse_diagnostics (
+    id INTEGER PRIMARY KEY,
+    file_path TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    start_line INTEGER,
+    start_column INTEGER,
+    end_line INTEGER,
+    end_column INTEGER,
+    message TEXT,
+    FOREIGN KEY (file_path) REFERENCES files(path)
+);
+"#;
+
+/// Create all tables in an empty (or partially initialized) database.
+pub async fn create_schema(pool: &SqlitePool) -> McpResult<()> {
+    sqlx::raw_sql(SCHEMA)
+        .execute(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    sqlx::raw_sql(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+        .execute(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(())
+}
+
+/// Schema version recorded in the database; 0 for indexes written before versioning.
+pub async fn schema_version(pool: &SqlitePool) -> McpResult<i64> {
+    sqlx::query_scalar::<_, i64>("PRAGMA user_version")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))
+}
+
+/// Names of [`TABLES`] missing from the database.
+pub async fn missing_tables(pool: &SqlitePool) -> McpResult<Vec<&'static str>> {
+    let present: Vec<String> = sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| McpError::Database(e.to_string()))?;
+    Ok(TABLES.iter().copied().filter(|table| !present.iter().any(|name| name == table)).collect())
+}
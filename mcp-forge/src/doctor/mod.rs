@@ -0,0 +1,292 @@
+//! Environment and index health checks for `mcp-forge doctor`.
+//!
+//! Each check reports what it found and, when something is wrong, the command
+//! or setting that fixes it. Checks never stop early: a broken config still
+//! lets the remaining checks run against the defaults.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::{ConfigOverrides, ForgeConfig};
+use crate::database::{self, queries, schema};
+use crate::embeddings::generator::{EmbeddingGenerator, DEFAULT_MODEL, PYTHON};
+use crate::error::McpResult;
+use crate::server::templates::{ServerTarget, TemplateSet, TemplateVars};
+use crate::table::render as table;
+
+/// Dangling relationships listed by id before the rest are summarised.
+const MAX_EXAMPLES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Ok,
+    /// Not broken, but something is missing or skipped.
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or failure.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: CheckStatus::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    /// True unless a check failed; warnings do not count.
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(|check| check.status != CheckStatus::Fail)
+    }
+
+    pub fn check(&self, name: &str) -> Option<&Check> {
+        self.checks.iter().find(|check| check.name == name)
+    }
+
+    pub fn render_table(&self) -> String {
+        let mut out = table(
+            &["STATUS", "CHECK", "DETAIL"],
+            self.checks.iter().map(|c| vec![c.status.to_string(), c.name.to_string(), c.detail.clone()]),
+        );
+        let fixes: Vec<&Check> = self.checks.iter().filter(|c| c.fix.is_some()).collect();
+        if !fixes.is_empty() {
+            out.push_str("\nSuggested fixes:\n");
+            for check in fixes {
+                out.push_str(&format!("  {}: {}\n", check.name, check.fix.as_deref().unwrap_or_default()));
+            }
+        }
+        let failed = self.checks.iter().filter(|c| c.status == CheckStatus::Fail).count();
+        out.push_str(&match failed {
+            0 => "\nNo problems found.\n".to_string(),
+            n => format!("\n{} check(s) failed.\n", n),
+        });
+        out
+    }
+}
+
+/// Checks for one project and its build output.
+pub struct Doctor {
+    pub source: PathBuf,
+    pub output: PathBuf,
+    /// Config file to use instead of the one discovered in `source`.
+    pub config_file: Option<PathBuf>,
+    pub overrides: ConfigOverrides,
+}
+
+impl Doctor {
+    pub fn new(source: impl Into<PathBuf>, output: impl Into<PathBuf>) -> Self {
+        Self { source: source.into(), output: output.into(), config_file: None, overrides: ConfigOverrides::default() }
+    }
+
+    fn check_config(&self) -> (Check, ForgeConfig) {
+        match ForgeConfig::load(&self.source, self.config_file.as_deref(), &self.overrides) {
+            Ok(config) => {
+                let origin = self
+                    .config_file
+                    .clone()
+                    .or_else(|| ForgeConfig::discover(&self.source))
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| "built-in defaults".to_string());
+                let check = if config.is_outdated() {
+                    Check::warn("config", format!("{} uses config version {}", origin, config.version), "Run `mcp-forge init --upgrade`")
+                } else {
+                    Check::ok("config", origin)
+                };
+                (check, config)
+            }
+            Err(e) => (
+                Check::fail("config", e.to_string(), "Fix the reported fields in mcp-forge.yaml or the MCP_FORGE_* variables"),
+                ForgeConfig::default(),
+            ),
+        }
+    }
+
+    fn check_embeddings(&self, config: &ForgeConfig) -> Vec<Check> {
+        if !config.analysis.embeddings {
+            return vec![Check::ok("embeddings", "disabled by analysis.embeddings")];
+        }
+        let disable = "or disable embeddings with `--no-embeddings` / `analysis.embeddings: false`";
+        let python = match Command::new(PYTHON).arg("--version").output() {
+            Ok(out) if out.status.success() => {
+                let version = String::from_utf8_lossy(if out.stdout.is_empty() { &out.stderr } else { &out.stdout });
+                Check::ok("python", version.trim().to_string())
+            }
+            Ok(out) => Check::fail("python", format!("`{} --version` exited with {}", PYTHON, out.status), format!("Install Python 3, {}", disable)),
+            Err(e) => Check::fail("python", format!("`{}` not found: {}", PYTHON, e), format!("Install Python 3 and put `{}` on PATH, {}", PYTHON, disable)),
+        };
+        if python.status == CheckStatus::Fail {
+            return vec![python];
+        }
+
+        let generator = EmbeddingGenerator::new(self.output.clone(), DEFAULT_MODEL.to_string());
+        let backend = match Command::new(PYTHON).args(["-c", "import sentence_transformers"]).output() {
+            Ok(out) if out.status.success() => Check::ok("sentence-transformers", format!("importable, model {}", generator.model_name())),
+            Ok(out) => Check::fail(
+                "sentence-transformers",
+                String::from_utf8_lossy(&out.stderr).lines().last().unwrap_or("import failed").to_string(),
+                format!("Run `{} -m pip install -r {}`, {}", PYTHON, self.output.join("requirements.txt").display(), disable),
+            ),
+            Err(e) => Check::fail("sentence-transformers", e.to_string(), format!("Check the Python installation, {}", disable)),
+        };
+
+        let script = generator.script_path();
+        let script_check = if script.is_file() {
+            Check::ok("embedding script", script.display().to_string())
+        } else if config.server.target == ServerTarget::Python && !self.output.join("database.sqlite").is_file() {
+            Check::warn(
+                "embedding script",
+                format!("{} is written by the first build", script.display()),
+                format!("Run `mcp-forge build {} --output {}`", self.source.display(), self.output.display()),
+            )
+        } else if config.server.target == ServerTarget::Python {
+            Check::fail(
+                "embedding script",
+                format!("{} is missing", script.display()),
+                format!("Run `mcp-forge build {} --output {}`, {}", self.source.display(), self.output.display(), disable),
+            )
+        } else {
+            Check::fail(
+                "embedding script",
+                format!("{} is missing; only the python target ships it", script.display()),
+                format!("Build with `--target python`, {}", disable),
+            )
+        };
+        vec![python, backend, script_check]
+    }
+
+    fn check_templates(&self, config: &ForgeConfig) -> Check {
+        let fix = "Fix or remove `server.template_dir` / `--template-dir`";
+        let set = match TemplateSet::for_config(config) {
+            Ok(set) => set,
+            Err(e) => return Check::fail("templates", e.to_string(), fix),
+        };
+        match set.render_all(&TemplateVars::new(config, config.indexing.languages.clone())) {
+            Ok(files) => {
+                let origin = match &config.server.template_dir {
+                    Some(dir) => format!("built-in + {}", dir.display()),
+                    None => "built-in".to_string(),
+                };
+                Check::ok("templates", format!("{} {} files ({})", files.len(), config.server.target, origin))
+            }
+            Err(e) => Check::fail("templates", e.to_string(), fix),
+        }
+    }
+
+    /// Probe the output directory, or the nearest existing parent it would be created in.
+    fn check_output(&self) -> Check {
+        let fix = "Choose another `--output` directory or fix its permissions";
+        let dir = self.output.ancestors().find(|dir| dir.is_dir()).unwrap_or_else(|| Path::new("."));
+        let probe = dir.join(".mcp-forge-doctor");
+        match std::fs::write(&probe, b"") {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&probe);
+                if dir == self.output {
+                    Check::ok("output", format!("{} is writable", dir.display()))
+                } else {
+                    Check::ok("output", format!("{} will be created in {}", self.output.display(), dir.display()))
+                }
+            }
+            Err(e) => Check::fail("output", format!("cannot write to {}: {}", dir.display(), e), fix),
+        }
+    }
+
+    async fn check_index(&self) -> McpResult<Vec<Check>> {
+        let rebuild = format!("Rebuild with `mcp-forge build {} --output {}`", self.source.display(), self.output.display());
+        if !self.output.join("database.sqlite").is_file() {
+            let build = format!("Run `mcp-forge build {} --output {}`", self.source.display(), self.output.display());
+            return Ok(vec![Check::warn("database", format!("no index in {}", self.output.display()), build)]);
+        }
+        let pool = database::open_index(&self.output).await?;
+        let missing = schema::missing_tables(&pool).await?;
+        if !missing.is_empty() {
+            return Ok(vec![Check::fail("database", format!("missing tables: {}", missing.join(", ")), rebuild)]);
+        }
+
+        let mut checks = Vec::new();
+        let version = schema::schema_version(&pool).await?;
+        checks.push(if version == schema::SCHEMA_VERSION {
+            Check::ok("schema version", version.to_string())
+        } else {
+            Check::fail("schema version", format!("{} (expected {})", version, schema::SCHEMA_VERSION), rebuild.clone())
+        });
+
+        let dangling = queries::get_dangling_relationships(&pool).await?;
+        checks.push(if dangling.is_empty() {
+            Check::ok("relationships", "every target symbol is indexed")
+        } else {
+            let mut examples: Vec<String> = dangling
+                .iter()
+                .take(MAX_EXAMPLES)
+                .map(|rel| format!("#{} -> {}", rel.id, rel.to_symbol_id.as_deref().unwrap_or_default()))
+                .collect();
+            if dangling.len() > MAX_EXAMPLES {
+                examples.push(format!("{} more", dangling.len() - MAX_EXAMPLES));
+            }
+            Check::fail(
+                "relationships",
+                format!("{} point at missing symbols ({})", dangling.len(), examples.join(", ")),
+                rebuild.clone(),
+            )
+        });
+
+        let orphans = queries::count_orphan_embeddings(&pool).await?;
+        checks.push(if orphans == 0 {
+            Check::ok("embeddings index", "every embedding belongs to an indexed symbol")
+        } else {
+            Check::fail("embeddings index", format!("{} orphan embeddings", orphans), rebuild)
+        });
+        pool.close().await;
+        Ok(checks)
+    }
+
+    /// Run every check; failures are reported, never returned as errors.
+    pub async fn run(&self) -> DoctorReport {
+        let mut report = DoctorReport::default();
+        let (config_check, config) = self.check_config();
+        report.checks.push(config_check);
+        report.checks.extend(self.check_embeddings(&config));
+        report.checks.push(self.check_templates(&config));
+        report.checks.push(self.check_output());
+        match self.check_index().await {
+            Ok(checks) => report.checks.extend(checks),
+            Err(e) => report.checks.push(Check::fail(
+                "database",
+                e.to_string(),
+                format!("Delete {} and run `mcp-forge build`", self.output.join("database.sqlite").display()),
+            )),
+        }
+        report
+    }
+}
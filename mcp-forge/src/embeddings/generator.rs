@@ -4,23 +4,41 @@ pub trait EmbeddingGen {
 }
 use std::process::Command;
 use std::path::PathBuf;
-use serde_json::json;
 use crate::error::{McpError, McpResult};
 
+/// Interpreter the embedding script runs under.
+pub const PYTHON: &str = "python3";
+
+/// Script written into the output directory by the Python server templates.
+pub const SCRIPT: &str = "generate_embeddings.py";
+
+/// Model the embedding script loads.
+pub const DEFAULT_MODEL: &str = "all-MiniLM-L6-v2";
+
 pub struct EmbeddingGenerator {
     python_env: PathBuf,
     model_name: String,
 }
 
-impl EmbeddingGen for EmbeddingGenerator {
+impl EmbeddingGenerator {
     pub fn new(python_env: PathBuf, model_name: String) -> Self {
         Self { python_env, model_name }
     }
 
-    pub fn generate_embeddings(&self, texts: Vec<String>) -> McpResult<Vec<Vec<f32>>> {
+    pub fn script_path(&self) -> PathBuf {
+        self.python_env.join(SCRIPT)
+    }
+
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+}
+
+impl EmbeddingGen for EmbeddingGenerator {
+    fn generate_embeddings(&self, texts: Vec<String>) -> McpResult<Vec<Vec<f32>>> {
         let json_texts = serde_json::to_string(&texts).map_err(|e| McpError::Transport(e.to_string()))?;
-        let output = Command::new("python3")
-            .arg(self.python_env.join("generate_embeddings.py"))
+        let output = Command::new(PYTHON)
+            .arg(self.script_path())
             .arg(json_texts)
             .output()
             .map_err(|e| McpError::Transport(e.to_string()))?;
@@ -33,4 +51,4 @@ impl EmbeddingGen for EmbeddingGenerator {
             .map_err(|e| McpError::Transport(e.to_string()))?;
         Ok(embeddings)
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1 @@
-mod.rs
-
-pub mod generator;
\ No newline at end of file
+pub mod generator;
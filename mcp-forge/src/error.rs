@@ -10,6 +10,8 @@ pub enum McpError {
     Dispatch(String),
     #[error("Database error: {0}")]
     Database(String),
+    #[error("Configuration error: {0}")]
+    Config(String),
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
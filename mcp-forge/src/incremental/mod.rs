@@ -1,78 +1,285 @@
-    /// Trait for incremental build operations, following SDK standards.
-    pub trait IncrementalOps {
-        fn cache_file_hash(&self, file_path: &Path) -> crate::error::McpResult<()>;
-        fn detect_changes(&self, file_path: &Path) -> crate::error::McpResult<bool>;
-        fn watch(&self) -> crate::error::McpResult<()>;
+//! Change detection for `mcp-forge watch`.
+//!
+//! Files are hashed so that saves without changes are ignored; changed files
+//! are reparsed incrementally by [`reparse::Reparser`] and turned into
+//! [`IndexUpdate`]s for the build's `database.sqlite`.
+
+pub mod reparse;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use log::{debug, info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use sha2::{Digest, Sha256};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::analyzer::content::read_source;
+use crate::analyzer::{discover_files_with, IgnoreMatcher};
+use crate::analyzer::parser::CodeParser;
+use crate::analyzer::symbols::Symbol;
+use crate::config::IndexingConfig;
+use crate::database::queries::{self, FileRecord};
+use crate::database::records::SyntaxError;
+use crate::error::{McpError, McpResult};
+use crate::knowledge::relationships::Relationship;
+use crate::server::builder::store_symbol;
+use crate::server::http::McpNotify;
+use crate::server::resources::FILE_SCHEME;
+use reparse::Reparser;
+
+/// Trait for incremental build operations, following SDK standards.
+pub trait IncrementalOps {
+    fn cache_file_hash(&self, file_path: &Path) -> McpResult<()>;
+    fn detect_changes(&self, file_path: &Path) -> McpResult<bool>;
+    fn watch(&self) -> McpResult<()>;
+}
+
+/// The new index rows of one changed or deleted file.
+#[derive(Debug, Clone, Default)]
+pub struct IndexUpdate {
+    /// Relative to the source root, as stored in `symbols.file_path`.
+    pub file_path: String,
+    /// Every symbol of the file; empty when it was deleted.
+    pub symbols: Vec<Symbol>,
+    pub relationships: Vec<Relationship>,
+    /// The file's `files` row; `None` when it was deleted.
+    pub file: Option<FileRecord>,
+    /// Syntax errors stored in `parse_diagnostics`.
+    pub diagnostics: Vec<SyntaxError>,
+    pub removed: bool,
+}
+
+impl IndexUpdate {
+    /// Replace the file's symbols, parameters, relationships, `files` row and
+    /// syntax errors in one transaction. Edges to symbols that no longer
+    /// exist, in this file or others, are dropped.
+    pub async fn apply(&self, pool: &SqlitePool) -> McpResult<()> {
+        let mut tx = pool.begin().await.map_err(|e| McpError::Database(e.to_string()))?;
+        // Other files' edges point at the symbols replaced here; they are checked on commit.
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| McpError::Database(e.to_string()))?;
+        queries::delete_file_symbols(&mut *tx, &self.file_path).await?;
+        if self.removed {
+            queries::delete_file(&mut *tx, &self.file_path).await?;
+        }
+        if let Some(file) = &self.file {
+            queries::insert_file(&mut *tx, file).await?;
+            queries::insert_parse_diagnostics(&mut *tx, &self.file_path, &self.diagnostics).await?;
+        }
+        for symbol in &self.symbols {
+            store_symbol(&mut *tx, symbol).await?;
+        }
+        for relationship in &self.relationships {
+            queries::insert_relationship(&mut *tx, &relationship.from, &relationship.to, &relationship.kind, relationship.strength).await?;
+        }
+        queries::delete_orphans(&mut *tx).await?;
+        tx.commit().await.map_err(|e| McpError::Database(e.to_string()))
+    }
+
+    /// Tell clients that the resource list and the file's `file://` resource changed.
+    pub fn notify(&self, notifier: &impl McpNotify) {
+        notifier.notify("notifications/resources/list_changed", None);
+        notifier.notify("notifications/resources/updated", Some(json!({ "uri": format!("{}{}", FILE_SCHEME, self.file_path) })));
     }
-pub mod incremental {
-    use std::collections::HashMap;
-    use std::fs;
-    use std::path::{Path, PathBuf};
-    use sha2::{Sha256, Digest};
-    use std::sync::{Arc, Mutex};
-    use notify::{Watcher, RecursiveMode, watcher};
-    use std::time::Duration;
-    use crate::error::{McpError, McpResult};
-
-    #[derive(Default)]
-    pub struct IncrementalBuilder {
-        file_hashes: Arc<Mutex<HashMap<String, String>>>,
-        cache_dir: PathBuf,
+}
+
+pub struct IncrementalBuilder {
+    file_hashes: Arc<Mutex<HashMap<String, String>>>,
+    source: PathBuf,
+    cache_dir: PathBuf,
+    indexing: IndexingConfig,
+    ignore: IgnoreMatcher,
+    reparser: Mutex<Reparser>,
+}
+
+impl IncrementalBuilder {
+    pub fn new(source: PathBuf, cache_dir: PathBuf, indexing: &IndexingConfig) -> Self {
+        Self {
+            file_hashes: Arc::new(Mutex::new(HashMap::new())),
+            reparser: Mutex::new(Reparser::new(source.clone(), indexing)),
+            ignore: IgnoreMatcher::new(&source, indexing),
+            source,
+            cache_dir,
+            indexing: indexing.clone(),
+        }
     }
 
-    impl IncrementalOps for IncrementalBuilder {
-        pub fn new(cache_dir: PathBuf) -> Self {
-            Self {
-                file_hashes: Arc::new(Mutex::new(HashMap::new())),
-                cache_dir,
+    /// Parse every indexed file once, so later changes reuse their trees.
+    pub fn prime(&self) {
+        let mut reparser = self.reparser.lock().unwrap();
+        for path in discover_files_with(&self.source, &self.indexing) {
+            if CodeParser::language_for(&path).is_none() {
+                continue;
+            }
+            match reparser.update_file(&path) {
+                Ok(_) => {
+                    let _ = self.cache_file_hash(&path);
+                }
+                Err(e) => debug!("Not tracking {:?}: {}", path, e),
             }
         }
+    }
+
+    fn handle(&self, event: notify::Event) -> Vec<IndexUpdate> {
+        event
+            .paths
+            .iter()
+            .filter(|path| !path.starts_with(&self.cache_dir))
+            .filter_map(|path| match event.kind {
+                EventKind::Remove(_) => self.removed(path),
+                EventKind::Create(_) | EventKind::Modify(_) if path.is_file() => self.changed(path),
+                _ => None,
+            })
+            .collect()
+    }
 
-        pub fn cache_file_hash(&self, file_path: &Path) -> McpResult<()> {
-            let mut hasher = Sha256::new();
-            let content = fs::read(file_path).map_err(|e| McpError::Transport(e.to_string()))?;
-            hasher.update(&content);
-            let hash = format!("{:x}", hasher.finalize());
+    /// Reparse `path` if its content changed since it was last seen. Files
+    /// that discovery leaves out, by `.gitignore`, `indexing.exclude_patterns`
+    /// or language, are not indexed here either. Like `mcp-forge build`, files
+    /// that are too large, binary, minified, generated or mostly syntax errors
+    /// lose their symbols and are recorded as skipped.
+    pub fn changed(&self, path: &Path) -> Option<IndexUpdate> {
+        if self.ignore.is_ignored(path) {
+            return None;
+        }
+        let language = match CodeParser::language_for(path) {
+            Some(language) if self.indexing.includes_language(language) => language,
+            _ => return None,
+        };
+        match self.detect_changes(path) {
+            Ok(true) => {}
+            Ok(false) => return None,
+            Err(e) => {
+                debug!("Skipping {:?}: {}", path, e);
+                return None;
+            }
+        }
+        let started = Instant::now();
+        let mut reparser = self.reparser.lock().unwrap();
+        let file_path = reparser.file_path(path);
+        let source = match read_source(path, &self.indexing) {
+            Ok(source) => source,
+            Err(reason) => {
+                info!("Skipping {:?}: {}", path, reason);
+                let _ = self.cache_file_hash(path);
+                reparser.remove(path);
+                let file = FileRecord {
+                    path: file_path.clone(),
+                    language: Some(language.to_string()),
+                    skip_reason: Some(reason.to_string()),
+                    skip_kind: Some(reason.kind().to_string()),
+                    ..Default::default()
+                };
+                return Some(IndexUpdate { file_path, file: Some(file), ..Default::default() });
+            }
+        };
+        let (lines, encoding) = (source.text.lines().count(), source.encoding);
+        match reparser.update(path, source.text) {
+            Ok(update) => {
+                let _ = self.cache_file_hash(path);
+                info!(
+                    "Reparsed {:?} in {:?}{}: {} symbols, {} re-extracted, {} removed",
+                    path,
+                    started.elapsed(),
+                    if update.incremental { "" } else { " (full parse)" },
+                    update.symbols.len(),
+                    update.reextracted.len(),
+                    update.removed.len(),
+                );
+                let diagnostics = update.diagnostics;
+                let skip_reason = diagnostics.skip_reason(self.indexing.max_error_ratio);
+                let file = FileRecord {
+                    path: file_path.clone(),
+                    language: Some(update.language.to_string()),
+                    lines: Some(lines as i64),
+                    error_count: diagnostics.error_count as i64,
+                    error_ratio: diagnostics.error_ratio(),
+                    timed_out: diagnostics.timed_out,
+                    skip_reason: skip_reason.clone(),
+                    skip_kind: skip_reason.as_ref().map(|_| "syntax_errors".to_string()),
+                    encoding: Some(encoding.as_str().to_string()),
+                };
+                if let Some(reason) = &skip_reason {
+                    info!("Not indexing {:?}: {}", path, reason);
+                }
+                let indexed = skip_reason.is_none();
+                Some(IndexUpdate {
+                    relationships: if indexed { reparser.relationships(path) } else { Vec::new() },
+                    symbols: if indexed { update.symbols } else { Vec::new() },
+                    file_path,
+                    file: Some(file),
+                    diagnostics: diagnostics.errors,
+                    removed: false,
+                })
+            }
+            Err(e) => {
+                warn!("Failed to reparse {:?}: {}", path, e);
+                None
+            }
+        }
+    }
 
-            let mut file_hashes = self.file_hashes.lock().unwrap();
-            file_hashes.insert(file_path.to_string_lossy().to_string(), hash);
-            Ok(())
+    /// Forget a deleted file.
+    pub fn removed(&self, path: &Path) -> Option<IndexUpdate> {
+        let mut reparser = self.reparser.lock().unwrap();
+        let removed = reparser.remove(path);
+        self.file_hashes.lock().unwrap().remove(path.to_string_lossy().as_ref());
+        if removed.is_empty() {
+            return None;
         }
+        info!("{:?} removed with {} symbols", path, removed.len());
+        Some(IndexUpdate { file_path: reparser.file_path(path), removed: true, ..Default::default() })
+    }
 
-        pub fn detect_changes(&self, file_path: &Path) -> McpResult<bool> {
-            let mut hasher = Sha256::new();
-            let content = fs::read(file_path).map_err(|e| McpError::Transport(e.to_string()))?;
-            hasher.update(&content);
-            let current_hash = format!("{:x}", hasher.finalize());
+    /// Like [`IncrementalOps::watch`], passing the update of every changed file to `on_update`.
+    pub fn watch_with(&self, mut on_update: impl FnMut(IndexUpdate)) -> McpResult<()> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).map_err(|e| McpError::Transport(e.to_string()))?;
+        watcher.watch(&self.source, RecursiveMode::Recursive).map_err(|e| McpError::Transport(e.to_string()))?;
+        self.prime();
 
-            let file_hashes = self.file_hashes.lock().unwrap();
-            if let Some(stored_hash) = file_hashes.get(file_path.to_string_lossy().as_ref()) {
-                return Ok(&current_hash != stored_hash);
+        for event in rx {
+            match event {
+                Ok(event) => self.handle(event).into_iter().for_each(&mut on_update),
+                Err(e) => warn!("Watch error: {}", e),
             }
-            Ok(true)
         }
+        Ok(())
+    }
 
-        pub fn watch(&self) -> McpResult<()> {
-            let (tx, rx) = std::sync::mpsc::channel();
-            let mut watcher = watcher(tx, Duration::from_secs(2)).map_err(|e| McpError::Transport(e.to_string()))?;
-
-            watcher.watch(&self.cache_dir, RecursiveMode::Recursive).map_err(|e| McpError::Transport(e.to_string()))?;
-
-            loop {
-                match rx.recv() {
-                    Ok(event) => {
-                        // Handle file change event
-                        if let notify::DebouncedEvent::Write(path) = event {
-                            if self.detect_changes(&path)? {
-                                println!("File changed: {:?}", path);
-                                // Trigger incremental build logic here
-                            }
-                        }
-                    }
-                    Err(e) => println!("Watch error: {:?}", e),
-                }
-            }
-            // Ok(())
+    fn hash(file_path: &Path) -> McpResult<String> {
+        let content = fs::read(file_path).map_err(|e| McpError::Transport(e.to_string()))?;
+        Ok(format!("{:x}", Sha256::digest(&content)))
+    }
+}
+
+impl IncrementalOps for IncrementalBuilder {
+    fn cache_file_hash(&self, file_path: &Path) -> McpResult<()> {
+        let hash = Self::hash(file_path)?;
+        let mut file_hashes = self.file_hashes.lock().unwrap();
+        file_hashes.insert(file_path.to_string_lossy().to_string(), hash);
+        Ok(())
+    }
+
+    fn detect_changes(&self, file_path: &Path) -> McpResult<bool> {
+        let current_hash = Self::hash(file_path)?;
+        let file_hashes = self.file_hashes.lock().unwrap();
+        if let Some(stored_hash) = file_hashes.get(file_path.to_string_lossy().as_ref()) {
+            return Ok(&current_hash != stored_hash);
         }
+        Ok(true)
+    }
+
+    /// Reparse files under the source root as they change; runs until the
+    /// watcher fails.
+    fn watch(&self) -> McpResult<()> {
+        self.watch_with(|_| {})
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,329 @@
+//! Incremental reparsing for watch mode.
+//!
+//! The last tree and source of every file are kept. A new version of a file is
+//! turned into a tree-sitter [`InputEdit`], reparsed from the edited old tree,
+//! and only the symbols overlapping the edit or the ranges tree-sitter reports
+//! as changed are extracted again; the others are carried over and shifted.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use tree_sitter::{InputEdit, Node, Point, Tree};
+
+use crate::analyzer::content::read_source;
+use crate::analyzer::diagnostics::ParseDiagnostics;
+use crate::analyzer::languages::{registry, Project};
+use crate::analyzer::parser::CodeParser;
+use crate::analyzer::symbols::{Symbol, SymbolExtractor};
+use crate::analyzer::tags::TagQueries;
+use crate::config::IndexingConfig;
+use crate::error::{McpError, McpResult};
+use crate::knowledge::relationships::Relationship;
+
+/// Symbols of one file after a change.
+#[derive(Debug, Clone, Default)]
+pub struct FileUpdate {
+    /// Every symbol of the file, in document order.
+    pub symbols: Vec<Symbol>,
+    /// Symbols extracted again because the change touched them.
+    pub reextracted: Vec<Symbol>,
+    /// Ids of symbols that no longer exist.
+    pub removed: Vec<String>,
+    /// Whether the previous tree was reused.
+    pub incremental: bool,
+    /// Language the file was parsed as.
+    pub language: &'static str,
+    /// Syntax errors of the new tree.
+    pub diagnostics: ParseDiagnostics,
+}
+
+struct FileState {
+    language: &'static str,
+    source: String,
+    tree: Tree,
+    symbols: Vec<Symbol>,
+}
+
+/// Keeps the parsed state of every file seen so far.
+pub struct Reparser {
+    root: PathBuf,
+    parser: CodeParser,
+    extractor: SymbolExtractor,
+    indexing: IndexingConfig,
+    files: HashMap<PathBuf, FileState>,
+}
+
+impl Reparser {
+    /// Symbol ids are relative to `root`.
+    pub fn new(root: impl Into<PathBuf>, indexing: &IndexingConfig) -> Self {
+        let queries = TagQueries::for_indexing(indexing).unwrap_or_else(|e| {
+            warn!("Using the built-in tag queries: {}", e);
+            TagQueries::builtin()
+        });
+        Self {
+            root: root.into(),
+            parser: CodeParser::for_indexing(indexing),
+            extractor: SymbolExtractor::with_queries(queries),
+            indexing: indexing.clone(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Read `path` and reparse it. Files that grew too large or turned out
+    /// binary, minified or generated are an error.
+    pub fn update_file(&mut self, path: &Path) -> McpResult<FileUpdate> {
+        let source = read_source(path, &self.indexing).map_err(|reason| McpError::Transport(format!("skipped, {}", reason)))?;
+        self.update(path, source.text)
+    }
+
+    /// Forget a deleted file, returning the ids of its symbols.
+    pub fn remove(&mut self, path: &Path) -> Vec<String> {
+        self.files.remove(path).map(|state| state.symbols.into_iter().map(|s| s.id).collect()).unwrap_or_default()
+    }
+
+    /// Relationships from the symbols of a tracked file, resolved against the other tracked files.
+    pub fn relationships(&self, path: &Path) -> Vec<Relationship> {
+        let (Some(state), file_path) = (self.files.get(path), self.file_path(path)) else {
+            return Vec::new();
+        };
+        let Some(language) = registry().get(state.language) else {
+            return Vec::new();
+        };
+        let root = state.tree.root_node();
+        let tags = self.extractor.extract_tags(&root, &state.source, &file_path);
+        let paths: Vec<PathBuf> = self.files.keys().cloned().collect();
+        language.relationships(&root, &state.source, &file_path, &tags, &Project::from_paths(&self.root, &paths))
+    }
+
+    /// Id prefix and `file_path` of the symbols of `path`.
+    pub fn file_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    }
+
+    /// Reparse `path` from its new `source`, reusing the previous tree.
+    pub fn update(&mut self, path: &Path, source: String) -> McpResult<FileUpdate> {
+        let language = registry()
+            .for_source(path, &source)
+            .map(|language| language.name())
+            .ok_or(McpError::Transport("Unsupported file type".to_string()))?;
+        let file_path = self.file_path(path);
+
+        let Some(mut previous) = self.files.remove(path).filter(|state| state.language == language) else {
+            let tree = self.parser.parse_source(language, &source)?;
+            let symbols = self.extractor.extract_symbols(&tree.root_node(), &source, &file_path);
+            let diagnostics = ParseDiagnostics::from_tree(&tree, &source);
+            self.files.insert(path.to_path_buf(), FileState { language, source, tree, symbols: symbols.clone() });
+            return Ok(FileUpdate { reextracted: symbols.clone(), symbols, removed: Vec::new(), incremental: false, language, diagnostics });
+        };
+        let Some(edit) = input_edit(&previous.source, &source) else {
+            let symbols = previous.symbols.clone();
+            let diagnostics = ParseDiagnostics::from_tree(&previous.tree, &previous.source);
+            self.files.insert(path.to_path_buf(), previous);
+            return Ok(FileUpdate { symbols, incremental: true, language, diagnostics, ..Default::default() });
+        };
+
+        let byte_delta = edit.new_end_byte as isize - edit.old_end_byte as isize;
+        let row_delta = edit.new_end_position.row as isize - edit.old_end_position.row as isize;
+        let to_new = |byte: usize| match byte {
+            byte if byte <= edit.start_byte => byte,
+            byte if byte >= edit.old_end_byte => shift(byte, byte_delta),
+            _ => edit.new_end_byte,
+        };
+        // Widened in the old tree too: removing the link between a comment and
+        // the definition below it only shows there.
+        let before = widen(&previous.tree.root_node(), &previous.source, edit.start_byte..edit.old_end_byte);
+
+        previous.tree.edit(&edit);
+        let tree = self.parser.reparse_source(language, &source, &previous.tree)?;
+        let diagnostics = ParseDiagnostics::from_tree(&tree, &source);
+        let root = tree.root_node();
+        // Error recovery can restructure a broken file well outside the ranges
+        // tree-sitter reports, so such files are extracted in full.
+        if root.has_error() || previous.tree.root_node().has_error() {
+            let symbols = self.extractor.extract_symbols(&root, &source, &file_path);
+            let removed = removed_ids(&previous.symbols, &symbols);
+            self.files.insert(path.to_path_buf(), FileState { language, source, tree, symbols: symbols.clone() });
+            return Ok(FileUpdate { reextracted: symbols.clone(), symbols, removed, incremental: true, language, diagnostics });
+        }
+        let mut changed: Vec<Range<usize>> = previous.tree.changed_ranges(&tree).map(|range| range.start_byte..range.end_byte).collect();
+        changed.push(edit.start_byte..edit.new_end_byte);
+        let mut changed: Vec<Range<usize>> = changed.into_iter().map(|range| widen(&root, &source, range)).collect();
+        changed.push(to_new(before.start)..to_new(before.end));
+
+        let mut reextracted: Vec<Symbol> = Vec::new();
+        let extract = |bytes: Range<usize>, reextracted: &mut Vec<Symbol>| {
+            for symbol in self.extractor.extract_symbols_in(&root, &source, &file_path, bytes) {
+                if !reextracted.iter().any(|s| s.byte_range == symbol.byte_range && s.name == symbol.name) {
+                    reextracted.push(symbol);
+                }
+            }
+        };
+        for range in &changed {
+            // Bounds are inclusive: definitions ending or starting at either end are touched.
+            extract(range.start.saturating_sub(1)..(range.end + 1).min(source.len()), &mut reextracted);
+        }
+        // A node reached by a pattern rooted at its parent may have other tags
+        // whose patterns only start inside the range.
+        let starts: Vec<usize> = reextracted.iter().map(|symbol| symbol.byte_range.start).collect();
+        for start in starts {
+            extract(start..start + 1, &mut reextracted);
+        }
+
+        let mut symbols: Vec<Symbol> = previous
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let mut symbol = symbol.clone();
+                if symbol.byte_range.end < edit.start_byte {
+                    Some(symbol)
+                } else if symbol.byte_range.start > edit.old_end_byte {
+                    symbol.byte_range = shift(symbol.byte_range.start, byte_delta)..shift(symbol.byte_range.end, byte_delta);
+                    symbol.start_line = shift(symbol.start_line, row_delta);
+                    symbol.end_line = shift(symbol.end_line, row_delta);
+                    Some(symbol)
+                } else {
+                    None
+                }
+            })
+            .filter(|symbol| !changed.iter().any(|range| symbol.byte_range.start <= range.end && range.start <= symbol.byte_range.end))
+            // Every tag of a re-extracted node was found again, in extraction order.
+            .filter(|symbol| !reextracted.iter().any(|s| s.byte_range == symbol.byte_range))
+            .collect();
+        symbols.extend(reextracted.iter().cloned());
+        symbols.sort_by_key(|symbol| (symbol.byte_range.start, std::cmp::Reverse(symbol.byte_range.end)));
+        if depends_on_whole_file(&previous.symbols, &reextracted, &symbols) {
+            symbols = self.extractor.extract_symbols(&root, &source, &file_path);
+            reextracted = symbols.clone();
+        }
+
+        let removed = removed_ids(&previous.symbols, &symbols);
+        self.files.insert(path.to_path_buf(), FileState { language, source, tree, symbols: symbols.clone() });
+        Ok(FileUpdate { symbols, reextracted, removed, incremental: true, language, diagnostics })
+    }
+}
+
+/// The single edit turning `old` into `new`: everything between their common
+/// prefix and common suffix. `None` when they are equal.
+pub fn input_edit(old: &str, new: &str) -> Option<InputEdit> {
+    if old == new {
+        return None;
+    }
+    let mut prefix = old.bytes().zip(new.bytes()).take_while(|(a, b)| a == b).count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old.bytes().rev().zip(new.bytes().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+    Some(InputEdit {
+        start_byte: prefix,
+        old_end_byte: old.len() - suffix,
+        new_end_byte: new.len() - suffix,
+        start_position: point_at(old, prefix),
+        old_end_position: point_at(old, old.len() - suffix),
+        new_end_position: point_at(new, new.len() - suffix),
+    })
+}
+
+/// Whether ids or parents of `merged` may depend on definitions outside the
+/// re-extracted ranges: ids of members follow their parent's, overloads are
+/// numbered in file order, and `@scope` parents (`Server.Run`) are looked up
+/// across the file.
+fn depends_on_whole_file(previous: &[Symbol], reextracted: &[Symbol], merged: &[Symbol]) -> bool {
+    let is_overload = |symbol: &Symbol| symbol.id.rsplit_once("::").is_some_and(|(_, path)| path.contains('~'));
+    let mut ids = HashSet::new();
+    if !merged.iter().all(|symbol| ids.insert(symbol.id.as_str())) {
+        return true;
+    }
+    previous.iter().chain(merged).any(is_overload)
+        || merged.iter().filter_map(|symbol| symbol.parent_id.as_deref()).any(|parent| !ids.contains(parent))
+        || reextracted
+            .iter()
+            .any(|symbol| symbol.parent_id.is_none() && previous.iter().any(|old| old.id == symbol.id && old.parent_id.is_some()))
+}
+
+/// Ids of `old` symbols missing from `new`.
+fn removed_ids(old: &[Symbol], new: &[Symbol]) -> Vec<String> {
+    let current: HashSet<&str> = new.iter().map(|symbol| symbol.id.as_str()).collect();
+    old.iter().filter(|symbol| !current.contains(symbol.id.as_str())).map(|symbol| symbol.id.clone()).collect()
+}
+
+fn point_at(text: &str, byte: usize) -> Point {
+    let before = &text.as_bytes()[..byte];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let column = byte - before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Point::new(row, column)
+}
+
+fn shift(value: usize, delta: isize) -> usize {
+    value.saturating_add_signed(delta)
+}
+
+/// Comments, and the Rust attributes that may separate an item from its doc comments.
+fn is_comment(node: &Node) -> bool {
+    node.kind().contains("comment") || node.kind() == "attribute_item"
+}
+
+/// A changed range grown to the comments documenting the definitions it
+/// touches, and to the definition documented by comments it touches: tag
+/// queries only see a definition's doc comments when both are in range.
+fn widen(root: &Node, source: &str, range: Range<usize>) -> Range<usize> {
+    let Range { mut start, mut end } = range;
+
+    let covering = root.named_descendant_for_byte_range(start, end).unwrap_or(*root);
+    let first_child = (0..covering.named_child_count())
+        .filter_map(|i| covering.named_child(i))
+        .find(|child| child.end_byte() > start && child.start_byte() < end);
+    let ancestors = std::iter::successors(Some(covering), |node| node.parent());
+    for node in first_child.into_iter().chain(ancestors) {
+        let mut sibling = node.prev_named_sibling();
+        while let Some(comment) = sibling.filter(is_comment) {
+            start = start.min(comment.start_byte());
+            sibling = comment.prev_named_sibling();
+        }
+    }
+
+    // Only an edited comment, or a blank line where one may have been, can
+    // change the documentation of the next definition.
+    let mut cursor = end;
+    let line_start = source[..end].rfind('\n').map_or(0, |i| i + 1);
+    let mut documents = source[line_start..end].trim().is_empty();
+    if let Some(comment) = root.descendant_for_byte_range(end.saturating_sub(1), end).filter(is_comment) {
+        cursor = comment.end_byte();
+        documents = true;
+    }
+    while let Some(next) = first_after(root, cursor) {
+        if !source[cursor..next.start_byte()].trim().is_empty() {
+            break;
+        }
+        if !is_comment(&next) {
+            if documents {
+                end = end.max(next.start_byte() + 1);
+            }
+            break;
+        }
+        cursor = next.end_byte();
+        documents = true;
+    }
+    start..end
+}
+
+/// The first named node starting at or after `byte`, outermost first.
+fn first_after<'t>(node: &Node<'t>, byte: usize) -> Option<Node<'t>> {
+    for child in (0..node.named_child_count()).filter_map(|i| node.named_child(i)) {
+        if child.end_byte() <= byte {
+            continue;
+        }
+        if child.start_byte() >= byte {
+            return Some(child);
+        }
+        if let Some(found) = first_after(&child, byte) {
+            return Some(found);
+        }
+    }
+    None
+}
@@ -0,0 +1,287 @@
+//! Workspace scaffolding for `mcp-forge init`.
+//!
+//! Every step checks what is already on disk first, so running `init` twice
+//! leaves the workspace unchanged.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::analyzer::discover_files_with;
+use crate::analyzer::parser::CodeParser;
+use crate::config::{ForgeConfig, IndexingConfig, CONFIG_FILE_NAMES};
+use crate::error::{McpError, McpResult};
+use crate::server::templates::server_slug;
+
+/// Directory holding the incremental build cache and generated client snippets.
+pub const CACHE_DIR: &str = ".mcp-forge";
+
+/// File inside [`CACHE_DIR`] with ready-to-paste client configuration.
+pub const CLIENTS_FILE: &str = "clients.md";
+
+/// Directories excluded in a freshly written config.
+const DEFAULT_EXCLUDES: &[&str] = &[
+    "node_modules/",
+    "target/",
+    "dist/",
+    "build/",
+    ".venv/",
+    "venv/",
+    "__pycache__/",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitAction {
+    Created,
+    Updated,
+    Upgraded,
+    Unchanged,
+    /// An older config the user chose not to upgrade.
+    Outdated,
+}
+
+impl fmt::Display for InitAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            InitAction::Created => "created",
+            InitAction::Updated => "updated",
+            InitAction::Upgraded => "upgraded",
+            InitAction::Unchanged => "unchanged",
+            InitAction::Outdated => "outdated",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug)]
+pub struct InitReport {
+    pub actions: Vec<(InitAction, PathBuf)>,
+    pub config: ForgeConfig,
+    pub snippets: ClientSnippets,
+}
+
+impl InitReport {
+    pub fn action_for(&self, path: &Path) -> Option<InitAction> {
+        self.actions.iter().find(|(_, p)| p == path).map(|(action, _)| *action)
+    }
+}
+
+/// A project root to initialise.
+pub struct Workspace {
+    pub root: PathBuf,
+    /// Project name for a new config; defaults to the directory name.
+    pub name: Option<String>,
+    /// Build output directory, relative to `root` unless absolute.
+    pub output: PathBuf,
+    /// Command clients launch, usually the path of this executable.
+    pub command: String,
+}
+
+impl Workspace {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let command = std::env::current_exe()
+            .map(|exe| exe.display().to_string())
+            .unwrap_or_else(|_| "mcp-forge".to_string());
+        Self { root: root.into(), name: None, output: PathBuf::from("mcp-server"), command }
+    }
+
+    fn output_dir(&self) -> PathBuf {
+        let root = self.root.canonicalize().unwrap_or_else(|_| self.root.clone());
+        root.join(&self.output)
+    }
+
+    fn default_name(&self) -> String {
+        self.root
+            .canonicalize()
+            .ok()
+            .and_then(|root| root.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "MyProject".to_string())
+    }
+
+    /// A new config with detected languages and common build directories excluded.
+    fn fresh_config(&self) -> ForgeConfig {
+        let mut config = ForgeConfig::default();
+        config.project.name = self.name.clone().unwrap_or_else(|| self.default_name());
+        config.indexing.exclude_patterns = DEFAULT_EXCLUDES.iter().map(|p| p.to_string()).collect();
+        config.indexing.languages = detect_languages(&self.root, &config.indexing);
+        config
+    }
+
+    fn write_config(&self, confirm: &mut dyn FnMut(&str) -> bool) -> McpResult<(InitAction, PathBuf, ForgeConfig)> {
+        let Some(path) = ForgeConfig::discover(&self.root) else {
+            let path = self.root.join(CONFIG_FILE_NAMES[0]);
+            let config = self.fresh_config();
+            config.validate()?;
+            write(&path, &render_config(&config)?)?;
+            return Ok((InitAction::Created, path, config));
+        };
+
+        let mut config = ForgeConfig::from_file(&path)?;
+        if !config.is_outdated() {
+            return Ok((InitAction::Unchanged, path, config));
+        }
+        let question = format!(
+            "{} uses config version {} (current is {}). Upgrade it? The original is kept as {}.bak",
+            path.display(),
+            config.version,
+            crate::config::CONFIG_VERSION,
+            path.display()
+        );
+        if !confirm(&question) {
+            return Ok((InitAction::Outdated, path, config));
+        }
+        config.version = crate::config::CONFIG_VERSION;
+        config.validate()?;
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        std::fs::copy(&path, &backup).map_err(|e| McpError::Config(format!("Cannot back up {}: {}", path.display(), e)))?;
+        write(&path, &render_config(&config)?)?;
+        Ok((InitAction::Upgraded, path, config))
+    }
+
+    /// Append ignore entries for the cache and build output to `.gitignore`.
+    fn write_gitignore(&self) -> McpResult<(InitAction, PathBuf)> {
+        let path = self.root.join(".gitignore");
+        let existing = std::fs::read_to_string(&path).unwrap_or_default();
+        let mut entries = vec![format!("/{}/", CACHE_DIR)];
+        if self.output.is_relative() {
+            entries.push(format!("/{}/", self.output.to_string_lossy().trim_start_matches("./").trim_end_matches('/')));
+        }
+        let present = |entry: &str| {
+            let bare = entry.trim_matches('/');
+            existing.lines().map(str::trim).any(|line| line.trim_matches('/') == bare)
+        };
+        let missing: Vec<&String> = entries.iter().filter(|entry| !present(entry)).collect();
+        if missing.is_empty() {
+            return Ok((InitAction::Unchanged, path));
+        }
+
+        let mut content = existing.clone();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str("# mcp-forge build output\n");
+        for entry in missing {
+            content.push_str(entry);
+            content.push('\n');
+        }
+        write(&path, &content)?;
+        let action = if existing.is_empty() { InitAction::Created } else { InitAction::Updated };
+        Ok((action, path))
+    }
+
+    /// Create whatever is missing; `confirm` is asked before rewriting user files.
+    pub fn scaffold(&self, confirm: &mut dyn FnMut(&str) -> bool) -> McpResult<InitReport> {
+        let mut actions = Vec::new();
+        let (action, config_path, config) = self.write_config(confirm)?;
+        actions.push((action, config_path));
+
+        let cache = self.root.join(CACHE_DIR);
+        if cache.is_dir() {
+            actions.push((InitAction::Unchanged, cache.clone()));
+        } else {
+            std::fs::create_dir_all(&cache).map_err(|e| McpError::Config(format!("Cannot create {}: {}", cache.display(), e)))?;
+            actions.push((InitAction::Created, cache.clone()));
+        }
+
+        let (action, gitignore) = self.write_gitignore()?;
+        actions.push((action, gitignore));
+
+        let snippets = ClientSnippets::new(&config, &self.command, &self.output_dir());
+        let clients = cache.join(CLIENTS_FILE);
+        actions.push((write_if_changed(&clients, &snippets.to_markdown())?, clients));
+
+        Ok(InitReport { actions, config, snippets })
+    }
+}
+
+/// MCP client configuration for the built server.
+#[derive(Debug, Clone)]
+pub struct ClientSnippets {
+    pub server_name: String,
+    /// Shell command starting the stdio server.
+    pub stdio_command: String,
+    /// `mcpServers` block used by most desktop clients.
+    pub stdio_json: serde_json::Value,
+    /// `servers` block for `.vscode/mcp.json`.
+    pub vscode_json: serde_json::Value,
+    /// Entry for clients that connect to `mcp-forge watch` over HTTP.
+    pub http_json: serde_json::Value,
+}
+
+impl ClientSnippets {
+    pub fn new(config: &ForgeConfig, command: &str, output: &Path) -> Self {
+        let server_name = server_slug(&config.project.name);
+        let output = output.display().to_string();
+        let args = json!(["serve", output]);
+        Self {
+            stdio_command: format!("{} serve {}", shell_quote(command), shell_quote(&output)),
+            stdio_json: json!({ "mcpServers": { &server_name: { "command": command, "args": args } } }),
+            vscode_json: json!({ "servers": { &server_name: { "type": "stdio", "command": command, "args": args } } }),
+            http_json: json!({
+                "mcpServers": { &server_name: { "url": format!("http://127.0.0.1:{}/mcp", config.server.port) } }
+            }),
+            server_name,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let pretty = |value: &serde_json::Value| serde_json::to_string_pretty(value).unwrap_or_default();
+        format!(
+            "# MCP client configuration for `{name}`\n\n\
+             Run `mcp-forge build` first; these entries serve its output.\n\n\
+             ## Command line (stdio)\n\n```sh\n{command}\n```\n\n\
+             ## mcpServers (desktop clients)\n\n```json\n{stdio}\n```\n\n\
+             ## VS Code (`.vscode/mcp.json`)\n\n```json\n{vscode}\n```\n\n\
+             ## Streamable HTTP (`mcp-forge watch`)\n\n```json\n{http}\n```\n",
+            name = self.server_name,
+            command = self.stdio_command,
+            stdio = pretty(&self.stdio_json),
+            vscode = pretty(&self.vscode_json),
+            http = pretty(&self.http_json),
+        )
+    }
+}
+
+/// Languages with at least one file under `root`, most common first.
+pub fn detect_languages(root: &Path, indexing: &IndexingConfig) -> Vec<String> {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for path in discover_files_with(root, indexing) {
+        if let Some(language) = CodeParser::language_for(&path) {
+            *counts.entry(language).or_insert(0) += 1;
+        }
+    }
+    let mut languages: Vec<(&str, usize)> = counts.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    languages.into_iter().map(|(language, _)| language.to_string()).collect()
+}
+
+fn render_config(config: &ForgeConfig) -> McpResult<String> {
+    Ok(format!(
+        "# mcp-forge project configuration; see the Configuration section of the README.\n\
+         # Values can be overridden with MCP_FORGE_* environment variables and CLI flags.\n{}",
+        config.to_yaml()?
+    ))
+}
+
+fn write(path: &Path, content: &str) -> McpResult<()> {
+    std::fs::write(path, content).map_err(|e| McpError::Config(format!("Cannot write {}: {}", path.display(), e)))
+}
+
+fn write_if_changed(path: &Path, content: &str) -> McpResult<InitAction> {
+    match std::fs::read_to_string(path) {
+        Ok(existing) if existing == content => Ok(InitAction::Unchanged),
+        Ok(_) => write(path, content).map(|_| InitAction::Updated),
+        Err(_) => write(path, content).map(|_| InitAction::Created),
+    }
+}
+
+fn shell_quote(arg: &str) -> String {
+    if arg.chars().all(|c| c.is_ascii_alphanumeric() || "/._-:=".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
@@ -1,117 +1,86 @@
+//! Symbols of a build and the relationships between them.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::analyzer::symbols::Symbol;
+use crate::knowledge::relationships::Relationship;
+
 /// Trait for knowledge graph operations, following SDK standards.
 pub trait KnowledgeGraphOps {
+    /// Replace the nodes with `symbols`.
     fn build(&mut self, symbols: &[SymbolNode]);
-    fn extract_relationships_from_ast(&mut self, ast: &tree_sitter::Node, file_path: &str);
-}
-pub struct KnowledgeGraph {
-    nodes: Vec<SymbolNode>,
-    relationships: Vec<Relationship>,
+    /// Add an edge; repeated edges (same ends and kind) are kept once.
+    fn add_relationship(&mut self, relationship: Relationship);
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct SymbolNode {
-    id: String,
-    name: String,
-    kind: String,
-    file_path: String,
-    start_line: usize,
-    end_line: usize,
-    scope: String,
-    documentation: Option<String>,
-    signature: Option<String>,
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub file_path: String,
 }
 
-pub struct Relationship {
-    from: String,
-    to: String,
-    kind: String,
-    strength: f64,
+impl From<&Symbol> for SymbolNode {
+    fn from(symbol: &Symbol) -> Self {
+        Self { id: symbol.id.clone(), name: symbol.name.clone(), kind: symbol.kind.clone(), file_path: symbol.file_path.clone() }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct KnowledgeGraph {
+    nodes: Vec<SymbolNode>,
+    relationships: Vec<Relationship>,
+    edges: HashSet<(String, String, String)>,
 }
 
 impl KnowledgeGraphOps for KnowledgeGraph {
-    /// Extract relationships from AST (Python only, basic)
-    pub fn extract_relationships_from_ast(&mut self, ast: &tree_sitter::Node, file_path: &str) {
-        let mut to_visit = vec![*ast];
-        while let Some(node) = to_visit.pop() {
-            let kind = node.kind();
-            // Function calls
-            if kind == "call" {
-                if let Some(func_node) = node.child(0) {
-                    let func_name = func_node.utf8_text(file_path.as_bytes()).unwrap_or("").to_string();
-                    // Find parent function/class
-                    let mut parent = node.parent();
-                    while let Some(p) = parent {
-                        let pk = p.kind();
-                        if pk == "function_definition" || pk == "class_definition" {
-                            let parent_name = p.child_by_field_name("name")
-                                .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                                .unwrap_or_else(|| "<unknown>".to_string());
-                            self.add_relationship(
-                                format!("{}::{}", file_path, parent_name),
-                                format!("{}::{}", file_path, func_name),
-                                "calls".to_string(),
-                                1.0,
-                            );
-                            break;
-                        }
-                        parent = p.parent();
-                    }
-                }
-            }
-            // Class inheritance
-            if kind == "class_definition" {
-                if let Some(inherits_node) = node.child_by_field_name("superclass") {
-                    let class_name = node.child_by_field_name("name")
-                        .map(|n| n.utf8_text(file_path.as_bytes()).unwrap_or("").to_string())
-                        .unwrap_or_else(|| "<unknown>".to_string());
-                    let super_name = inherits_node.utf8_text(file_path.as_bytes()).unwrap_or("").to_string();
-                    self.add_relationship(
-                        format!("{}::{}", file_path, class_name),
-                        format!("{}::{}", file_path, super_name),
-                        "inherits".to_string(),
-                        1.0,
-                    );
-                }
-            }
-            for i in 0..node.child_count() {
-                if let Some(child) = node.child(i) {
-                    to_visit.push(child);
-                }
-            }
+    fn build(&mut self, symbols: &[SymbolNode]) {
+        self.nodes = symbols.to_vec();
+    }
+
+    fn add_relationship(&mut self, relationship: Relationship) {
+        let key = (relationship.from.clone(), relationship.to.clone(), relationship.kind.clone());
+        if self.edges.insert(key) {
+            self.relationships.push(relationship);
         }
     }
+}
+
+impl KnowledgeGraph {
     pub fn new() -> Self {
-        KnowledgeGraph {
-            nodes: Vec::new(),
-            relationships: Vec::new(),
-        }
+        Self::default()
     }
 
-    pub fn add_node(&mut self, node: SymbolNode) {
-        self.nodes.push(node);
+    pub fn nodes(&self) -> &[SymbolNode] {
+        &self.nodes
     }
 
-    pub fn add_relationship(&mut self, from: String, to: String, kind: String, strength: f64) {
-        self.relationships.push(Relationship { from, to, kind, strength });
+    pub fn relationships(&self) -> &[Relationship] {
+        &self.relationships
     }
 
     pub fn calculate_metrics(&self) -> Metrics {
-        // Implementation for calculating metrics like coupling, complexity, etc.
-        Metrics::default()
+        if self.nodes.is_empty() {
+            return Metrics::default();
+        }
+        let mut fan_in: HashMap<&str, usize> = HashMap::new();
+        for relationship in &self.relationships {
+            *fan_in.entry(relationship.to.as_str()).or_default() += 1;
+        }
+        let nodes = self.nodes.len() as f64;
+        Metrics {
+            coupling: self.relationships.len() as f64 / nodes,
+            centrality: fan_in.values().copied().max().unwrap_or(0) as f64 / nodes,
+        }
     }
 }
 
+/// Whole-graph measures.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Metrics {
-    coupling: f64,
-    complexity: f64,
-    centrality: f64,
+    /// Relationships per symbol.
+    pub coupling: f64,
+    /// Incoming relationships of the most depended-on symbol, per symbol.
+    pub centrality: f64,
 }
-
-impl Default for Metrics {
-    fn default() -> Self {
-        Metrics {
-            coupling: 0.0,
-            complexity: 0.0,
-            centrality: 0.0,
-        }
-    }
-}
\ No newline at end of file
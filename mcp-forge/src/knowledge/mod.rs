@@ -1,4 +1,2 @@
-mod.rs
-
 pub mod graph;
-pub mod relationships;
\ No newline at end of file
+pub mod relationships;
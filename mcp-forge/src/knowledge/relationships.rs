@@ -15,6 +15,12 @@ pub struct RelationshipMapper {
     node_indices: HashMap<String, NodeIndex>,
 }
 
+impl Default for RelationshipMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RelationshipMapper {
     pub fn new() -> Self {
         Self {
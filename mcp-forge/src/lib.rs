@@ -0,0 +1,12 @@
+pub mod analyzer;
+pub mod config;
+pub mod database;
+pub mod doctor;
+pub mod embeddings;
+pub mod error;
+pub mod incremental;
+pub mod init;
+pub mod knowledge;
+pub mod query;
+pub mod server;
+pub mod table;
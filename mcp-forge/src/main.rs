@@ -1,6 +1,5 @@
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
-use env_logger;
 use log::{info, warn, error};
 
 #[derive(Parser)]
@@ -13,28 +12,146 @@ struct Cli {
     command: Commands,
 }
 
+/// Settings layered over `mcp-forge.yaml` and `MCP_FORGE_*` variables.
+#[derive(clap::Args)]
+struct ConfigArgs {
+    /// Config file to use instead of the one discovered in the source root
+    #[clap(long)]
+    config: Option<PathBuf>,
+    /// Project name reported by the generated server
+    #[clap(long)]
+    name: Option<String>,
+    /// Additional gitignore-style pattern to exclude (repeatable)
+    #[clap(long = "exclude")]
+    exclude: Vec<String>,
+    /// Comma-separated languages to index
+    #[clap(long, value_delimiter = ',')]
+    languages: Option<Vec<String>>,
+    /// Skip design pattern detection
+    #[clap(long)]
+    no_patterns: bool,
+    /// Skip embedding generation
+    #[clap(long)]
+    no_embeddings: bool,
+    /// Language of the generated server project
+    #[clap(long, value_enum)]
+    target: Option<mcp_forge::server::templates::ServerTarget>,
+    /// Directory of server templates laid over the built-in ones
+    #[clap(long)]
+    template_dir: Option<PathBuf>,
+}
+
+impl ConfigArgs {
+    fn overrides(&self, port: Option<u16>) -> mcp_forge::config::ConfigOverrides {
+        mcp_forge::config::ConfigOverrides {
+            project_name: self.name.clone(),
+            exclude_patterns: self.exclude.clone(),
+            languages: self.languages.clone(),
+            patterns: self.no_patterns.then_some(false),
+            embeddings: self.no_embeddings.then_some(false),
+            port,
+            target: self.target,
+            template_dir: self.template_dir.clone(),
+        }
+    }
+
+    /// Resolve the effective configuration, exiting with a message when it is invalid.
+    fn load(&self, source: &std::path::Path, port: Option<u16>) -> mcp_forge::config::ForgeConfig {
+        match mcp_forge::config::ForgeConfig::load(source, self.config.as_deref(), &self.overrides(port)) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize MCP-Forge in the current directory
     Init {
+        /// Project root to initialize
+        #[clap(default_value = ".")]
+        path: PathBuf,
+        /// Project name for a new config (default: the directory name)
         #[clap(short, long)]
-        name: String,
+        name: Option<String>,
+        /// Build output directory the client snippets point at, relative to the project root
+        #[clap(short, long, default_value = "mcp-server")]
+        output: PathBuf,
+        /// Upgrade an older config without asking
+        #[clap(long)]
+        upgrade: bool,
     },
     /// Build MCP server from the specified codebase
     Build {
         source: PathBuf,
         #[clap(short, long)]
         output: Option<PathBuf>,
+        #[clap(flatten)]
+        config: ConfigArgs,
     },
     /// Watch mode with auto-rebuild
     Watch {
         source: PathBuf,
-        #[clap(short, long, default_value = "3000")]
-        port: u16,
+        /// Port for the MCP HTTP endpoint (default: `server.port`, 3000)
+        #[clap(short, long)]
+        port: Option<u16>,
+        /// Address the MCP HTTP endpoint binds to
+        #[clap(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+        /// Output directory whose index is served
+        #[clap(short, long, default_value = "./mcp-server")]
+        output: PathBuf,
+        #[clap(flatten)]
+        config: ConfigArgs,
     },
     /// Show codebase statistics
     Analyze {
         source: PathBuf,
+        /// Report format
+        #[clap(short, long, value_enum, default_value = "table")]
+        format: mcp_forge::analyzer::report::ReportFormat,
+        /// Write the report to a file instead of stdout
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+        #[clap(flatten)]
+        config: ConfigArgs,
+    },
+    /// Serve a built index as an MCP server over stdio
+    Serve {
+        /// Output directory produced by `build`
+        #[clap(default_value = "./mcp-server")]
+        output: PathBuf,
+        /// Serve over streamable HTTP on this port instead of stdio
+        #[clap(short, long)]
+        port: Option<u16>,
+        /// Address the HTTP endpoint binds to
+        #[clap(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+    },
+    /// Query a built index without starting a server
+    Query {
+        /// Output directory produced by `build`
+        #[clap(short, long, default_value = "./mcp-server")]
+        output: PathBuf,
+        /// Print JSON instead of tables
+        #[clap(long, global = true)]
+        json: bool,
+        #[clap(subcommand)]
+        verb: mcp_forge::query::QueryVerb,
+    },
+    /// Check the embedding backend, templates, output directory and index
+    Doctor {
+        /// Project root
+        #[clap(default_value = ".")]
+        source: PathBuf,
+        /// Output directory produced by `build`
+        #[clap(short, long, default_value = "./mcp-server")]
+        output: PathBuf,
+        #[clap(flatten)]
+        config: ConfigArgs,
     },
 }
 
@@ -49,15 +166,46 @@ fn main() {
     info!("Starting MCP-Forge CLI");
 
     match cli.command {
-        Commands::Init { name } => {
-            info!("Initializing MCP-Forge for project: {}", name);
-            println!("MCP-Forge initialized for project: {}", name);
+        Commands::Init { path, name, output, upgrade } => {
+            use mcp_forge::init::Workspace;
+            use std::io::{BufRead, IsTerminal, Write};
+            info!("Initializing MCP-Forge in {:?}", path);
+            let workspace = Workspace { name, output, ..Workspace::new(&path) };
+            let mut confirm = |question: &str| {
+                if upgrade {
+                    return true;
+                }
+                if !std::io::stdin().is_terminal() {
+                    println!("{} (re-run with --upgrade to accept)", question);
+                    return false;
+                }
+                print!("{} [y/N] ", question);
+                let _ = std::io::stdout().flush();
+                let mut answer = String::new();
+                let _ = std::io::stdin().lock().read_line(&mut answer);
+                matches!(answer.trim(), "y" | "Y" | "yes")
+            };
+            match workspace.scaffold(&mut confirm) {
+                Ok(report) => {
+                    for (action, file) in &report.actions {
+                        println!("{:>9}  {}", action, file.display());
+                    }
+                    println!("\nMCP-Forge initialized for project: {}", report.config.project.name);
+                    println!("Client configuration (also saved to {}):\n", path.join(mcp_forge::init::CACHE_DIR).join(mcp_forge::init::CLIENTS_FILE).display());
+                    print!("{}", report.snippets.to_markdown());
+                }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
         }
-        Commands::Build { source, output } => {
+        Commands::Build { source, output, config } => {
+            let config = config.load(&source, None);
             let output_path = output.unwrap_or_else(|| std::path::PathBuf::from("./mcp-server"));
             let rt = tokio::runtime::Runtime::new().unwrap();
             rt.block_on(async {
-                match mcp_forge::server::builder::build(source.clone(), output_path.clone()).await {
+                match mcp_forge::server::builder::build(source.clone(), output_path.clone(), &config).await {
                     Ok(_) => info!("MCP server generated at {:?}", output_path),
                     Err(e) => {
                         error!("Build failed: {}", e);
@@ -66,25 +214,144 @@ fn main() {
                 }
             });
         }
-        }
-        Commands::Watch { source, port } => {
-            use mcp_forge::incremental::incremental::IncrementalBuilder;
-            use std::path::PathBuf;
-            let cache_dir = PathBuf::from(".mcp-forge-cache");
-            let builder = IncrementalBuilder::new(cache_dir.clone());
+        Commands::Watch { source, port, host, output, config } => {
+            let config = config.load(&source, port);
+            let port = config.server.port;
+            use mcp_forge::incremental::IncrementalBuilder;
+            use mcp_forge::server::http::HttpTransport;
+            use mcp_forge::server::native::McpServer;
+            let cache_dir = source.join(mcp_forge::init::CACHE_DIR);
+            let builder = IncrementalBuilder::new(source.clone(), cache_dir, &config.indexing);
+            let auto_reload = config.server.auto_reload;
             info!("Starting watch mode on {:?} (port {})...", source, port);
-            println!("Watching for file changes. Press Ctrl+C to exit.");
-            std::thread::spawn(move || {
-                builder.watch();
-            });
-            loop {
-                std::thread::sleep(std::time::Duration::from_secs(60));
+            if auto_reload {
+                println!("Watching for file changes. Press Ctrl+C to exit.");
+            } else {
+                println!("server.auto_reload is off: serving the index without watching for changes. Press Ctrl+C to exit.");
             }
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let opened = match mcp_forge::database::open_index_writable(&output).await {
+                    Ok(pool) => McpServer::open(&output).await.map(|server| (pool, server)),
+                    Err(e) => Err(e),
+                };
+                let (index, server) = match opened {
+                    Ok(opened) => opened,
+                    Err(e) => {
+                        error!("Watch failed: {}", e);
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                };
+                let transport = HttpTransport::new(server.with_resource_changes(auto_reload));
+                let addr = std::net::SocketAddr::new(host, port);
+                println!("MCP server available at http://{}/mcp", addr);
+                if !auto_reload {
+                    if let Err(e) = transport.serve(addr).await {
+                        error!("MCP HTTP transport stopped: {}", e);
+                        std::process::exit(1);
+                    }
+                    return;
+                }
+                let (updates_tx, mut updates) = tokio::sync::mpsc::unbounded_channel();
+                std::thread::spawn(move || {
+                    if let Err(e) = builder.watch_with(|update| {
+                        let _ = updates_tx.send(update);
+                    }) {
+                        error!("Watcher stopped: {}", e);
+                    }
+                });
+                let serving = transport.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serving.serve(addr).await {
+                        error!("MCP HTTP transport stopped: {}", e);
+                        std::process::exit(1);
+                    }
+                });
+                while let Some(update) = updates.recv().await {
+                    match update.apply(&index).await {
+                        Ok(()) => update.notify(&transport),
+                        Err(e) => error!("Failed to update the index for {}: {}", update.file_path, e),
+                    }
+                }
+                // The watcher only stops on errors, which it has logged.
+                std::process::exit(1);
+            });
         }
-        Commands::Analyze { source } => {
+        Commands::Analyze { source, format, output, config } => {
+            use mcp_forge::analyzer::report::analyze_with;
+            let config = config.load(&source, None);
             info!("Analyzing codebase at {:?}", source);
-            println!("Analysis started for codebase: {:?}", source);
-            // ...existing code...
+            let report = analyze_with(&source, &config);
+            for failure in &report.parse_failures {
+                warn!("Failed to parse {}: {}", failure.file, failure.error);
+            }
+            let rendered = report.render(format);
+            match output {
+                Some(path) => match std::fs::write(&path, rendered) {
+                    Ok(()) => println!("Analysis report written to {:?}", path),
+                    Err(e) => {
+                        error!("Failed to write report: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                None => print!("{}", rendered),
+            }
+        }
+        Commands::Serve { output, port, host } => {
+            use mcp_forge::server::http::HttpTransport;
+            use mcp_forge::server::native::McpServer;
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                // stdout carries the JSON-RPC stream; diagnostics go to stderr via the logger.
+                let result = match (McpServer::open(&output).await, port) {
+                    (Ok(server), Some(port)) => {
+                        info!("Serving {} from {:?} over HTTP", server.project_name(), output);
+                        HttpTransport::new(server).serve(std::net::SocketAddr::new(host, port)).await
+                    }
+                    (Ok(server), None) => {
+                        info!("Serving {} from {:?} over stdio", server.project_name(), output);
+                        server.serve_stdio().await
+                    }
+                    (Err(e), _) => Err(e),
+                };
+                if let Err(e) = result {
+                    error!("Serve failed: {}", e);
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            });
+        }
+        Commands::Query { output, json, verb } => {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let result = match mcp_forge::database::open_index(&output).await {
+                    Ok(pool) => verb.run(&pool, &mcp_forge::database::IndexInfo::load(&output)).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(value) if json => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+                    Ok(value) => print!("{}", verb.render_table(&value)),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            });
+        }
+        Commands::Doctor { source, output, config } => {
+            use mcp_forge::doctor::Doctor;
+            let doctor = Doctor {
+                config_file: config.config.clone(),
+                overrides: config.overrides(None),
+                ..Doctor::new(&source, &output)
+            };
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let report = rt.block_on(doctor.run());
+            print!("{}", report.render_table());
+            if !report.is_healthy() {
+                std::process::exit(1);
+            }
         }
     }
-}
\ No newline at end of file
+}
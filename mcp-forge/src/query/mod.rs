@@ -0,0 +1,131 @@
+//! Offline queries against a built index, backing `mcp-forge query`.
+//!
+//! Every verb runs the same lookup as the corresponding MCP tool, so the CLI
+//! and the servers always agree on results.
+
+use clap::Subcommand;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::database::IndexInfo;
+use crate::error::McpResult;
+use crate::server::tools;
+use crate::table::render as table;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum QueryVerb {
+    /// Find symbols by full or partial name
+    Symbol {
+        name: String,
+        #[clap(short, long)]
+        kind: Option<String>,
+    },
+    /// List the symbols that reference a symbol
+    Refs {
+        name: String,
+    },
+    /// Trace what a symbol depends on
+    Deps {
+        name: String,
+        #[clap(short, long, default_value = "2")]
+        depth: i64,
+    },
+    /// List detected design patterns
+    Patterns {
+        pattern_type: Option<String>,
+    },
+    /// Rank symbols against a natural-language query
+    Search {
+        query: String,
+        #[clap(short, long, default_value = "10")]
+        limit: i64,
+    },
+}
+
+impl QueryVerb {
+    /// The MCP tool and arguments this verb maps onto.
+    pub fn tool_call(&self) -> (&'static str, Value) {
+        match self {
+            QueryVerb::Symbol { name, kind } => ("find_symbol", json!({ "name": name, "kind": kind })),
+            QueryVerb::Refs { name } => ("find_references", json!({ "symbol_name": name })),
+            QueryVerb::Deps { name, depth } => ("analyze_dependencies", json!({ "symbol_name": name, "depth": depth })),
+            QueryVerb::Patterns { pattern_type } => ("find_patterns", json!({ "pattern_type": pattern_type })),
+            QueryVerb::Search { query, limit } => ("semantic_search", json!({ "query": query, "limit": limit })),
+        }
+    }
+
+    pub async fn run(&self, pool: &SqlitePool, index: &IndexInfo) -> McpResult<Value> {
+        let (tool, args) = self.tool_call();
+        tools::call_tool(pool, index, tool, &args).await
+    }
+
+    pub fn render_table(&self, result: &Value) -> String {
+        match self {
+            QueryVerb::Symbol { .. } => table(
+                &["NAME", "KIND", "LOCATION", "SCOPE"],
+                rows(result).map(|s| vec![text(&s["name"]), text(&s["kind"]), location(s), text(&s["scope"])]),
+            ),
+            QueryVerb::Refs { .. } => {
+                let header = format!("References to {}\n", text(&result["symbol"]["id"]));
+                header + &table(
+                    &["FROM", "KIND", "LOCATION"],
+                    rows(&result["references"]).map(|r| vec![text(&r["from"]), text(&r["kind"]), location(&r["symbol"])]),
+                )
+            }
+            QueryVerb::Deps { .. } => {
+                let edge_rows = |edges: &Value| {
+                    rows(edges)
+                        .map(|e| vec![text(&e["depth"]), text(&e["from"]), text(&e["kind"]), text(&e["to"])])
+                        .collect::<Vec<_>>()
+                };
+                format!(
+                    "Dependencies of {}\n{}\nDependents\n{}",
+                    text(&result["symbol"]["id"]),
+                    table(&["DEPTH", "FROM", "KIND", "TO"], edge_rows(&result["dependencies"]).into_iter()),
+                    table(&["DEPTH", "FROM", "KIND", "TO"], edge_rows(&result["dependents"]).into_iter()),
+                )
+            }
+            QueryVerb::Patterns { .. } => table(
+                &["NAME", "TYPE", "OCCURRENCES", "LOCATIONS"],
+                rows(result).map(|p| {
+                    let locations = match &p["locations"] {
+                        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(", "),
+                        other => text(other),
+                    };
+                    vec![text(&p["name"]), text(&p["pattern_type"]), text(&p["occurrences"]), locations]
+                }),
+            ),
+            QueryVerb::Search { .. } => table(
+                &["SCORE", "NAME", "KIND", "LOCATION"],
+                rows(result).map(|r| {
+                    let score = r["score"].as_f64().map(|s| format!("{:.2}", s)).unwrap_or_default();
+                    vec![score, text(&r["symbol"]["name"]), text(&r["symbol"]["kind"]), location(&r["symbol"])]
+                }),
+            ),
+        }
+    }
+}
+
+fn rows(value: &Value) -> impl Iterator<Item = &Value> {
+    value.as_array().into_iter().flatten()
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn location(symbol: &Value) -> String {
+    let location = match (symbol["file_path"].as_str(), symbol["start_line"].as_i64(), symbol["end_line"].as_i64()) {
+        (Some(path), Some(start), Some(end)) => format!("{}:{}-{}", path, start, end),
+        (Some(path), _, _) => path.to_string(),
+        _ => return "-".to_string(),
+    };
+    match (symbol["region_kind"].as_str(), symbol["region_index"].as_i64()) {
+        (Some(kind), Some(index)) => format!("{} ({} {})", location, kind, index),
+        _ => location,
+    }
+}
@@ -1,132 +1,342 @@
+//! `mcp-forge build`: index a source tree into `database.sqlite` and render
+//! the MCP server around it.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
 use indicatif::{ProgressBar, ProgressStyle};
+use log::{debug, warn};
+use serde_json::json;
+use sqlx::Acquire;
+
+use crate::analyzer::content::{SkipReason, SkippedFile};
+use crate::analyzer::diagnostics::{ParseDiagnostics, ParseHealth};
+use crate::analyzer::embedded;
+use crate::analyzer::languages::{registry, Project};
+use crate::analyzer::parser::{CodeParser, ParsedFile, ParserPool};
+use crate::analyzer::patterns::{pattern_category, PatternDetector};
+use crate::analyzer::symbols::{Symbol, SymbolExtractor};
+use crate::analyzer::tags::TagQueries;
+use crate::config::ForgeConfig;
+use crate::database::queries::FileRecord;
+use crate::database::{self, queries};
+use crate::embeddings::generator::{EmbeddingGen, EmbeddingGenerator, DEFAULT_MODEL};
+use crate::error::{McpError, McpResult};
+use crate::knowledge::graph::{KnowledgeGraph, KnowledgeGraphOps, SymbolNode};
+
+/// Texts sent to the embedding script per run.
+const EMBEDDING_BATCH: usize = 256;
+
+/// Parse results of one discovered file; embedded code has one region per block or cell.
+struct FileEntry {
+    language: &'static str,
+    lines: usize,
+    diagnostics: ParseDiagnostics,
+    encoding: &'static str,
+    skip_reason: Option<String>,
+}
+
+pub async fn build(source: PathBuf, output: PathBuf, forge_config: &ForgeConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let relative = |path: &Path| path.strip_prefix(&source).unwrap_or(path).to_string_lossy().replace('\\', "/");
+
+    // 1. Collect source files (respect .gitignore, exclude patterns, size limit and languages)
+    let discovery = crate::analyzer::discover(&source, &forge_config.indexing);
+    let source_files = discovery.files;
+    let mut skipped_files: Vec<(PathBuf, SkipReason)> = discovery.skipped;
+    let mut languages: Vec<String> = source_files
+        .iter()
+        .filter_map(|file| CodeParser::language_for(file))
+        .map(str::to_string)
+        .collect();
+    languages.sort();
+    languages.dedup();
 
-pub async fn build(source: std::path::PathBuf, output: std::path::PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    use crate::error::{McpError, McpResult};
-
-    // 1. Collect source files (respect .gitignore)
-    let source_files = walkdir::WalkDir::new(&source)
-        .into_iter()
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            if entry.file_type().is_file() {
-                Some(entry.path().to_path_buf())
-            } else {
-                None
+    // 2. Parse files with Tree-sitter (parallel processing with rayon, one parser per thread);
+    //    Markdown, notebooks and Vue/Svelte components yield one ParsedFile per code region.
+    //    Binary, minified and generated files come back with their skip reason instead
+    let parsers = ParserPool::new(&forge_config.indexing);
+    let mut parsed_files: Vec<ParsedFile> = Vec::new();
+    let code_files: Vec<PathBuf> = source_files
+        .iter()
+        .filter(|file| CodeParser::language_for(file).is_some() || embedded::is_host(file))
+        .cloned()
+        .collect();
+    for (path, parsed) in parsers.parse_files(&code_files) {
+        match parsed {
+            Ok(parsed) => parsed_files.push(parsed),
+            Err(reason) => {
+                debug!("Skipping {:?}: {}", path, reason);
+                skipped_files.push((path, reason));
             }
-        })
-        .collect::<Vec<_>>();
-
-    // 2. Parse files with Tree-sitter (parallel processing with rayon)
-    let parsed_files: Vec<ParsedFile> = rayon::scope(|s| {
-        let mut results = Vec::new();
-        for file in source_files {
-            s.spawn(|_| {
-                if let Ok(parsed) = CodeParser::parse_file(&file) {
-                    results.push(parsed);
-                }
-            });
         }
-        results
+    }
+    skipped_files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Files that are mostly syntax errors are left out, and so are broken
+    // blocks or cells on their own; every file's diagnostics are kept
+    let mut files: BTreeMap<String, FileEntry> = BTreeMap::new();
+    parsed_files.retain(|parsed| {
+        let skip_reason = parsed.diagnostics.skip_reason(forge_config.indexing.max_error_ratio);
+        let file = files.entry(relative(&parsed.path)).or_insert_with(|| FileEntry {
+            language: parsed.language,
+            lines: 0,
+            diagnostics: ParseDiagnostics::default(),
+            encoding: parsed.encoding.as_str(),
+            skip_reason: None,
+        });
+        file.lines += parsed.source.lines().count();
+        file.diagnostics.merge(&parsed.diagnostics);
+        if parsed.region.is_none() {
+            file.skip_reason = skip_reason.clone();
+        }
+        skip_reason.is_none()
     });
+    let mut parse_health = ParseHealth::default();
+    for (path, file) in &files {
+        parse_health.record(path, &file.diagnostics, file.skip_reason.clone());
+    }
+    parse_health.finish();
+    let skipped_files: Vec<(String, SkipReason)> = skipped_files.into_iter().map(|(path, reason)| (relative(&path), reason)).collect();
 
-    // 3. Extract symbols from AST and detect patterns per file/module
-    let mut symbol_extractor = SymbolExtractor::new();
-    let mut all_patterns = Vec::new();
+    // 3. Extract symbols and relationships from the AST, and detect patterns per file
+    let queries = TagQueries::for_indexing(&forge_config.indexing).unwrap_or_else(|e| {
+        warn!("Using the built-in tag queries: {}", e);
+        TagQueries::builtin()
+    });
+    let extractor = SymbolExtractor::with_queries(queries);
+    let project = Project::from_paths(&source, &source_files);
+    let detector = PatternDetector::new();
+    let mut symbols: Vec<Symbol> = Vec::new();
+    let mut knowledge_graph = KnowledgeGraph::new();
+    let mut patterns: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
     for parsed in &parsed_files {
-        let file_path = parsed.file_path(); // Assume ParsedFile has file_path()
-        let ext = std::path::Path::new(&file_path)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("");
-        let module = file_path.split('/').nth(1).unwrap_or("");
-        symbol_extractor.extract(parsed);
-        let code = std::fs::read_to_string(&file_path).unwrap_or_default();
-        let pattern_detector = PatternDetector::new();
-        let detected = pattern_detector.detect_patterns(&code, ext);
-        for (name, confidence) in detected {
-            // Store pattern with module context
-            all_patterns.push((name, ext.to_string(), module.to_string(), confidence, file_path.clone()));
+        let file_path = relative(&parsed.path);
+        symbols.extend(extractor.extract_parsed(parsed, &file_path));
+        for relationship in extractor.extract_parsed_relationships(parsed, &file_path, &project) {
+            knowledge_graph.add_relationship(relationship);
+        }
+        if !forge_config.analysis.patterns {
+            continue;
+        }
+        // Embedded code and scripts recognised by their `#!` line use their language's main extension
+        let ext = match (parsed.region, parsed.path.extension().and_then(|e| e.to_str())) {
+            (None, Some(ext)) => ext,
+            _ => registry().get(parsed.language).and_then(|language| language.extensions().first().copied()).unwrap_or(""),
+        };
+        for name in detector.detect_patterns(&parsed.source, ext).into_keys() {
+            patterns.entry(name).or_default().insert(file_path.clone());
+        }
+    }
+    for relationship in registry().project_relationships(&project) {
+        knowledge_graph.add_relationship(relationship);
+    }
+    let mut seen = HashSet::new();
+    symbols.retain(|symbol| {
+        let first = seen.insert(symbol.id.clone());
+        if !first {
+            debug!("Dropping duplicate symbol {}", symbol.id);
         }
+        first
+    });
+    let nodes: Vec<SymbolNode> = symbols.iter().map(SymbolNode::from).collect();
+    knowledge_graph.build(&nodes);
+
+    // 4. Render server templates (embedded, or overlaid by the configured template dir) into output
+    let templates = crate::server::templates::TemplateSet::for_config(forge_config)?;
+    let vars = crate::server::templates::TemplateVars::new(forge_config, languages);
+    templates.write_to(&output, &vars)?;
+
+    // 5. Generate embeddings with the script the templates ship (batch processing)
+    let embeddings = if forge_config.analysis.embeddings {
+        generate_embeddings(&output, &symbols).await
+    } else {
+        Vec::new()
+    };
+
+    // 6. Store everything in SQLite, in one transaction
+    let pool = database::create_index(&output).await?;
+    let mut tx = pool.begin().await.map_err(|e| McpError::Database(e.to_string()))?;
+    let progress = progress_bar(symbols.len(), "green", "Storing symbols in database");
+    for symbol in &symbols {
+        store_symbol(&mut *tx, symbol).await?;
+        progress.inc(1);
     }
+    progress.finish_with_message("Symbols stored");
 
-    // 4. Build knowledge graph
-    let mut knowledge_graph = KnowledgeGraph::new();
-    knowledge_graph.build(&symbol_extractor.symbols);
-
-    // 5. Patterns for all files/modules
-    // Convert all_patterns to pattern DB objects as needed
-    let patterns = all_patterns; // Replace with proper struct if needed
-
-    // 6. Generate embeddings (batch processing)
-    let embeddings = EmbeddingGenerator::generate(&symbol_extractor.symbols);
-
-    // 7. Store everything in SQLite
-    let db = Database::connect(&output.join("database.sqlite")).await?;
-    let pb_db_symbols = ProgressBar::new(symbol_extractor.symbols.len() as u64);
-    pb_db_symbols.set_style(ProgressStyle::default_bar()
-        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.green/blue}] {pos}/{len} {msg}")
-        .progress_chars("##-"));
-    pb_db_symbols.set_message("Storing symbols in database");
-    for symbol in &symbol_extractor.symbols {
-        db.store_symbol(symbol).await?;
-        pb_db_symbols.inc(1);
-    }
-    pb_db_symbols.finish_with_message("Symbols stored");
-
-    let pb_db_rels = ProgressBar::new(knowledge_graph.relationships.len() as u64);
-    pb_db_rels.set_style(ProgressStyle::default_bar()
-        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-        .progress_chars("##-"));
-    pb_db_rels.set_message("Storing relationships in database");
-    for rel in &knowledge_graph.relationships {
-        db.store_relationship(rel).await?;
-        pb_db_rels.inc(1);
-    }
-    pb_db_rels.finish_with_message("Relationships stored");
-
-    let pb_db_emb = ProgressBar::new(embeddings.len() as u64);
-    pb_db_emb.set_style(ProgressStyle::default_bar()
-        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.magenta/blue}] {pos}/{len} {msg}")
-        .progress_chars("##-"));
-    pb_db_emb.set_message("Storing embeddings in database");
-    for emb in &embeddings {
-        db.store_embedding(emb).await?;
-        pb_db_emb.inc(1);
-    }
-    pb_db_emb.finish_with_message("Embeddings stored");
-
-    let pb_db_pat = ProgressBar::new(patterns.len() as u64);
-    pb_db_pat.set_style(ProgressStyle::default_bar()
-        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.yellow/blue}] {pos}/{len} {msg}")
-        .progress_chars("##-"));
-    pb_db_pat.set_message("Storing patterns in database");
-    for pat in &patterns {
-        db.store_pattern(pat).await?;
-        pb_db_pat.inc(1);
-    }
-    pb_db_pat.finish_with_message("Patterns stored");
-
-    // 8. Copy server template to output
-    std::fs::copy("templates/mcp-server/server.py", output.join("server.py")).map_err(|e| McpError::Transport(e.to_string()))?;
-    std::fs::copy("templates/mcp-server/requirements.txt", output.join("requirements.txt")).map_err(|e| McpError::Transport(e.to_string()))?;
-    std::fs::copy("templates/mcp-server/start.sh", output.join("start.sh")).map_err(|e| McpError::Transport(e.to_string()))?;
-
-    // 9. Copy database to output directory
-    std::fs::copy(output.join("database.sqlite"), output.join("database.sqlite")).map_err(|e| McpError::Transport(e.to_string()))?;
-
-    // 10. Generate config.json with project metadata
+    // Calls into libraries and unresolved names have no symbol to point at
+    let relationships: Vec<_> = knowledge_graph
+        .relationships()
+        .iter()
+        .filter(|relationship| seen.contains(&relationship.from) && seen.contains(&relationship.to))
+        .collect();
+    let progress = progress_bar(relationships.len(), "cyan", "Storing relationships in database");
+    for relationship in relationships {
+        queries::insert_relationship(&mut *tx, &relationship.from, &relationship.to, &relationship.kind, relationship.strength).await?;
+        progress.inc(1);
+    }
+    progress.finish_with_message("Relationships stored");
+
+    let progress = progress_bar(embeddings.len(), "magenta", "Storing embeddings in database");
+    for (symbol_id, content, vector) in &embeddings {
+        let bytes = vector.iter().flat_map(|value| value.to_le_bytes()).collect();
+        queries::insert_embedding(&mut *tx, symbol_id, bytes, content, DEFAULT_MODEL).await?;
+        progress.inc(1);
+    }
+    progress.finish_with_message("Embeddings stored");
+
+    let progress = progress_bar(patterns.len(), "yellow", "Storing patterns in database");
+    for (name, files) in &patterns {
+        let locations = serde_json::to_string(files)?;
+        queries::insert_pattern(&mut *tx, name, pattern_category(name), files.len() as i32, &locations).await?;
+        progress.inc(1);
+    }
+    progress.finish_with_message("Patterns stored");
+
+    for (path, file) in &files {
+        let record = FileRecord {
+            path: path.clone(),
+            language: Some(file.language.to_string()),
+            lines: Some(file.lines as i64),
+            error_count: file.diagnostics.error_count as i64,
+            error_ratio: file.diagnostics.error_ratio(),
+            timed_out: file.diagnostics.timed_out,
+            skip_reason: file.skip_reason.clone(),
+            skip_kind: file.skip_reason.as_ref().map(|_| {
+                if file.diagnostics.timed_out { "timed_out" } else { "syntax_errors" }.to_string()
+            }),
+            encoding: Some(file.encoding.to_string()),
+        };
+        queries::insert_file(&mut *tx, &record).await?;
+        queries::insert_parse_diagnostics(&mut *tx, path, &file.diagnostics.errors).await?;
+    }
+    for (path, reason) in &skipped_files {
+        let record = FileRecord {
+            path: path.clone(),
+            language: CodeParser::language_for(Path::new(path)).map(str::to_string),
+            skip_reason: Some(reason.to_string()),
+            skip_kind: Some(reason.kind().to_string()),
+            ..Default::default()
+        };
+        queries::insert_file(&mut *tx, &record).await?;
+    }
+    tx.commit().await.map_err(|e| McpError::Database(e.to_string()))?;
+    pool.close().await;
+
+    // 7. Generate config.json with project metadata
     let config = json!({
         "project": {
-            "name": "MyProject",
-            "description": "AI expert for my codebase"
+            "name": forge_config.project.name,
+            "description": forge_config.project.description,
+            "root": source.canonicalize().unwrap_or_else(|_| source.clone())
+        },
+        "analysis": {
+            "complexity_threshold": forge_config.analysis.complexity_threshold
+        },
+        "server": {
+            "port": forge_config.server.port,
+            "auto_reload": forge_config.server.auto_reload,
+            "target": forge_config.server.target
         }
     });
     std::fs::write(output.join("config.json"), serde_json::to_string(&config)?)?;
 
-    // 11. Make start.sh executable
-    let mut permissions = std::fs::metadata(output.join("start.sh")).map_err(|e| McpError::Transport(e.to_string()))?.permissions();
-    permissions.set_mode(0o755);
-    std::fs::set_permissions(output.join("start.sh"), permissions).map_err(|e| McpError::Transport(e.to_string()))?;
+    // 8. Report which files the index is blind to
+    let skipped_files: Vec<SkippedFile> = skipped_files.iter().map(|(path, reason)| SkippedFile::new(path.as_str(), reason)).collect();
+    println!("{}", parse_health.render_table());
+    println!("{}", crate::analyzer::content::render_skipped(&skipped_files));
+    std::fs::write(
+        output.join("build-report.json"),
+        serde_json::to_string_pretty(&json!({ "parse_health": parse_health, "skipped_files": skipped_files }))?,
+    )?;
+
+    Ok(())
+}
 
+/// Write one symbol and everything known about it.
+pub(crate) async fn store_symbol(conn: impl Acquire<'_, Database = sqlx::Sqlite>, symbol: &Symbol) -> McpResult<()> {
+    let mut conn = conn.acquire().await.map_err(|e| McpError::Database(e.to_string()))?;
+    queries::insert_symbol(
+        &mut *conn,
+        &symbol.id,
+        &symbol.name,
+        &symbol.kind,
+        &symbol.file_path,
+        symbol.start_line as i32,
+        symbol.end_line as i32,
+        &symbol.scope,
+        symbol.documentation.as_deref(),
+        symbol.signature.as_deref(),
+    )
+    .await?;
+    queries::set_symbol_scope(&mut *conn, &symbol.id, &symbol.qualified_name, symbol.parent_id.as_deref()).await?;
+    if let Some(callable) = &symbol.callable {
+        queries::set_symbol_callable(&mut *conn, &symbol.id, callable).await?;
+    }
+    if let Some(doc_tags) = &symbol.doc_tags {
+        queries::set_symbol_doc_tags(&mut *conn, &symbol.id, doc_tags).await?;
+    }
+    if let Some(type_name) = &symbol.type_name {
+        queries::set_symbol_type(&mut *conn, &symbol.id, type_name).await?;
+    }
+    if symbol.visibility.is_some() || !symbol.attributes.is_empty() {
+        queries::set_symbol_attributes(&mut *conn, &symbol.id, symbol.visibility.as_deref(), &symbol.attributes).await?;
+    }
+    if let Some(region) = symbol.region {
+        queries::set_symbol_region(&mut *conn, &symbol.id, region.kind.as_str(), region.index as i64).await?;
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// `(symbol id, embedded text, vector)` for every symbol; empty, with a
+/// warning, when the Python backend is missing or fails.
+async fn generate_embeddings(output: &Path, symbols: &[Symbol]) -> Vec<(String, String, Vec<f32>)> {
+    let generator = EmbeddingGenerator::new(output.to_path_buf(), DEFAULT_MODEL.to_string());
+    if !generator.script_path().is_file() {
+        warn!("Skipping embeddings: {:?} is only generated for the Python server", generator.script_path());
+        return Vec::new();
+    }
+    let texts: Vec<(String, String)> = symbols
+        .iter()
+        .map(|symbol| {
+            let head = symbol.signature.clone().unwrap_or_else(|| format!("{} {}", symbol.kind, symbol.qualified_name));
+            let text = match &symbol.documentation {
+                Some(documentation) => format!("{}\n{}", head, documentation),
+                None => head,
+            };
+            (symbol.id.clone(), text)
+        })
+        .collect();
+    let progress = progress_bar(texts.len(), "magenta", "Generating embeddings");
+    let generated = tokio::task::spawn_blocking(move || -> McpResult<Vec<(String, String, Vec<f32>)>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBEDDING_BATCH) {
+            let vectors = generator.generate_embeddings(batch.iter().map(|(_, text)| text.clone()).collect())?;
+            embeddings.extend(batch.iter().cloned().zip(vectors).map(|((id, text), vector)| (id, text, vector)));
+            progress.inc(batch.len() as u64);
+        }
+        progress.finish_with_message("Embeddings generated");
+        Ok(embeddings)
+    })
+    .await;
+    match generated {
+        Ok(Ok(embeddings)) => embeddings,
+        Ok(Err(e)) => {
+            warn!("Skipping embeddings: {}", e);
+            Vec::new()
+        }
+        Err(e) => {
+            warn!("Skipping embeddings: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn progress_bar(len: usize, color: &str, message: &'static str) -> ProgressBar {
+    let progress = ProgressBar::new(len as u64);
+    let template = format!("{{spinner:.green}} [{{elapsed_precise}}] [{{bar:40.{}/blue}}] {{pos}}/{{len}} {{msg}}", color);
+    if let Ok(style) = ProgressStyle::default_bar().template(&template) {
+        progress.set_style(style.progress_chars("##-"));
+    }
+    progress.set_message(message);
+    progress
+}
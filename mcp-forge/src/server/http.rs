@@ -0,0 +1,200 @@
+//! Streamable HTTP transport for the native MCP server.
+//!
+//! A single `/mcp` endpoint accepts JSON-RPC over `POST`, opens a server-sent
+//! event stream for server→client notifications on `GET`, and ends a session
+//! on `DELETE`, following the MCP streamable HTTP transport.
+
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use log::{info, warn};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+
+use crate::error::{McpError, McpResult};
+use crate::server::native::McpServer;
+use crate::server::protocol::JsonRpcRequest;
+
+pub const MCP_ENDPOINT: &str = "/mcp";
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+type Rejection = (StatusCode, &'static str);
+
+/// Where server→client notifications go. [`IndexUpdate::notify`](crate::incremental::IndexUpdate::notify)
+/// takes any `McpNotify` rather than an [`HttpTransport`], so announcing index
+/// changes does not tie the incremental module to one transport.
+pub trait McpNotify {
+    fn notify(&self, method: &str, params: Option<Value>);
+}
+
+struct Shared {
+    server: McpServer,
+    sessions: Mutex<HashSet<String>>,
+    events: broadcast::Sender<String>,
+}
+
+/// Cloneable handle to a running HTTP transport; clones share sessions and the event stream.
+#[derive(Clone)]
+pub struct HttpTransport {
+    shared: Arc<Shared>,
+}
+
+impl McpNotify for HttpTransport {
+    /// Broadcast a notification to every open `GET` event stream. Dropped when nobody listens.
+    fn notify(&self, method: &str, params: Option<Value>) {
+        let notification = JsonRpcRequest { jsonrpc: "2.0".to_string(), id: None, method: method.to_string(), params };
+        if let Ok(message) = serde_json::to_string(&notification) {
+            let _ = self.shared.events.send(message);
+        }
+    }
+}
+
+impl HttpTransport {
+    pub fn new(server: McpServer) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            shared: Arc::new(Shared { server, sessions: Mutex::new(HashSet::new()), events }),
+        }
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(MCP_ENDPOINT, get(handle_get).post(handle_post).delete(handle_delete))
+            .with_state(self.clone())
+    }
+
+    pub async fn serve(self, addr: SocketAddr) -> McpResult<()> {
+        let listener = TcpListener::bind(addr).await.map_err(|e| McpError::Transport(e.to_string()))?;
+        self.serve_listener(listener).await
+    }
+
+    /// Serve on an already bound listener (e.g. port 0 in tests).
+    pub async fn serve_listener(self, listener: TcpListener) -> McpResult<()> {
+        if let Ok(addr) = listener.local_addr() {
+            info!("MCP streamable HTTP transport listening on http://{}{}", addr, MCP_ENDPOINT);
+        }
+        axum::serve(listener, self.router())
+            .await
+            .map_err(|e| McpError::Transport(e.to_string()))
+    }
+
+    fn open_session(&self) -> String {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        self.shared.sessions.lock().unwrap().insert(id.clone());
+        id
+    }
+
+    fn has_session(&self, id: &str) -> bool {
+        self.shared.sessions.lock().unwrap().contains(id)
+    }
+
+    fn close_session(&self, id: &str) -> bool {
+        self.shared.sessions.lock().unwrap().remove(id)
+    }
+
+    /// Reject unknown session ids; requests without a session id are served statelessly.
+    fn check_session(&self, headers: &HeaderMap) -> Result<(), Rejection> {
+        match headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+            Some(id) if !self.has_session(id) => Err((StatusCode::NOT_FOUND, "Unknown session")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Browsers always send `Origin`; only accept local pages to guard against DNS rebinding.
+fn check_origin(headers: &HeaderMap) -> Result<(), Rejection> {
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return Ok(());
+    };
+    let host = origin
+        .split("://")
+        .nth(1)
+        .unwrap_or(origin)
+        .trim_end_matches('/');
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => host.split(':').next().unwrap_or(""),
+    };
+    if matches!(host, "localhost" | "127.0.0.1" | "::1") {
+        Ok(())
+    } else {
+        warn!("Rejected request from origin {}", origin);
+        Err((StatusCode::FORBIDDEN, "Origin not allowed"))
+    }
+}
+
+fn accepts(headers: &HeaderMap, mime: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.contains(mime) || v.contains("*/*"))
+}
+
+fn is_initialize(body: &str) -> bool {
+    serde_json::from_str::<Value>(body)
+        .map(|message| message["method"] == "initialize")
+        .unwrap_or(false)
+}
+
+async fn handle_post(State(transport): State<HttpTransport>, headers: HeaderMap, body: String) -> Response {
+    if let Err(rejection) = check_origin(&headers).and_then(|_| transport.check_session(&headers)) {
+        return rejection.into_response();
+    }
+
+    let Some(reply) = transport.shared.server.handle_message(&body).await else {
+        // Only notifications or responses were posted.
+        return StatusCode::ACCEPTED.into_response();
+    };
+
+    let mut response = if accepts(&headers, "text/event-stream") && !accepts(&headers, "application/json") {
+        let event = Event::default().event("message").data(reply);
+        Sse::new(tokio_stream::once(Ok::<_, Infallible>(event))).into_response()
+    } else {
+        ([(header::CONTENT_TYPE, "application/json")], reply).into_response()
+    };
+
+    if is_initialize(&body) {
+        if let Ok(value) = HeaderValue::from_str(&transport.open_session()) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+    }
+    response
+}
+
+async fn handle_get(State(transport): State<HttpTransport>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = check_origin(&headers).and_then(|_| transport.check_session(&headers)) {
+        return rejection.into_response();
+    }
+    if !accepts(&headers, "text/event-stream") {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
+
+    let events = BroadcastStream::new(transport.shared.events.subscribe()).filter_map(|message| {
+        // Lagging subscribers skip missed notifications rather than closing the stream.
+        message.ok().map(|data| Ok::<_, Infallible>(Event::default().event("message").data(data)))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+async fn handle_delete(State(transport): State<HttpTransport>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = check_origin(&headers) {
+        return rejection.into_response();
+    }
+    match headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+        Some(id) if transport.close_session(id) => StatusCode::OK.into_response(),
+        Some(_) => StatusCode::NOT_FOUND.into_response(),
+        None => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
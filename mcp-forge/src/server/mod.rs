@@ -1,3 +1,7 @@
-mod.rs
-
-pub mod builder;
\ No newline at end of file
+pub mod builder;
+pub mod http;
+pub mod native;
+pub mod protocol;
+pub mod resources;
+pub mod templates;
+pub mod tools;
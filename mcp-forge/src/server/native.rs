@@ -0,0 +1,210 @@
+//! Native MCP server speaking JSON-RPC over stdio, backed by `database.sqlite`.
+
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde_json::{json, Value};
+use sqlx::sqlite::SqlitePool;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::database::{self, IndexInfo};
+use crate::error::{McpError, McpResult};
+use crate::server::protocol::{
+    JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+    PARSE_ERROR, PROTOCOL_VERSION, RESOURCE_NOT_FOUND, SUPPORTED_PROTOCOL_VERSIONS,
+};
+use crate::server::resources::{self, KnowledgeResources};
+use crate::server::tools::{self, KnowledgeTools};
+
+pub struct McpServer {
+    pool: SqlitePool,
+    index: IndexInfo,
+    /// Where `file://` resources are read from; without it only symbol metadata is served.
+    source_root: Option<PathBuf>,
+    /// Whether `notifications/resources/list_changed` is sent, as in watch mode.
+    resources_change: bool,
+}
+
+impl McpServer {
+    pub fn new(pool: SqlitePool, project_name: String) -> Self {
+        Self { pool, index: IndexInfo::new(project_name), source_root: None, resources_change: false }
+    }
+
+    pub fn with_source_root(mut self, root: Option<PathBuf>) -> Self {
+        self.source_root = root;
+        self
+    }
+
+    /// Advertise `resources.listChanged`, for an index that is updated while served.
+    pub fn with_resource_changes(mut self, enabled: bool) -> Self {
+        self.resources_change = enabled;
+        self
+    }
+
+    /// Open a server over a `build` output directory (`database.sqlite` + `config.json`).
+    pub async fn open(output_dir: &Path) -> McpResult<Self> {
+        let pool = database::open_index(output_dir).await?;
+        let index = IndexInfo::load(output_dir);
+        Ok(Self { pool, index, source_root: database::source_root(output_dir), resources_change: false })
+    }
+
+    pub fn project_name(&self) -> &str {
+        &self.index.project_name
+    }
+
+    /// Handle one raw JSON-RPC message (single request or batch) and return the serialized reply, if any.
+    pub async fn handle_message(&self, raw: &str) -> Option<String> {
+        let message: Value = match serde_json::from_str(raw) {
+            Ok(message) => message,
+            Err(e) => {
+                let response = JsonRpcResponse::error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e));
+                return serde_json::to_string(&response).ok();
+            }
+        };
+
+        match message {
+            Value::Array(batch) if !batch.is_empty() => {
+                let mut responses = Vec::new();
+                for item in batch {
+                    if let Some(response) = self.handle_value(item).await {
+                        responses.push(response);
+                    }
+                }
+                if responses.is_empty() {
+                    None
+                } else {
+                    serde_json::to_string(&responses).ok()
+                }
+            }
+            other => {
+                let response = self.handle_value(other).await?;
+                serde_json::to_string(&response).ok()
+            }
+        }
+    }
+
+    async fn handle_value(&self, value: Value) -> Option<JsonRpcResponse> {
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        match serde_json::from_value::<JsonRpcRequest>(value) {
+            Ok(request) if request.jsonrpc == "2.0" => self.dispatch(request).await,
+            _ => Some(JsonRpcResponse::error(id, INVALID_REQUEST, "Invalid request")),
+        }
+    }
+
+    /// Serve newline-delimited JSON-RPC messages until the reader is closed.
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> McpResult<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await.map_err(|e| McpError::Transport(e.to_string()))? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(reply) = self.handle_message(&line).await {
+                writer.write_all(reply.as_bytes()).await.map_err(|e| McpError::Transport(e.to_string()))?;
+                writer.write_all(b"\n").await.map_err(|e| McpError::Transport(e.to_string()))?;
+                writer.flush().await.map_err(|e| McpError::Transport(e.to_string()))?;
+            }
+        }
+        info!("stdin closed, shutting down MCP server");
+        Ok(())
+    }
+
+    pub async fn serve_stdio(&self) -> McpResult<()> {
+        let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+        self.serve(stdin, tokio::io::stdout()).await
+    }
+
+    fn initialize_result(&self, params: &Value) -> Value {
+        let requested = params["protocolVersion"].as_str().unwrap_or(PROTOCOL_VERSION);
+        let version = if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) { requested } else { PROTOCOL_VERSION };
+        json!({
+            "protocolVersion": version,
+            "capabilities": {
+                "tools": { "listChanged": false },
+                "resources": { "subscribe": false, "listChanged": self.resources_change },
+                "prompts": { "listChanged": false },
+                "logging": {},
+            },
+            "serverInfo": {
+                "name": format!("{}_Expert", self.index.project_name),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params["name"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        if !KnowledgeTools.tool_definitions().iter().any(|t| t["name"] == name) {
+            return Err((INVALID_PARAMS, format!("Unknown tool: {}", name)));
+        }
+        let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        // Tool failures are reported inside the result so the model can see them.
+        let (text, is_error) = match tools::call_tool(&self.pool, &self.index, name, &args).await {
+            Ok(value) => (serde_json::to_string_pretty(&value).map_err(|e| (INTERNAL_ERROR, e.to_string()))?, false),
+            Err(e) => {
+                warn!("Tool {} failed: {}", name, e);
+                (e.to_string(), true)
+            }
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+
+    async fn read_resource(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params["uri"].as_str().ok_or((INVALID_PARAMS, "Missing resource uri".to_string()))?;
+        match resources::read_resource(&self.pool, self.source_root.as_deref(), uri).await {
+            Ok(Some(contents)) => Ok(contents),
+            Ok(None) => Err((RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri))),
+            Err(e) => Err(error_code(e)),
+        }
+    }
+
+    async fn get_prompt(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params["name"].as_str().ok_or((INVALID_PARAMS, "Missing prompt name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        resources::get_prompt(&self.pool, &self.index.project_name, self.source_root.as_deref(), name, &args)
+            .await
+            .map_err(error_code)
+    }
+}
+
+/// Bad arguments are the client's fault; anything else is ours.
+fn error_code(error: McpError) -> (i64, String) {
+    match error {
+        McpError::Dispatch(message) => (INVALID_PARAMS, message),
+        other => (INTERNAL_ERROR, other.to_string()),
+    }
+}
+
+impl McpServer {
+    /// Handle one request; notifications resolve to `None`.
+    pub async fn dispatch(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        let params = request.params.unwrap_or_else(|| json!({}));
+        let result = match request.method.as_str() {
+            "initialize" => Ok(self.initialize_result(&params)),
+            "ping" | "logging/setLevel" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": KnowledgeTools.tool_definitions() })),
+            "tools/call" => self.call_tool(&params).await,
+            "resources/list" => resources::list_resources(&self.pool, params["cursor"].as_str()).await.map_err(error_code),
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": KnowledgeResources.resource_templates() })),
+            "resources/read" => self.read_resource(&params).await,
+            "prompts/list" => Ok(json!({ "prompts": KnowledgeResources.prompt_definitions() })),
+            "prompts/get" => self.get_prompt(&params).await,
+            method if method.starts_with("notifications/") => return None,
+            method => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        let id = request.id?;
+        Some(match result {
+            Ok(value) => JsonRpcResponse::success(id, value),
+            Err((code, message)) => JsonRpcResponse::error(id, code, message),
+        })
+    }
+}
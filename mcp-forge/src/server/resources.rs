@@ -0,0 +1,335 @@
+//! MCP resources and prompts backing the native server.
+//!
+//! Every indexed file is a `file://<relative path>` resource and every symbol a
+//! `symbol://<id>` resource. The index only stores symbol metadata, so file
+//! contents are read from the source root recorded by `build`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path};
+
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::database::queries::{self, Symbol};
+use crate::error::{McpError, McpResult};
+use crate::server::tools::{is_test_path, module_of, resolve_symbol};
+
+pub const FILE_SCHEME: &str = "file://";
+pub const SYMBOL_SCHEME: &str = "symbol://";
+/// Lookup by (partial) symbol name.
+pub const SEARCH_SCHEME: &str = "symbols://";
+
+/// Resources returned per `resources/list` page.
+pub const PAGE_SIZE: usize = 200;
+
+/// Upper bound on the symbols and edges listed in one prompt.
+const PROMPT_LIST_LIMIT: usize = 50;
+
+pub struct KnowledgeResources;
+
+impl KnowledgeResources {
+    pub fn resource_templates(&self) -> Vec<Value> {
+        vec![
+            json!({
+                "uriTemplate": format!("{}{{+path}}", FILE_SCHEME),
+                "name": "Source file",
+                "description": "Contents of an indexed file, by path relative to the project root.",
+            }),
+            json!({
+                "uriTemplate": format!("{}{{+id}}", SYMBOL_SCHEME),
+                "name": "Symbol",
+                "description": "A symbol with its code, callers and callees, by id (`<file>::<name>`).",
+                "mimeType": "application/json",
+            }),
+            json!({
+                "uriTemplate": format!("{}{{name}}", SEARCH_SCHEME),
+                "name": "Symbol search",
+                "description": "Symbols whose name contains the given text.",
+                "mimeType": "application/json",
+            }),
+        ]
+    }
+
+    pub fn prompt_definitions(&self) -> Vec<Value> {
+        vec![
+            json!({
+                "name": "explain_module",
+                "description": "Explain a module using its files, symbols and dependencies from the knowledge base.",
+                "arguments": [
+                    { "name": "module", "description": "Module name, as listed by explain_architecture", "required": true },
+                ],
+            }),
+            json!({
+                "name": "review_change",
+                "description": "Review a change to a symbol with its current code, callers and callees.",
+                "arguments": [
+                    { "name": "symbol", "description": "Symbol name or id", "required": true },
+                    { "name": "change", "description": "Description or diff of the change", "required": false },
+                ],
+            }),
+        ]
+    }
+}
+
+/// MIME type reported for a source file.
+pub fn mime_type(path: &str) -> &'static str {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or("") {
+        "py" => "text/x-python",
+        "js" | "mjs" | "cjs" | "jsx" => "text/javascript",
+        "ts" | "tsx" => "text/x-typescript",
+        "rs" => "text/x-rust",
+        "java" => "text/x-java",
+        "go" => "text/x-go",
+        "md" => "text/markdown",
+        "json" => "application/json",
+        _ => "text/plain",
+    }
+}
+
+/// Read an indexed file below `root`; paths escaping the root are refused.
+pub fn read_source(root: Option<&Path>, relative: &str) -> Option<String> {
+    let relative = Path::new(relative);
+    if !relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return None;
+    }
+    std::fs::read_to_string(root?.join(relative)).ok()
+}
+
+/// The lines of `symbol` in its source file, if the source is available.
+pub fn symbol_code(root: Option<&Path>, symbol: &Symbol) -> Option<String> {
+    let (start, end) = (symbol.start_line?, symbol.end_line?);
+    let source = read_source(root, &symbol.file_path)?;
+    let lines: Vec<&str> = source
+        .lines()
+        .skip((start.max(1) - 1) as usize)
+        .take((end - start.max(1) + 1).max(0) as usize)
+        .collect();
+    if lines.is_empty() { None } else { Some(lines.join("\n")) }
+}
+
+fn location(symbol: &Symbol) -> String {
+    match (symbol.start_line, symbol.end_line) {
+        (Some(start), Some(end)) => format!("{}:{}-{}", symbol.file_path, start, end),
+        _ => symbol.file_path.clone(),
+    }
+}
+
+/// One page of file and symbol resources; `cursor` is the offset returned as `nextCursor`.
+///
+/// Indexed files come first, by path, then symbols; each page is read with
+/// `LIMIT`/`OFFSET` rather than loading the whole index.
+pub async fn list_resources(pool: &SqlitePool, cursor: Option<&str>) -> McpResult<Value> {
+    let offset = match cursor {
+        Some(cursor) => cursor.parse::<i64>().ok().filter(|offset| *offset >= 0).ok_or_else(|| McpError::Dispatch(format!("Invalid cursor: {}", cursor)))?,
+        None => 0,
+    };
+    let page_size = PAGE_SIZE as i64;
+    let file_count = queries::count_indexed_files(pool).await?;
+    let total = file_count + queries::count_symbols(pool).await?;
+
+    let mut page: Vec<Value> = Vec::new();
+    if offset < file_count {
+        for (path, count) in queries::get_indexed_files_page(pool, offset, page_size).await? {
+            page.push(json!({
+                "uri": format!("{}{}", FILE_SCHEME, path),
+                "name": path,
+                "description": format!("{} symbols", count),
+                "mimeType": mime_type(&path),
+            }));
+        }
+    }
+    let remaining = page_size - page.len() as i64;
+    if remaining > 0 {
+        for symbol in queries::get_symbols_page(pool, (offset - file_count).max(0), remaining).await? {
+            page.push(json!({
+                "uri": format!("{}{}", SYMBOL_SCHEME, symbol.id),
+                "name": symbol.name,
+                "description": format!("{} in {}", symbol.kind, location(&symbol)),
+                "mimeType": "application/json",
+            }));
+        }
+    }
+
+    let mut result = json!({ "resources": page });
+    if offset + page_size < total {
+        result["nextCursor"] = json!((offset + page_size).to_string());
+    }
+    Ok(result)
+}
+
+/// Contents of `uri` for `resources/read`, or `None` if nothing readable is indexed under it.
+pub async fn read_resource(pool: &SqlitePool, source_root: Option<&Path>, uri: &str) -> McpResult<Option<Value>> {
+    let (mime, text) = if let Some(path) = uri.strip_prefix(FILE_SCHEME) {
+        // Skipped files were never parsed and are not listed either.
+        if queries::get_file(pool, path).await?.is_none_or(|file| file.skip_reason.is_some()) {
+            return Ok(None);
+        }
+        // Indexed, but moved or deleted since the build.
+        let Some(text) = read_source(source_root, path) else {
+            return Ok(None);
+        };
+        (mime_type(path), text)
+    } else if let Some(id) = uri.strip_prefix(SYMBOL_SCHEME) {
+        let Some(symbol) = queries::get_symbol(pool, id).await? else {
+            return Ok(None);
+        };
+        let callers = queries::get_dependents(pool, id).await?;
+        let callees = queries::get_relationships(pool, id).await?;
+        let children = queries::get_child_symbols(pool, id).await?;
+        let parameters = queries::get_parameters(pool, id).await?;
+        let value = json!({
+            "symbol": symbol,
+            "parameters": parameters,
+            "children": children.iter().map(|s| s.id.clone()).collect::<Vec<_>>(),
+            "code": symbol_code(source_root, &symbol),
+            "callers": callers.iter().filter_map(|r| r.from_symbol_id.clone()).collect::<Vec<_>>(),
+            "callees": callees.iter().filter_map(|r| r.to_symbol_id.clone()).collect::<Vec<_>>(),
+        });
+        ("application/json", serde_json::to_string_pretty(&value).map_err(|e| McpError::Unknown(e.to_string()))?)
+    } else if let Some(name) = uri.strip_prefix(SEARCH_SCHEME) {
+        let symbols = queries::find_symbols(pool, name, None, 50).await?;
+        ("application/json", serde_json::to_string_pretty(&symbols).map_err(|e| McpError::Unknown(e.to_string()))?)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(json!({ "contents": [{ "uri": uri, "mimeType": mime, "text": text }] })))
+}
+
+/// Render a prompt by name for `prompts/get`.
+pub async fn get_prompt(
+    pool: &SqlitePool,
+    project_name: &str,
+    source_root: Option<&Path>,
+    name: &str,
+    args: &Value,
+) -> McpResult<Value> {
+    let arg = |key: &str| args.get(key).and_then(Value::as_str).filter(|s| !s.is_empty());
+    let required = |key: &str| arg(key).ok_or_else(|| McpError::Dispatch(format!("Missing required argument: {}", key)));
+    match name {
+        "explain_module" => explain_module(pool, project_name, required("module")?).await,
+        "review_change" => review_change(pool, project_name, source_root, required("symbol")?, arg("change")).await,
+        _ => Err(McpError::Dispatch(format!("Unknown prompt: {}", name))),
+    }
+}
+
+fn prompt_result(description: String, text: String) -> Value {
+    json!({
+        "description": description,
+        "messages": [{ "role": "user", "content": { "type": "text", "text": text } }],
+    })
+}
+
+fn push_list(text: &mut String, heading: &str, items: impl Iterator<Item = String>, empty: &str) {
+    text.push_str(&format!("\n## {}\n", heading));
+    let items: Vec<String> = items.collect();
+    if items.is_empty() {
+        text.push_str(&format!("- {}\n", empty));
+    }
+    for item in items.iter().take(PROMPT_LIST_LIMIT) {
+        text.push_str(&format!("- {}\n", item));
+    }
+    if items.len() > PROMPT_LIST_LIMIT {
+        text.push_str(&format!("- ... and {} more\n", items.len() - PROMPT_LIST_LIMIT));
+    }
+}
+
+async fn explain_module(pool: &SqlitePool, project_name: &str, module: &str) -> McpResult<Value> {
+    let symbols = queries::get_all_symbols(pool).await?;
+    let members: Vec<&Symbol> = symbols.iter().filter(|s| module_of(s) == module).collect();
+    if members.is_empty() {
+        let known: BTreeSet<&str> = symbols.iter().map(module_of).collect();
+        return Err(McpError::Dispatch(format!(
+            "Unknown module: {} (known: {})",
+            module,
+            known.into_iter().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    let module_by_id: HashMap<&str, &str> = symbols.iter().map(|s| (s.id.as_str(), module_of(s))).collect();
+    let mut uses: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut used_by: BTreeMap<&str, usize> = BTreeMap::new();
+    for rel in queries::get_all_relationships(pool).await? {
+        let from = rel.from_symbol_id.as_deref().and_then(|id| module_by_id.get(id)).copied();
+        let to = rel.to_symbol_id.as_deref().and_then(|id| module_by_id.get(id)).copied();
+        match (from, to) {
+            (Some(from), Some(to)) if from == module && to != module => *uses.entry(to).or_insert(0) += 1,
+            (Some(from), Some(to)) if to == module && from != module => *used_by.entry(from).or_insert(0) += 1,
+            _ => {}
+        }
+    }
+    let mut files: BTreeMap<&str, usize> = BTreeMap::new();
+    for symbol in &members {
+        *files.entry(symbol.file_path.as_str()).or_insert(0) += 1;
+    }
+
+    let mut text = format!("Explain the `{}` module of {}.\n", module, project_name);
+    push_list(&mut text, "Files", files.iter().map(|(path, count)| format!("{}{} ({} symbols)", FILE_SCHEME, path, count)), "none");
+    push_list(
+        &mut text,
+        "Symbols",
+        members.iter().map(|s| {
+            let doc = s.documentation.as_deref().and_then(|d| d.lines().next()).filter(|d| !d.trim().is_empty());
+            match doc {
+                Some(doc) => format!("{} `{}` ({}): {}", s.kind, s.name, location(s), doc.trim()),
+                None => format!("{} `{}` ({})", s.kind, s.name, location(s)),
+            }
+        }),
+        "none",
+    );
+    push_list(&mut text, "Depends on", uses.iter().map(|(m, n)| format!("`{}` ({} references)", m, n)), "no other modules");
+    push_list(&mut text, "Used by", used_by.iter().map(|(m, n)| format!("`{}` ({} references)", m, n)), "no other modules");
+    text.push_str(
+        "\nDescribe what this module is responsible for, its main entry points and how it \
+         interacts with the modules above. Read the listed file:// resources for the code.\n",
+    );
+    Ok(prompt_result(format!("Explain the {} module", module), text))
+}
+
+async fn review_change(
+    pool: &SqlitePool,
+    project_name: &str,
+    source_root: Option<&Path>,
+    name: &str,
+    change: Option<&str>,
+) -> McpResult<Value> {
+    let symbol = resolve_symbol(pool, name).await?;
+    let callers = queries::get_dependents(pool, &symbol.id).await?;
+    let callees = queries::get_relationships(pool, &symbol.id).await?;
+
+    let mut text = format!("Review a change to `{}` in {}.\n", symbol.id, project_name);
+    text.push_str(&format!("\n## Symbol\n- kind: {}\n- location: {}\n", symbol.kind, location(&symbol)));
+    if let Some(signature) = symbol.signature.as_deref().filter(|s| !s.is_empty()) {
+        text.push_str(&format!("- signature: `{}`\n", signature));
+    }
+    if let Some(doc) = symbol.documentation.as_deref().filter(|d| !d.is_empty()) {
+        text.push_str(&format!("- documentation: {}\n", doc.trim()));
+    }
+    if let Some(change) = change {
+        text.push_str(&format!("\n## Proposed change\n{}\n", change.trim_end()));
+    }
+    if let Some(code) = symbol_code(source_root, &symbol) {
+        text.push_str(&format!("\n## Current code\n```\n{}\n```\n", code));
+    }
+    let edge = |id: &str, kind: Option<&str>| {
+        let file = id.split("::").next().unwrap_or(id);
+        let test = if is_test_path(file) { ", test" } else { "" };
+        format!("`{}` ({}{})", id, kind.unwrap_or("references"), test)
+    };
+    push_list(
+        &mut text,
+        "Callers",
+        callers.iter().filter_map(|r| r.from_symbol_id.as_deref().map(|id| edge(id, r.kind.as_deref()))),
+        "none indexed",
+    );
+    push_list(
+        &mut text,
+        "Callees",
+        callees.iter().filter_map(|r| r.to_symbol_id.as_deref().map(|id| edge(id, r.kind.as_deref()))),
+        "none indexed",
+    );
+    text.push_str(
+        "\nCheck that the change keeps the behaviour the callers rely on, name the callers and \
+         tests that need updating, and point out missing tests.\n",
+    );
+    Ok(prompt_result(format!("Review a change to {}", symbol.id), text))
+}
@@ -0,0 +1,306 @@
+//! Files of the generated server.
+//!
+//! Each [`ServerTarget`] has a default set compiled into the binary. A
+//! template directory set with `--template-dir` or `server.template_dir` is
+//! laid over it: its files replace defaults of the same name and anything
+//! extra is added. Text files are rendered with `{{VARIABLE}}` placeholders.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ForgeConfig;
+use crate::error::{McpError, McpResult};
+use crate::server::tools::KnowledgeTools;
+
+/// Language of the generated server project.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerTarget {
+    /// FastMCP server (`server.py`), stdio or streamable HTTP
+    #[default]
+    Python,
+    /// Node server on the official MCP SDK (`src/index.ts`), stdio
+    Typescript,
+    /// Standalone Cargo project built from the native server's sources, stdio
+    Rust,
+}
+
+impl ServerTarget {
+    pub const ALL: &'static [ServerTarget] = &[ServerTarget::Python, ServerTarget::Typescript, ServerTarget::Rust];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ServerTarget::Python => "python",
+            ServerTarget::Typescript => "typescript",
+            ServerTarget::Rust => "rust",
+        }
+    }
+
+    /// Built-in templates, as (output path, content).
+    pub fn embedded_templates(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ServerTarget::Python => PYTHON_TEMPLATES,
+            ServerTarget::Typescript => TYPESCRIPT_TEMPLATES,
+            ServerTarget::Rust => RUST_TEMPLATES,
+        }
+    }
+
+    /// Files copied verbatim, without placeholder rendering.
+    pub fn embedded_sources(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ServerTarget::Rust => RUST_SHARED_SOURCES,
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for ServerTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ServerTarget {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        ServerTarget::ALL
+            .iter()
+            .copied()
+            .find(|target| target.name().eq_ignore_ascii_case(raw.trim()))
+            .ok_or_else(|| format!("unknown server target {:?} (expected python, typescript or rust)", raw))
+    }
+}
+
+const PYTHON_TEMPLATES: &[(&str, &str)] = &[
+    ("server.py", include_str!("../../templates/mcp-server/server.py")),
+    ("requirements.txt", include_str!("../../templates/mcp-server/requirements.txt")),
+    ("start.sh", include_str!("../../templates/mcp-server/start.sh")),
+    ("generate_embeddings.py", include_str!("../../templates/mcp-server/generate_embeddings.py")),
+];
+
+const TYPESCRIPT_TEMPLATES: &[(&str, &str)] = &[
+    ("package.json", include_str!("../../templates/mcp-server-typescript/package.json")),
+    ("tsconfig.json", include_str!("../../templates/mcp-server-typescript/tsconfig.json")),
+    ("src/index.ts", include_str!("../../templates/mcp-server-typescript/src/index.ts")),
+    ("start.sh", include_str!("../../templates/mcp-server-typescript/start.sh")),
+];
+
+const RUST_TEMPLATES: &[(&str, &str)] = &[
+    // Stored as `.tmpl` so cargo does not treat the template as a nested package.
+    ("Cargo.toml", include_str!("../../templates/mcp-server-rust/Cargo.toml.tmpl")),
+    ("src/main.rs", include_str!("../../templates/mcp-server-rust/src/main.rs")),
+    ("start.sh", include_str!("../../templates/mcp-server-rust/start.sh")),
+];
+
+/// The native server and the code it depends on, so the standalone server
+/// shares the schema and tool implementations with `mcp-forge serve`.
+const RUST_SHARED_SOURCES: &[(&str, &str)] = &[
+    ("src/error.rs", include_str!("../error.rs")),
+    ("src/database/mod.rs", include_str!("../database/mod.rs")),
+    ("src/database/queries.rs", include_str!("../database/queries.rs")),
+    ("src/database/records.rs", include_str!("../database/records.rs")),
+    ("src/database/schema.rs", include_str!("../database/schema.rs")),
+    ("src/server/native.rs", include_str!("native.rs")),
+    ("src/server/protocol.rs", include_str!("protocol.rs")),
+    ("src/server/resources.rs", include_str!("resources.rs")),
+    ("src/server/tools.rs", include_str!("tools.rs")),
+];
+
+/// Placeholders available to every template.
+pub const TEMPLATE_VARIABLES: &[&str] =
+    &["PROJECT_NAME", "PROJECT_DESCRIPTION", "SERVER_SLUG", "LANGUAGES", "TOOLS", "DB_PATH", "SERVER_PORT"];
+
+/// Values substituted for `{{VARIABLE}}` placeholders.
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    pub project_name: String,
+    pub project_description: String,
+    pub languages: Vec<String>,
+    pub tools: Vec<String>,
+    /// Database path relative to the output directory.
+    pub db_path: String,
+    pub server_port: u16,
+}
+
+impl TemplateVars {
+    /// Variables for `config`, with `languages` as found in the indexed files.
+    pub fn new(config: &ForgeConfig, languages: Vec<String>) -> Self {
+        let tools = KnowledgeTools
+            .tool_definitions()
+            .iter()
+            .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+            .collect();
+        Self {
+            project_name: config.project.name.clone(),
+            project_description: config.project.description.clone(),
+            languages,
+            tools,
+            db_path: "database.sqlite".to_string(),
+            server_port: config.server.port,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        match name {
+            "PROJECT_NAME" => Some(self.project_name.clone()),
+            "PROJECT_DESCRIPTION" => Some(self.project_description.clone()),
+            "SERVER_SLUG" => Some(server_slug(&self.project_name)),
+            "LANGUAGES" => Some(self.languages.join(", ")),
+            "TOOLS" => Some(self.tools.join(", ")),
+            "DB_PATH" => Some(self.db_path.clone()),
+            "SERVER_PORT" => Some(self.server_port.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Package and client-facing server name: lowercase, dash-separated.
+pub fn server_slug(project: &str) -> String {
+    let slug: String = project
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "mcp-forge".to_string() } else { slug }
+}
+
+/// Substitute `{{VARIABLE}}` placeholders; unknown upper-case names are an error
+/// so typos in custom templates do not ship. Other `{{...}}` text is left as is.
+pub fn render_str(template: &str, vars: &TemplateVars) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let name = after.find("}}").map(|end| &after[..end]).filter(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        });
+        match name {
+            Some(name) => {
+                let value = vars.get(name).ok_or_else(|| {
+                    format!("unknown variable {{{{{}}}}} (available: {})", name, TEMPLATE_VARIABLES.join(", "))
+                })?;
+                out.push_str(&value);
+                rest = &after[name.len() + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// The files to write into the output directory, keyed by relative path.
+#[derive(Debug, Clone)]
+pub struct TemplateSet {
+    files: BTreeMap<PathBuf, TemplateFile>,
+}
+
+#[derive(Debug, Clone)]
+struct TemplateFile {
+    content: Vec<u8>,
+    /// Where the file came from, for error messages.
+    origin: String,
+    render: bool,
+}
+
+impl Default for TemplateSet {
+    fn default() -> Self {
+        Self::embedded(ServerTarget::default())
+    }
+}
+
+impl TemplateSet {
+    pub fn embedded(target: ServerTarget) -> Self {
+        let embedded = |files: &[(&str, &str)], render: bool| {
+            files
+                .iter()
+                .map(|(name, content)| {
+                    let origin = format!("<embedded {}>/{}", target, name);
+                    (PathBuf::from(name), TemplateFile { content: content.as_bytes().to_vec(), origin, render })
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut files = BTreeMap::new();
+        files.extend(embedded(target.embedded_templates(), true));
+        files.extend(embedded(target.embedded_sources(), false));
+        Self { files }
+    }
+
+    /// The embedded set for `target` with every file under `dir` laid over it.
+    pub fn with_dir(dir: &Path, target: ServerTarget) -> McpResult<Self> {
+        if !dir.is_dir() {
+            return Err(McpError::Config(format!("Template directory {} does not exist", dir.display())));
+        }
+        let mut set = Self::embedded(target);
+        for entry in walkdir::WalkDir::new(dir).into_iter() {
+            let entry = entry.map_err(|e| McpError::Config(format!("Cannot read template directory: {}", e)))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path()).to_path_buf();
+            let content = std::fs::read(entry.path())
+                .map_err(|e| McpError::Config(format!("Cannot read template {}: {}", entry.path().display(), e)))?;
+            let origin = entry.path().display().to_string();
+            set.files.insert(relative, TemplateFile { content, origin, render: true });
+        }
+        Ok(set)
+    }
+
+    /// Templates for `config`: the configured target, overlaid by its template directory if set.
+    pub fn for_config(config: &ForgeConfig) -> McpResult<Self> {
+        match &config.server.template_dir {
+            Some(dir) => Self::with_dir(dir, config.server.target),
+            None => Ok(Self::embedded(config.server.target)),
+        }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Render every template into `output`; shell scripts are made executable.
+    pub fn write_to(&self, output: &Path, vars: &TemplateVars) -> McpResult<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for (relative, content) in self.render_all(vars)? {
+            let path = output.join(&relative);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| McpError::Transport(e.to_string()))?;
+            }
+            std::fs::write(&path, content).map_err(|e| McpError::Transport(format!("{}: {}", path.display(), e)))?;
+            #[cfg(unix)]
+            if relative.extension().is_some_and(|ext| ext == "sh") {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                    .map_err(|e| McpError::Transport(e.to_string()))?;
+            }
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Every file in the set with `{{VARIABLE}}` placeholders substituted.
+    pub fn render_all(&self, vars: &TemplateVars) -> McpResult<BTreeMap<PathBuf, Vec<u8>>> {
+        self.files
+            .iter()
+            .map(|(relative, file)| {
+                // Binary files (images, archives) and shared sources are copied untouched.
+                let rendered = match std::str::from_utf8(&file.content) {
+                    Ok(text) if file.render => render_str(text, vars)
+                        .map_err(|e| McpError::Config(format!("Template {}: {}", file.origin, e)))?
+                        .into_bytes(),
+                    _ => file.content.clone(),
+                };
+                Ok((relative.clone(), rendered))
+            })
+            .collect()
+    }
+}
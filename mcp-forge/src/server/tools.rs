@@ -0,0 +1,569 @@
+//! Tool implementations backing the native MCP server.
+//!
+//! Mirrors the tools of `templates/mcp-server/server.py`, answering from the
+//! SQLite knowledge base written by `build`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::database::queries::{self, Relationship, Symbol};
+use crate::database::IndexInfo;
+use crate::error::{McpError, McpResult};
+
+pub struct KnowledgeTools;
+
+/// Kinds of symbols with a body and parameters; Python routes and properties are functions too.
+pub const CALLABLE_KINDS: &[&str] = &["function", "method", "route", "property"];
+
+impl KnowledgeTools {
+    pub fn tool_definitions(&self) -> Vec<Value> {
+        vec![
+            tool("get_project_overview", "Get comprehensive project statistics and structure.", json!({}), &[]),
+            tool("find_symbol", "Find symbols by name and optional type. Functions and methods include their parameters.", json!({
+                "name": { "type": "string", "description": "Full or partial symbol name" },
+                "kind": { "type": "string", "description": "Symbol kind, e.g. function, class, method" }
+            }), &["name"]),
+            tool("find_references", "List the symbols that reference a symbol.", json!({
+                "symbol_name": { "type": "string", "description": "Symbol name or id" }
+            }), &["symbol_name"]),
+            tool("analyze_dependencies", "Trace dependencies for a symbol.", json!({
+                "symbol_name": { "type": "string", "description": "Symbol name or id" },
+                "depth": { "type": "integer", "default": 2 }
+            }), &["symbol_name"]),
+            tool("trace_data_flow", "Trace how data flows through the system.", json!({
+                "variable": { "type": "string" }
+            }), &["variable"]),
+            tool("find_patterns", "Find design patterns in codebase.", json!({
+                "pattern_type": { "type": "string", "description": "Pattern name, e.g. Singleton" }
+            }), &[]),
+            tool("analyze_complexity", "Analyze code complexity metrics (approximated from symbol length and call fan-out).", json!({
+                "module": { "type": "string" },
+                "threshold": { "type": "integer", "description": "Minimum score to report (default: analysis.complexity_threshold, 10)" }
+            }), &[]),
+            tool("suggest_refactoring", "Suggest refactoring opportunities.", json!({
+                "symbol": { "type": "string" }
+            }), &[]),
+            tool("semantic_search", "Search code using natural language.", json!({
+                "query": { "type": "string" },
+                "limit": { "type": "integer", "default": 10 }
+            }), &["query"]),
+            tool("get_test_coverage", "Analyze test coverage.", json!({
+                "module": { "type": "string" }
+            }), &[]),
+            tool("explain_architecture", "Explain the overall system architecture.", json!({}), &[]),
+        ]
+    }
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": { "type": "object", "properties": properties, "required": required },
+    })
+}
+
+/// Run a tool by name against the knowledge base.
+pub async fn call_tool(pool: &SqlitePool, index: &IndexInfo, name: &str, args: &Value) -> McpResult<Value> {
+    match name {
+        "get_project_overview" => get_project_overview(pool, &index.project_name).await,
+        "find_symbol" => {
+            let symbol = required_str(args, "name")?;
+            let symbols = queries::find_symbols(pool, symbol, optional_str(args, "kind"), 50).await?;
+            let mut results = Vec::with_capacity(symbols.len());
+            for symbol in symbols {
+                let mut value = json!(symbol);
+                if CALLABLE_KINDS.contains(&symbol.kind.as_str()) {
+                    value["parameters"] = json!(queries::get_parameters(pool, &symbol.id).await?);
+                }
+                results.push(value);
+            }
+            Ok(json!(results))
+        }
+        "find_references" => find_references(pool, required_str(args, "symbol_name")?).await,
+        "analyze_dependencies" => {
+            analyze_dependencies(pool, required_str(args, "symbol_name")?, int_arg(args, "depth", 2)).await
+        }
+        "trace_data_flow" => trace_data_flow(pool, required_str(args, "variable")?).await,
+        "find_patterns" => find_patterns(pool, optional_str(args, "pattern_type")).await,
+        "analyze_complexity" => {
+            analyze_complexity(pool, optional_str(args, "module"), int_arg(args, "threshold", index.complexity_threshold)).await
+        }
+        "suggest_refactoring" => suggest_refactoring(pool, optional_str(args, "symbol")).await,
+        "semantic_search" => {
+            semantic_search(pool, required_str(args, "query")?, int_arg(args, "limit", 10)).await
+        }
+        "get_test_coverage" => get_test_coverage(pool, optional_str(args, "module")).await,
+        "explain_architecture" => explain_architecture(pool, &index.project_name).await,
+        _ => Err(McpError::Dispatch(format!("Unknown tool: {}", name))),
+    }
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(Value::as_str).filter(|s| !s.is_empty())
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> McpResult<&'a str> {
+    optional_str(args, key).ok_or_else(|| McpError::Dispatch(format!("Missing required argument: {}", key)))
+}
+
+fn int_arg(args: &Value, key: &str, default: i64) -> i64 {
+    args.get(key).and_then(Value::as_i64).unwrap_or(default)
+}
+
+fn line_count(symbol: &Symbol) -> i64 {
+    match (symbol.start_line, symbol.end_line) {
+        (Some(start), Some(end)) if end >= start => end - start + 1,
+        _ => 0,
+    }
+}
+
+pub(crate) fn module_of(symbol: &Symbol) -> &str {
+    symbol.scope.as_deref().filter(|s| !s.is_empty()).unwrap_or("<root>")
+}
+
+pub(crate) fn is_test_path(path: &str) -> bool {
+    path.split(['/', '\\']).any(|segment| {
+        let segment = segment.to_lowercase();
+        segment == "tests" || segment == "test" || segment == "__tests__"
+            || segment.starts_with("test_") || segment.contains(".test.") || segment.contains(".spec.")
+            || segment.ends_with("_test.py") || segment.ends_with("_tests.rs")
+    })
+}
+
+/// Resolve a user-supplied name to a symbol: exact id first, then exact name, then partial name.
+pub(crate) async fn resolve_symbol(pool: &SqlitePool, name: &str) -> McpResult<Symbol> {
+    if let Some(symbol) = queries::get_symbol(pool, name).await? {
+        return Ok(symbol);
+    }
+    queries::find_symbols(pool, name, None, 1)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| McpError::Dispatch(format!("Symbol not found: {}", name)))
+}
+
+fn edge_json(rel: &Relationship, depth: i64) -> Value {
+    json!({
+        "from": rel.from_symbol_id,
+        "to": rel.to_symbol_id,
+        "kind": rel.kind,
+        "strength": rel.strength,
+        "depth": depth,
+    })
+}
+
+/// Count outgoing and incoming edges per symbol id.
+fn fan_counts(relationships: &[Relationship]) -> (HashMap<&str, i64>, HashMap<&str, i64>) {
+    let mut fan_out = HashMap::new();
+    let mut fan_in = HashMap::new();
+    for rel in relationships {
+        if let Some(from) = rel.from_symbol_id.as_deref() {
+            *fan_out.entry(from).or_insert(0) += 1;
+        }
+        if let Some(to) = rel.to_symbol_id.as_deref() {
+            *fan_in.entry(to).or_insert(0) += 1;
+        }
+    }
+    (fan_out, fan_in)
+}
+
+async fn get_project_overview(pool: &SqlitePool, project_name: &str) -> McpResult<Value> {
+    let symbols = queries::get_all_symbols(pool).await?;
+    let by_kind: BTreeMap<String, i64> = queries::count_symbols_by_kind(pool).await?.into_iter().collect();
+    let relationships = queries::get_all_relationships(pool).await?;
+    let patterns = queries::get_patterns(pool).await?;
+
+    let mut files = HashSet::new();
+    let mut languages: BTreeMap<String, usize> = BTreeMap::new();
+    let mut modules: BTreeMap<String, usize> = BTreeMap::new();
+    for symbol in &symbols {
+        if files.insert(symbol.file_path.as_str()) {
+            let ext = std::path::Path::new(&symbol.file_path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_string();
+            *languages.entry(ext).or_insert(0) += 1;
+        }
+        *modules.entry(module_of(symbol).to_string()).or_insert(0) += 1;
+    }
+
+    Ok(json!({
+        "project": project_name,
+        "files": files.len(),
+        "symbols": symbols.len(),
+        "symbols_by_kind": by_kind,
+        "files_by_extension": languages,
+        "symbols_by_module": modules,
+        "relationships": relationships.len(),
+        "patterns": patterns.iter().filter_map(|p| p.name.clone()).collect::<Vec<_>>(),
+    }))
+}
+
+async fn find_references(pool: &SqlitePool, symbol_name: &str) -> McpResult<Value> {
+    let target = resolve_symbol(pool, symbol_name).await?;
+    let mut references = Vec::new();
+    for rel in queries::get_dependents(pool, &target.id).await? {
+        let from = match rel.from_symbol_id.as_deref() {
+            Some(id) => queries::get_symbol(pool, id).await?,
+            None => None,
+        };
+        references.push(json!({
+            "from": rel.from_symbol_id,
+            "kind": rel.kind,
+            "symbol": from,
+        }));
+    }
+    Ok(json!({ "symbol": target, "references": references }))
+}
+
+async fn analyze_dependencies(pool: &SqlitePool, symbol_name: &str, depth: i64) -> McpResult<Value> {
+    let root = resolve_symbol(pool, symbol_name).await?;
+    let depth = depth.clamp(1, 10);
+
+    let mut seen = HashSet::from([root.id.clone()]);
+    let mut queue = VecDeque::from([(root.id.clone(), 0)]);
+    let mut dependencies = Vec::new();
+    while let Some((id, level)) = queue.pop_front() {
+        if level >= depth {
+            continue;
+        }
+        for rel in queries::get_relationships(pool, &id).await? {
+            dependencies.push(edge_json(&rel, level + 1));
+            if let Some(to) = rel.to_symbol_id {
+                if seen.insert(to.clone()) {
+                    queue.push_back((to, level + 1));
+                }
+            }
+        }
+    }
+
+    let dependents: Vec<Value> = queries::get_dependents(pool, &root.id)
+        .await?
+        .iter()
+        .map(|rel| edge_json(rel, 1))
+        .collect();
+
+    Ok(json!({
+        "symbol": root,
+        "depth": depth,
+        "dependencies": dependencies,
+        "dependents": dependents,
+    }))
+}
+
+async fn trace_data_flow(pool: &SqlitePool, variable: &str) -> McpResult<Value> {
+    let definitions: Vec<Symbol> = queries::find_symbols(pool, variable, None, 50)
+        .await?
+        .into_iter()
+        .filter(|s| s.name == variable)
+        .collect();
+
+    let mut flows = Vec::new();
+    for symbol in &definitions {
+        let readers = queries::get_dependents(pool, &symbol.id).await?;
+        let writes = queries::get_relationships(pool, &symbol.id).await?;
+        flows.push(json!({
+            "definition": symbol,
+            "used_by": readers.iter().filter_map(|r| r.from_symbol_id.clone()).collect::<Vec<_>>(),
+            "flows_to": writes.iter().filter_map(|r| r.to_symbol_id.clone()).collect::<Vec<_>>(),
+        }));
+    }
+
+    Ok(json!({ "variable": variable, "flows": flows }))
+}
+
+async fn find_patterns(pool: &SqlitePool, pattern_type: Option<&str>) -> McpResult<Value> {
+    let wanted = pattern_type.map(str::to_lowercase);
+    let patterns: Vec<Value> = queries::get_patterns(pool)
+        .await?
+        .into_iter()
+        .filter(|p| match &wanted {
+            Some(w) => [&p.name, &p.pattern_type]
+                .iter()
+                .any(|field| field.as_deref().map(str::to_lowercase).as_ref() == Some(w)),
+            None => true,
+        })
+        .map(|p| {
+            let locations = p
+                .locations
+                .as_deref()
+                .and_then(|l| serde_json::from_str::<Value>(l).ok())
+                .unwrap_or_else(|| json!(p.locations));
+            json!({
+                "name": p.name,
+                "pattern_type": p.pattern_type,
+                "occurrences": p.occurrences,
+                "locations": locations,
+            })
+        })
+        .collect();
+    Ok(json!(patterns))
+}
+
+async fn analyze_complexity(pool: &SqlitePool, module: Option<&str>, threshold: i64) -> McpResult<Value> {
+    let symbols = queries::get_all_symbols(pool).await?;
+    let relationships = queries::get_all_relationships(pool).await?;
+    let (fan_out, _) = fan_counts(&relationships);
+
+    let mut scored: Vec<(i64, &Symbol, i64, i64)> = symbols
+        .iter()
+        .filter(|s| CALLABLE_KINDS.contains(&s.kind.as_str()))
+        .filter(|s| module.is_none_or(|m| module_of(s) == m))
+        .map(|s| {
+            let lines = line_count(s);
+            let calls = fan_out.get(s.id.as_str()).copied().unwrap_or(0);
+            (1 + calls + lines / 10, s, lines, calls)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+
+    let average = if scored.is_empty() {
+        0.0
+    } else {
+        scored.iter().map(|s| s.0 as f64).sum::<f64>() / scored.len() as f64
+    };
+    let over: Vec<Value> = scored
+        .iter()
+        .filter(|s| s.0 >= threshold)
+        .map(|(score, s, lines, calls)| json!({
+            "symbol": s.id,
+            "file_path": s.file_path,
+            "complexity": score,
+            "lines": lines,
+            "calls": calls,
+        }))
+        .collect();
+
+    Ok(json!({
+        "module": module,
+        "threshold": threshold,
+        "analyzed": scored.len(),
+        "average_complexity": average,
+        "over_threshold": over,
+    }))
+}
+
+async fn suggest_refactoring(pool: &SqlitePool, symbol: Option<&str>) -> McpResult<Value> {
+    let candidates = match symbol {
+        Some(name) => vec![resolve_symbol(pool, name).await?],
+        None => queries::get_all_symbols(pool).await?,
+    };
+    let relationships = queries::get_all_relationships(pool).await?;
+    let (fan_out, fan_in) = fan_counts(&relationships);
+
+    let mut suggestions = Vec::new();
+    for s in &candidates {
+        let lines = line_count(s);
+        let calls = fan_out.get(s.id.as_str()).copied().unwrap_or(0);
+        let callers = fan_in.get(s.id.as_str()).copied().unwrap_or(0);
+        let mut push = |issue: String, suggestion: &str| {
+            suggestions.push(json!({
+                "symbol": s.id,
+                "file_path": s.file_path,
+                "issue": issue,
+                "suggestion": suggestion,
+            }));
+        };
+        if CALLABLE_KINDS.contains(&s.kind.as_str()) && lines > 50 {
+            push(format!("{} lines long", lines), "Extract cohesive blocks into smaller helper functions.");
+        }
+        if s.kind == "class" && lines > 300 {
+            push(format!("class spans {} lines", lines), "Split responsibilities into separate classes.");
+        }
+        if calls > 10 {
+            push(format!("calls {} other symbols", calls), "Reduce coupling by grouping collaborators behind an abstraction.");
+        }
+        if callers > 15 {
+            push(format!("used by {} symbols", callers), "Keep this interface stable; consider documenting it as a public contract.");
+        }
+    }
+    Ok(json!(suggestions))
+}
+
+/// Split text into lowercase terms on punctuation, `snake_case` and `camelCase` boundaries.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                terms.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            terms.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+    terms
+}
+
+/// Rank symbols by term overlap with the query. Names weigh more than
+/// documentation, signatures and embedded content, so this works without the
+/// Python embedding backend.
+async fn semantic_search(pool: &SqlitePool, query: &str, limit: i64) -> McpResult<Value> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().filter(|t| t.len() > 1).collect();
+    if query_terms.is_empty() {
+        return Ok(json!([]));
+    }
+    let symbols = queries::get_all_symbols(pool).await?;
+    let mut content: HashMap<String, String> = HashMap::new();
+    for embedding in queries::get_all_embeddings(pool).await? {
+        if let (Some(id), Some(text)) = (embedding.symbol_id, embedding.content) {
+            content.entry(id).or_default().push_str(&text);
+        }
+    }
+
+    let mut results: Vec<(f64, &Symbol)> = symbols
+        .iter()
+        .filter_map(|s| {
+            let name_terms: HashSet<String> = tokenize(&s.name).into_iter().collect();
+            let body = format!(
+                "{} {} {} {}",
+                s.file_path,
+                s.documentation.as_deref().unwrap_or(""),
+                s.signature.as_deref().unwrap_or(""),
+                content.get(&s.id).map(String::as_str).unwrap_or(""),
+            );
+            let body_terms: HashSet<String> = tokenize(&body).into_iter().collect();
+            let score: f64 = query_terms
+                .iter()
+                .map(|t| {
+                    if name_terms.contains(t) { 3.0 } else if body_terms.contains(t) { 1.0 } else { 0.0 }
+                })
+                .sum();
+            (score > 0.0).then(|| (score / (3.0 * query_terms.len() as f64), s))
+        })
+        .collect();
+    results.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.id.cmp(&b.1.id)));
+
+    Ok(json!(results
+        .into_iter()
+        .take(limit.max(1) as usize)
+        .map(|(score, s)| json!({ "score": score, "symbol": s }))
+        .collect::<Vec<_>>()))
+}
+
+async fn get_test_coverage(pool: &SqlitePool, module: Option<&str>) -> McpResult<Value> {
+    let symbols = queries::get_all_symbols(pool).await?;
+    let relationships = queries::get_all_relationships(pool).await?;
+
+    let test_ids: HashSet<&str> = symbols
+        .iter()
+        .filter(|s| is_test_path(&s.file_path) || s.name.starts_with("test_"))
+        .map(|s| s.id.as_str())
+        .collect();
+    let test_files: HashSet<&str> = symbols
+        .iter()
+        .filter(|s| test_ids.contains(s.id.as_str()))
+        .map(|s| s.file_path.as_str())
+        .collect();
+    let mut covered: HashSet<&str> = relationships
+        .iter()
+        .filter(|r| r.from_symbol_id.as_deref().is_some_and(|f| test_ids.contains(f)))
+        .filter_map(|r| r.to_symbol_id.as_deref())
+        .collect();
+    // `test_foo` conventionally exercises `foo`.
+    let tested_names: HashSet<&str> = symbols
+        .iter()
+        .filter(|s| test_ids.contains(s.id.as_str()))
+        .filter_map(|s| s.name.strip_prefix("test_"))
+        .collect();
+
+    let targets: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| CALLABLE_KINDS.contains(&s.kind.as_str()) && !test_ids.contains(s.id.as_str()))
+        .filter(|s| module.is_none_or(|m| module_of(s) == m))
+        .collect();
+    for s in &targets {
+        if tested_names.contains(s.name.as_str()) {
+            covered.insert(s.id.as_str());
+        }
+    }
+    let untested: Vec<&str> = targets
+        .iter()
+        .filter(|s| !covered.contains(s.id.as_str()))
+        .map(|s| s.id.as_str())
+        .collect();
+    let tested = targets.len() - untested.len();
+    let coverage = if targets.is_empty() { 0.0 } else { tested as f64 * 100.0 / targets.len() as f64 };
+
+    Ok(json!({
+        "module": module,
+        "test_files": test_files.len(),
+        "callables": targets.len(),
+        "tested": tested,
+        "coverage_percent": coverage,
+        "untested": untested.into_iter().take(50).collect::<Vec<_>>(),
+    }))
+}
+
+async fn explain_architecture(pool: &SqlitePool, project_name: &str) -> McpResult<Value> {
+    let symbols = queries::get_all_symbols(pool).await?;
+    let relationships = queries::get_all_relationships(pool).await?;
+    let patterns = queries::get_patterns(pool).await?;
+
+    let module_by_id: HashMap<&str, &str> = symbols.iter().map(|s| (s.id.as_str(), module_of(s))).collect();
+    let mut modules: BTreeMap<&str, (HashSet<&str>, usize)> = BTreeMap::new();
+    for s in &symbols {
+        let entry = modules.entry(module_of(s)).or_default();
+        entry.0.insert(s.file_path.as_str());
+        entry.1 += 1;
+    }
+    let mut edges: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for rel in &relationships {
+        let from = rel.from_symbol_id.as_deref().and_then(|id| module_by_id.get(id));
+        let to = rel.to_symbol_id.as_deref().and_then(|id| module_by_id.get(id));
+        if let (Some(from), Some(to)) = (from, to) {
+            if from != to {
+                *edges.entry((from, to)).or_insert(0) += 1;
+            }
+        }
+    }
+    let entry_points: BTreeSet<&str> = symbols
+        .iter()
+        .filter(|s| {
+            let stem = std::path::Path::new(&s.file_path)
+                .file_stem()
+                .and_then(|f| f.to_str())
+                .unwrap_or("");
+            matches!(stem, "main" | "index" | "app" | "server" | "__main__" | "lib")
+        })
+        .map(|s| s.file_path.as_str())
+        .collect();
+
+    let summary = format!(
+        "{} has {} modules, {} symbols and {} cross-module dependencies.",
+        project_name,
+        modules.len(),
+        symbols.len(),
+        edges.values().sum::<usize>(),
+    );
+
+    Ok(json!({
+        "summary": summary,
+        "modules": modules.iter().map(|(name, (files, count))| json!({
+            "name": name,
+            "files": files.len(),
+            "symbols": count,
+        })).collect::<Vec<_>>(),
+        "module_dependencies": edges.iter().map(|((from, to), count)| json!({
+            "from": from,
+            "to": to,
+            "edges": count,
+        })).collect::<Vec<_>>(),
+        "entry_points": entry_points,
+        "patterns": patterns.iter().filter_map(|p| p.name.clone()).collect::<Vec<_>>(),
+    }))
+}
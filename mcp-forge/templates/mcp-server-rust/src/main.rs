@@ -0,0 +1,40 @@
+//! {{PROJECT_NAME}} MCP server: {{PROJECT_DESCRIPTION}}
+//!
+//! Languages: {{LANGUAGES}}
+//! Tools: {{TOOLS}}
+//!
+//! Standalone build of the native `mcp-forge serve` server: `database`, `error`
+//! and `server` are copied from mcp-forge, so tools answer exactly as it does.
+//! Usage: `{{SERVER_SLUG}} [index-dir]`, defaulting to this project's directory.
+#![allow(dead_code)]
+
+mod database;
+mod error;
+mod server {
+    pub mod native;
+    pub mod protocol;
+    pub mod resources;
+    pub mod tools;
+}
+
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() {
+    // stdout carries the JSON-RPC stream; diagnostics go to stderr.
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"))
+        .target(env_logger::Target::Stderr)
+        .init();
+    let index_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+    let result = match server::native::McpServer::open(&index_dir).await {
+        Ok(server) => server.serve_stdio().await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,5 @@
+#!/bin/bash
+
+# Start the MCP server over stdio, compiling it on first run
+cd "$(dirname "$0")" || exit 1
+exec cargo run --quiet --release -- "$(pwd)"
@@ -0,0 +1,577 @@
+#!/usr/bin/env node
+/**
+ * {{PROJECT_NAME}} MCP server: {{PROJECT_DESCRIPTION}}
+ *
+ * Languages: {{LANGUAGES}}
+ * Tools: {{TOOLS}}
+ *
+ * Answers from the SQLite knowledge base written by `mcp-forge build`, with the
+ * same results as `mcp-forge serve`.
+ */
+import { readFileSync } from "node:fs";
+import { basename, dirname, extname, isAbsolute, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+import Database from "better-sqlite3";
+import { McpServer, ResourceTemplate } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { StdioServerTransport } from "@modelcontextprotocol/sdk/server/stdio.js";
+import { z } from "zod";
+
+const HERE = join(dirname(fileURLToPath(import.meta.url)), "..");
+const DB_PATH = join(HERE, "{{DB_PATH}}");
+const CALLABLE_KINDS = ["function", "method", "route", "property"];
+const PROMPT_LIST_LIMIT = 50;
+const MIME_TYPES: Record<string, string> = {
+  ".py": "text/x-python", ".js": "text/javascript", ".mjs": "text/javascript", ".cjs": "text/javascript",
+  ".jsx": "text/javascript", ".ts": "text/x-typescript", ".tsx": "text/x-typescript", ".rs": "text/x-rust",
+  ".java": "text/x-java", ".go": "text/x-go", ".md": "text/markdown", ".json": "application/json",
+};
+
+interface SymbolRow {
+  id: string;
+  name: string;
+  kind: string;
+  file_path: string;
+  start_line: number | null;
+  end_line: number | null;
+  scope: string | null;
+  documentation: string | null;
+  doc_tags: string | null;
+  signature: string | null;
+  qualified_name: string | null;
+  parent_id: string | null;
+  visibility: string | null;
+  attributes: string | null;
+  return_type: string | null;
+  type_parameters: string | null;
+  modifiers: string | null;
+  type_name: string | null;
+}
+
+interface ParameterRow {
+  position: number;
+  name: string;
+  type: string | null;
+  default: string | null;
+  kind: string;
+}
+
+interface RelationshipRow {
+  id: number;
+  from_symbol_id: string | null;
+  to_symbol_id: string | null;
+  kind: string | null;
+  strength: number | null;
+}
+
+interface PatternRow {
+  id: number;
+  name: string | null;
+  pattern_type: string | null;
+  occurrences: number | null;
+  locations: string | null;
+}
+
+const db = new Database(DB_PATH, { readonly: true, fileMustExist: true });
+
+function config<T>(section: string, key: string): T | null {
+  try {
+    return JSON.parse(readFileSync(join(HERE, "config.json"), "utf8"))[section]?.[key] ?? null;
+  } catch {
+    return null;
+  }
+}
+
+const projectConfig = (key: string): string | null => config<string>("project", key);
+
+const projectName = (): string => projectConfig("name") ?? "Project";
+
+function rows<T>(sql: string, ...params: unknown[]): T[] {
+  return db.prepare(sql).all(...params) as T[];
+}
+
+const getSymbol = (id: string): SymbolRow | null =>
+  (db.prepare("SELECT * FROM symbols WHERE id = ?").get(id) as SymbolRow | undefined) ?? null;
+
+const findSymbols = (name: string, kind: string | null, limit: number): SymbolRow[] =>
+  rows<SymbolRow>(
+    "SELECT * FROM symbols WHERE (name LIKE ? OR qualified_name LIKE ?) AND (? IS NULL OR kind = ?) " +
+      "ORDER BY name = ? OR qualified_name = ? DESC, length(name), file_path LIMIT ?",
+    `%${name}%`, `%${name}%`, kind, kind, name, name, limit,
+  );
+
+const parametersOf = (id: string) =>
+  rows<ParameterRow>(
+    "SELECT position, name, type_name AS type, default_value AS \"default\", kind FROM parameters WHERE symbol_id = ? ORDER BY position",
+    id,
+  );
+
+/** Functions and methods with their `parameters`. */
+const withParameters = (symbols: SymbolRow[]) =>
+  symbols.map((s) => (CALLABLE_KINDS.includes(s.kind) ? { ...s, parameters: parametersOf(s.id) } : s));
+
+const relationshipsFrom = (id: string) => rows<RelationshipRow>("SELECT * FROM relationships WHERE from_symbol_id = ?", id);
+const dependentsOf = (id: string) => rows<RelationshipRow>("SELECT * FROM relationships WHERE to_symbol_id = ?", id);
+const allSymbols = () => rows<SymbolRow>("SELECT * FROM symbols ORDER BY file_path, start_line");
+const allRelationships = () => rows<RelationshipRow>("SELECT * FROM relationships");
+const allPatterns = () => rows<PatternRow>("SELECT * FROM patterns");
+
+/** Exact id first, then exact name, then partial name. */
+function resolveSymbol(name: string): SymbolRow {
+  const symbol = getSymbol(name) ?? findSymbols(name, null, 1)[0];
+  if (!symbol) throw new Error(`Symbol not found: ${name}`);
+  return symbol;
+}
+
+function lineCount(s: SymbolRow): number {
+  return s.start_line !== null && s.end_line !== null && s.end_line >= s.start_line ? s.end_line - s.start_line + 1 : 0;
+}
+
+const moduleOf = (s: SymbolRow): string => s.scope || "<root>";
+
+function isTestPath(path: string): boolean {
+  return path.split(/[/\\]/).some((raw) => {
+    const segment = raw.toLowerCase();
+    return ["tests", "test", "__tests__"].includes(segment) || segment.startsWith("test_")
+      || segment.includes(".test.") || segment.includes(".spec.")
+      || segment.endsWith("_test.py") || segment.endsWith("_tests.rs");
+  });
+}
+
+function fanCounts(relationships: RelationshipRow[]): [Map<string, number>, Map<string, number>] {
+  const fanOut = new Map<string, number>();
+  const fanIn = new Map<string, number>();
+  for (const rel of relationships) {
+    if (rel.from_symbol_id) fanOut.set(rel.from_symbol_id, (fanOut.get(rel.from_symbol_id) ?? 0) + 1);
+    if (rel.to_symbol_id) fanIn.set(rel.to_symbol_id, (fanIn.get(rel.to_symbol_id) ?? 0) + 1);
+  }
+  return [fanOut, fanIn];
+}
+
+const edge = (rel: RelationshipRow, depth: number) => ({
+  from: rel.from_symbol_id, to: rel.to_symbol_id, kind: rel.kind, strength: rel.strength, depth,
+});
+
+const byKey = <T>(entries: Iterable<[string, T]>) =>
+  Object.fromEntries([...entries].sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0)));
+
+/** Lowercase terms split on punctuation, snake_case and camelCase boundaries. */
+function tokenize(text: string): string[] {
+  const terms: string[] = [];
+  let current = "";
+  let prevLower = false;
+  for (const c of text) {
+    if (!/[\p{L}\p{N}]/u.test(c)) {
+      if (current) terms.push(current);
+      current = "";
+      prevLower = false;
+      continue;
+    }
+    const upper = c !== c.toLowerCase();
+    if (upper && prevLower && current) {
+      terms.push(current);
+      current = "";
+    }
+    prevLower = c !== c.toUpperCase() || /\p{N}/u.test(c);
+    current += c.toLowerCase();
+  }
+  if (current) terms.push(current);
+  return terms;
+}
+
+function getProjectOverview() {
+  const symbols = allSymbols();
+  const byKind = rows<{ kind: string; count: number }>("SELECT kind, COUNT(*) AS count FROM symbols GROUP BY kind");
+  const files = new Set<string>();
+  const extensions = new Map<string, number>();
+  const modules = new Map<string, number>();
+  for (const s of symbols) {
+    if (!files.has(s.file_path)) {
+      files.add(s.file_path);
+      const ext = extname(s.file_path).replace(/^\./, "");
+      extensions.set(ext, (extensions.get(ext) ?? 0) + 1);
+    }
+    modules.set(moduleOf(s), (modules.get(moduleOf(s)) ?? 0) + 1);
+  }
+  return {
+    project: projectName(),
+    files: files.size,
+    symbols: symbols.length,
+    symbols_by_kind: byKey(byKind.map((r) => [r.kind, r.count] as [string, number])),
+    files_by_extension: byKey(extensions),
+    symbols_by_module: byKey(modules),
+    relationships: allRelationships().length,
+    patterns: allPatterns().flatMap((p) => (p.name ? [p.name] : [])),
+  };
+}
+
+function findReferences(symbolName: string) {
+  const target = resolveSymbol(symbolName);
+  const references = dependentsOf(target.id).map((rel) => ({
+    from: rel.from_symbol_id,
+    kind: rel.kind,
+    symbol: rel.from_symbol_id ? getSymbol(rel.from_symbol_id) : null,
+  }));
+  return { symbol: target, references };
+}
+
+function analyzeDependencies(symbolName: string, requested: number) {
+  const root = resolveSymbol(symbolName);
+  const depth = Math.min(Math.max(requested, 1), 10);
+  const seen = new Set([root.id]);
+  const queue: [string, number][] = [[root.id, 0]];
+  const dependencies = [];
+  while (queue.length > 0) {
+    const [id, level] = queue.shift()!;
+    if (level >= depth) continue;
+    for (const rel of relationshipsFrom(id)) {
+      dependencies.push(edge(rel, level + 1));
+      if (rel.to_symbol_id && !seen.has(rel.to_symbol_id)) {
+        seen.add(rel.to_symbol_id);
+        queue.push([rel.to_symbol_id, level + 1]);
+      }
+    }
+  }
+  const dependents = dependentsOf(root.id).map((rel) => edge(rel, 1));
+  return { symbol: root, depth, dependencies, dependents };
+}
+
+function traceDataFlow(variable: string) {
+  const flows = findSymbols(variable, null, 50)
+    .filter((s) => s.name === variable)
+    .map((s) => ({
+      definition: s,
+      used_by: dependentsOf(s.id).flatMap((r) => (r.from_symbol_id ? [r.from_symbol_id] : [])),
+      flows_to: relationshipsFrom(s.id).flatMap((r) => (r.to_symbol_id ? [r.to_symbol_id] : [])),
+    }));
+  return { variable, flows };
+}
+
+function findPatterns(patternType?: string) {
+  const wanted = patternType?.toLowerCase();
+  return allPatterns()
+    .filter((p) => !wanted || [p.name, p.pattern_type].some((field) => field?.toLowerCase() === wanted))
+    .map((p) => {
+      let locations: unknown = p.locations;
+      try {
+        if (p.locations) locations = JSON.parse(p.locations);
+      } catch {
+        // Plain-text locations are returned as stored.
+      }
+      return { name: p.name, pattern_type: p.pattern_type, occurrences: p.occurrences, locations };
+    });
+}
+
+function analyzeComplexity(module: string | undefined, threshold = config<number>("analysis", "complexity_threshold") ?? 10) {
+  const [fanOut] = fanCounts(allRelationships());
+  const scored = allSymbols()
+    .filter((s) => CALLABLE_KINDS.includes(s.kind) && (!module || moduleOf(s) === module))
+    .map((s) => {
+      const lines = lineCount(s);
+      const calls = fanOut.get(s.id) ?? 0;
+      return { score: 1 + calls + Math.floor(lines / 10), s, lines, calls };
+    })
+    .sort((a, b) => b.score - a.score || (a.s.id < b.s.id ? -1 : a.s.id > b.s.id ? 1 : 0));
+  const average = scored.length ? scored.reduce((sum, item) => sum + item.score, 0) / scored.length : 0;
+  return {
+    module: module ?? null,
+    threshold,
+    analyzed: scored.length,
+    average_complexity: average,
+    over_threshold: scored
+      .filter((item) => item.score >= threshold)
+      .map(({ score, s, lines, calls }) => ({ symbol: s.id, file_path: s.file_path, complexity: score, lines, calls })),
+  };
+}
+
+function suggestRefactoring(symbol?: string) {
+  const candidates = symbol ? [resolveSymbol(symbol)] : allSymbols();
+  const [fanOut, fanIn] = fanCounts(allRelationships());
+  const suggestions: object[] = [];
+  for (const s of candidates) {
+    const lines = lineCount(s);
+    const calls = fanOut.get(s.id) ?? 0;
+    const callers = fanIn.get(s.id) ?? 0;
+    const push = (issue: string, suggestion: string) =>
+      suggestions.push({ symbol: s.id, file_path: s.file_path, issue, suggestion });
+    if (CALLABLE_KINDS.includes(s.kind) && lines > 50) push(`${lines} lines long`, "Extract cohesive blocks into smaller helper functions.");
+    if (s.kind === "class" && lines > 300) push(`class spans ${lines} lines`, "Split responsibilities into separate classes.");
+    if (calls > 10) push(`calls ${calls} other symbols`, "Reduce coupling by grouping collaborators behind an abstraction.");
+    if (callers > 15) push(`used by ${callers} symbols`, "Keep this interface stable; consider documenting it as a public contract.");
+  }
+  return suggestions;
+}
+
+function semanticSearch(query: string, limit: number) {
+  const queryTerms = new Set(tokenize(query).filter((t) => t.length > 1));
+  if (queryTerms.size === 0) return [];
+  const content = new Map<string, string>();
+  for (const row of rows<{ symbol_id: string | null; content: string | null }>("SELECT symbol_id, content FROM embeddings")) {
+    if (row.symbol_id && row.content) content.set(row.symbol_id, (content.get(row.symbol_id) ?? "") + row.content);
+  }
+  const results: { score: number; symbol: SymbolRow }[] = [];
+  for (const s of allSymbols()) {
+    const nameTerms = new Set(tokenize(s.name));
+    const bodyTerms = new Set(tokenize([s.file_path, s.documentation ?? "", s.signature ?? "", content.get(s.id) ?? ""].join(" ")));
+    let score = 0;
+    for (const t of queryTerms) score += nameTerms.has(t) ? 3 : bodyTerms.has(t) ? 1 : 0;
+    if (score > 0) results.push({ score: score / (3 * queryTerms.size), symbol: s });
+  }
+  results.sort((a, b) => b.score - a.score || (a.symbol.id < b.symbol.id ? -1 : a.symbol.id > b.symbol.id ? 1 : 0));
+  return results.slice(0, Math.max(limit, 1));
+}
+
+function getTestCoverage(module?: string) {
+  const symbols = allSymbols();
+  const testIds = new Set(symbols.filter((s) => isTestPath(s.file_path) || s.name.startsWith("test_")).map((s) => s.id));
+  const testFiles = new Set(symbols.filter((s) => testIds.has(s.id)).map((s) => s.file_path));
+  const covered = new Set(
+    allRelationships()
+      .filter((r) => r.from_symbol_id && testIds.has(r.from_symbol_id) && r.to_symbol_id)
+      .map((r) => r.to_symbol_id as string),
+  );
+  // `test_foo` conventionally exercises `foo`.
+  const testedNames = new Set(
+    symbols.filter((s) => testIds.has(s.id) && s.name.startsWith("test_")).map((s) => s.name.slice("test_".length)),
+  );
+  const targets = symbols.filter(
+    (s) => CALLABLE_KINDS.includes(s.kind) && !testIds.has(s.id) && (!module || moduleOf(s) === module),
+  );
+  for (const s of targets) if (testedNames.has(s.name)) covered.add(s.id);
+  const untested = targets.filter((s) => !covered.has(s.id)).map((s) => s.id);
+  const tested = targets.length - untested.length;
+  return {
+    module: module ?? null,
+    test_files: testFiles.size,
+    callables: targets.length,
+    tested,
+    coverage_percent: targets.length ? (tested * 100) / targets.length : 0,
+    untested: untested.slice(0, 50),
+  };
+}
+
+function explainArchitecture() {
+  const symbols = allSymbols();
+  const moduleById = new Map(symbols.map((s) => [s.id, moduleOf(s)]));
+  const modules = new Map<string, { files: Set<string>; symbols: number }>();
+  for (const s of symbols) {
+    const entry = modules.get(moduleOf(s)) ?? { files: new Set<string>(), symbols: 0 };
+    entry.files.add(s.file_path);
+    entry.symbols += 1;
+    modules.set(moduleOf(s), entry);
+  }
+  const edges = new Map<string, { from: string; to: string; edges: number }>();
+  for (const rel of allRelationships()) {
+    const from = rel.from_symbol_id ? moduleById.get(rel.from_symbol_id) : undefined;
+    const to = rel.to_symbol_id ? moduleById.get(rel.to_symbol_id) : undefined;
+    if (from && to && from !== to) {
+      const key = JSON.stringify([from, to]);
+      const entry = edges.get(key) ?? { from, to, edges: 0 };
+      entry.edges += 1;
+      edges.set(key, entry);
+    }
+  }
+  const entryPoints = [...new Set(
+    symbols
+      .filter((s) => ["main", "index", "app", "server", "__main__", "lib"].includes(basename(s.file_path, extname(s.file_path))))
+      .map((s) => s.file_path),
+  )].sort();
+  const crossModule = [...edges.values()].reduce((sum, e) => sum + e.edges, 0);
+  return {
+    summary: `${projectName()} has ${modules.size} modules, ${symbols.length} symbols and ${crossModule} cross-module dependencies.`,
+    modules: [...modules.entries()].sort(([a], [b]) => (a < b ? -1 : 1)).map(([name, m]) => ({ name, files: m.files.size, symbols: m.symbols })),
+    module_dependencies: [...edges.values()].sort((a, b) => (a.from + "\0" + a.to < b.from + "\0" + b.to ? -1 : 1)),
+    entry_points: entryPoints,
+    patterns: allPatterns().flatMap((p) => (p.name ? [p.name] : [])),
+  };
+}
+
+// Resources: file://<path> for every indexed file, symbol://<id> for every symbol.
+
+const mimeType = (path: string): string => MIME_TYPES[extname(path)] ?? "text/plain";
+
+/** Read an indexed file below the project root; paths escaping the root are refused. */
+function readSource(path: string): string | null {
+  const root = projectConfig("root");
+  if (!root || isAbsolute(path) || path.split(/[/\\]/).includes("..")) return null;
+  try {
+    return readFileSync(join(root, path), "utf8");
+  } catch {
+    return null;
+  }
+}
+
+function symbolCode(s: SymbolRow): string | null {
+  const source = readSource(s.file_path);
+  if (s.start_line === null || s.end_line === null || source === null) return null;
+  const lines = source.split(/\r?\n/).slice(Math.max(s.start_line, 1) - 1, s.end_line);
+  return lines.length ? lines.join("\n") : null;
+}
+
+const location = (s: SymbolRow): string =>
+  s.start_line !== null && s.end_line !== null ? `${s.file_path}:${s.start_line}-${s.end_line}` : s.file_path;
+
+function readFile(path: string): string {
+  const indexed = db.prepare("SELECT path FROM files WHERE path = ? AND skip_reason IS NULL").get(path);
+  const source = indexed ? readSource(path) : null;
+  if (source === null) throw new Error(`Resource not found: file://${path}`);
+  return source;
+}
+
+function readSymbol(id: string): string {
+  const symbol = getSymbol(id);
+  if (!symbol) throw new Error(`Resource not found: symbol://${id}`);
+  return JSON.stringify({
+    symbol,
+    parameters: parametersOf(id),
+    code: symbolCode(symbol),
+    callers: dependentsOf(id).flatMap((r) => (r.from_symbol_id ? [r.from_symbol_id] : [])),
+    callees: relationshipsFrom(id).flatMap((r) => (r.to_symbol_id ? [r.to_symbol_id] : [])),
+  }, null, 2);
+}
+
+const textContents = (uri: URL, mime: string, text: string) => ({ contents: [{ uri: uri.href, mimeType: mime, text }] });
+
+function registerResources() {
+  const files = rows<{ path: string; count: number }>(
+    "SELECT path, (SELECT COUNT(*) FROM symbols WHERE symbols.file_path = files.path) AS count " +
+    "FROM files WHERE skip_reason IS NULL ORDER BY path");
+  for (const { path, count } of files) {
+    server.resource(path, `file://${path}`, { description: `${count} symbols`, mimeType: mimeType(path) },
+      async (uri) => textContents(uri, mimeType(path), readFile(path)));
+  }
+  for (const s of allSymbols()) {
+    server.resource(s.name, `symbol://${s.id}`, { description: `${s.kind} in ${location(s)}`, mimeType: "application/json" },
+      async (uri) => textContents(uri, "application/json", readSymbol(s.id)));
+  }
+  server.resource("Source file", new ResourceTemplate("file://{+path}", { list: undefined }),
+    { description: "Contents of an indexed file, by path relative to the project root." },
+    async (uri, { path }) => textContents(uri, mimeType(String(path)), readFile(String(path))));
+  server.resource("Symbol", new ResourceTemplate("symbol://{+id}", { list: undefined }),
+    { description: "A symbol with its code, callers and callees, by id (`<file>::<name>`).", mimeType: "application/json" },
+    async (uri, { id }) => textContents(uri, "application/json", readSymbol(String(id))));
+  server.resource("Symbol search", new ResourceTemplate("symbols://{name}", { list: undefined }),
+    { description: "Symbols whose name contains the given text.", mimeType: "application/json" },
+    async (uri, { name }) => textContents(uri, "application/json", JSON.stringify(findSymbols(String(name), null, 50), null, 2)));
+}
+
+// Prompts, pre-filled from the knowledge base.
+
+function listSection(heading: string, items: string[], empty: string): string {
+  let text = `\n## ${heading}\n`;
+  if (!items.length) text += `- ${empty}\n`;
+  for (const item of items.slice(0, PROMPT_LIST_LIMIT)) text += `- ${item}\n`;
+  if (items.length > PROMPT_LIST_LIMIT) text += `- ... and ${items.length - PROMPT_LIST_LIMIT} more\n`;
+  return text;
+}
+
+const sortedEntries = <T>(map: Map<string, T>) => [...map.entries()].sort(([a], [b]) => (a < b ? -1 : 1));
+
+function explainModule(module: string): string {
+  const symbols = allSymbols();
+  const members = symbols.filter((s) => moduleOf(s) === module);
+  if (!members.length) {
+    const known = [...new Set(symbols.map(moduleOf))].sort().join(", ");
+    throw new Error(`Unknown module: ${module} (known: ${known})`);
+  }
+  const moduleById = new Map(symbols.map((s) => [s.id, moduleOf(s)]));
+  const uses = new Map<string, number>();
+  const usedBy = new Map<string, number>();
+  for (const rel of allRelationships()) {
+    const from = rel.from_symbol_id ? moduleById.get(rel.from_symbol_id) : undefined;
+    const to = rel.to_symbol_id ? moduleById.get(rel.to_symbol_id) : undefined;
+    if (from && to && from === module && to !== module) uses.set(to, (uses.get(to) ?? 0) + 1);
+    else if (from && to && to === module && from !== module) usedBy.set(from, (usedBy.get(from) ?? 0) + 1);
+  }
+  const files = new Map<string, number>();
+  for (const s of members) files.set(s.file_path, (files.get(s.file_path) ?? 0) + 1);
+  const describe = (s: SymbolRow) => {
+    const doc = (s.documentation ?? "").split("\n")[0].trim();
+    const base = `${s.kind} \`${s.name}\` (${location(s)})`;
+    return doc ? `${base}: ${doc}` : base;
+  };
+
+  let text = `Explain the \`${module}\` module of ${projectName()}.\n`;
+  text += listSection("Files", sortedEntries(files).map(([p, n]) => `file://${p} (${n} symbols)`), "none");
+  text += listSection("Symbols", members.map(describe), "none");
+  text += listSection("Depends on", sortedEntries(uses).map(([m, n]) => `\`${m}\` (${n} references)`), "no other modules");
+  text += listSection("Used by", sortedEntries(usedBy).map(([m, n]) => `\`${m}\` (${n} references)`), "no other modules");
+  text += "\nDescribe what this module is responsible for, its main entry points and how it "
+    + "interacts with the modules above. Read the listed file:// resources for the code.\n";
+  return text;
+}
+
+function reviewChange(symbol: string, change?: string): string {
+  const target = resolveSymbol(symbol);
+  let text = `Review a change to \`${target.id}\` in ${projectName()}.\n`;
+  text += `\n## Symbol\n- kind: ${target.kind}\n- location: ${location(target)}\n`;
+  if (target.signature) text += `- signature: \`${target.signature}\`\n`;
+  if (target.documentation) text += `- documentation: ${target.documentation.trim()}\n`;
+  if (change) text += `\n## Proposed change\n${change.trimEnd()}\n`;
+  const code = symbolCode(target);
+  if (code) text += `\n## Current code\n\`\`\`\n${code}\n\`\`\`\n`;
+  const edgeLine = (id: string, kind: string | null) =>
+    `\`${id}\` (${kind || "references"}${isTestPath(id.split("::")[0]) ? ", test" : ""})`;
+  text += listSection("Callers",
+    dependentsOf(target.id).flatMap((r) => (r.from_symbol_id ? [edgeLine(r.from_symbol_id, r.kind)] : [])), "none indexed");
+  text += listSection("Callees",
+    relationshipsFrom(target.id).flatMap((r) => (r.to_symbol_id ? [edgeLine(r.to_symbol_id, r.kind)] : [])), "none indexed");
+  text += "\nCheck that the change keeps the behaviour the callers rely on, name the callers and "
+    + "tests that need updating, and point out missing tests.\n";
+  return text;
+}
+
+const promptText = (text: string) => ({ messages: [{ role: "user" as const, content: { type: "text" as const, text } }] });
+
+const server = new McpServer({ name: "{{PROJECT_NAME}}_Expert", version: "0.1.0" });
+
+function register(name: string, description: string, shape: z.ZodRawShape, run: (args: any) => unknown) {
+  server.tool(name, description, shape, async (args: any) => {
+    try {
+      return { content: [{ type: "text" as const, text: JSON.stringify(run(args), null, 2) }] };
+    } catch (e) {
+      return { content: [{ type: "text" as const, text: String(e instanceof Error ? e.message : e) }], isError: true };
+    }
+  });
+}
+
+register("get_project_overview", "Get comprehensive project statistics and structure.", {}, () => getProjectOverview());
+register("find_symbol", "Find symbols by name and optional type. Functions and methods include their parameters.",
+  { name: z.string().describe("Full or partial symbol name"), kind: z.string().optional().describe("Symbol kind, e.g. function, class, method") },
+  ({ name, kind }) => withParameters(findSymbols(name, kind || null, 50)));
+register("find_references", "List the symbols that reference a symbol.",
+  { symbol_name: z.string().describe("Symbol name or id") },
+  ({ symbol_name }) => findReferences(symbol_name));
+register("analyze_dependencies", "Trace dependencies for a symbol.",
+  { symbol_name: z.string().describe("Symbol name or id"), depth: z.number().int().default(2) },
+  ({ symbol_name, depth }) => analyzeDependencies(symbol_name, depth));
+register("trace_data_flow", "Trace how data flows through the system.",
+  { variable: z.string() },
+  ({ variable }) => traceDataFlow(variable));
+register("find_patterns", "Find design patterns in codebase.",
+  { pattern_type: z.string().optional().describe("Pattern name, e.g. Singleton") },
+  ({ pattern_type }) => findPatterns(pattern_type || undefined));
+register("analyze_complexity", "Analyze code complexity metrics (approximated from symbol length and call fan-out).",
+  { module: z.string().optional(), threshold: z.number().int().optional().describe("Default: analysis.complexity_threshold") },
+  ({ module, threshold }) => analyzeComplexity(module || undefined, threshold));
+register("suggest_refactoring", "Suggest refactoring opportunities.",
+  { symbol: z.string().optional() },
+  ({ symbol }) => suggestRefactoring(symbol || undefined));
+register("semantic_search", "Search code using natural language.",
+  { query: z.string(), limit: z.number().int().default(10) },
+  ({ query, limit }) => semanticSearch(query, limit));
+register("get_test_coverage", "Analyze test coverage.",
+  { module: z.string().optional() },
+  ({ module }) => getTestCoverage(module || undefined));
+register("explain_architecture", "Explain the overall system architecture.", {}, () => explainArchitecture());
+
+registerResources();
+
+server.prompt("explain_module", "Explain a module using its files, symbols and dependencies from the knowledge base.",
+  { module: z.string().describe("Module name, as listed by explain_architecture") },
+  ({ module }) => promptText(explainModule(module)));
+server.prompt("review_change", "Review a change to a symbol with its current code, callers and callees.",
+  { symbol: z.string().describe("Symbol name or id"), change: z.string().optional().describe("Description or diff of the change") },
+  ({ symbol, change }) => promptText(reviewChange(symbol, change)));
+
+await server.connect(new StdioServerTransport());
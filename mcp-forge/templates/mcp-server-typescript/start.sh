@@ -0,0 +1,11 @@
+#!/bin/bash
+
+# Start the MCP server over stdio, installing and compiling on first run
+cd "$(dirname "$0")" || exit 1
+if [ ! -d node_modules ]; then
+    npm install --no-fund --no-audit >&2 || exit 1
+fi
+if [ ! -f dist/index.js ]; then
+    npm run build >&2 || exit 1
+fi
+exec node dist/index.js
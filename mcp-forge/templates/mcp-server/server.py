@@ -1,63 +1,574 @@
+"""{{PROJECT_NAME}} MCP server: {{PROJECT_DESCRIPTION}}
+
+Languages: {{LANGUAGES}}
+Tools: {{TOOLS}}
+
+Answers from the SQLite knowledge base written by `mcp-forge build`, with the
+same results as `mcp-forge serve`.
+"""
+import json
+import os
+import sqlite3
+from collections import deque
+from contextlib import closing
+
 from mcp.server.fastmcp import FastMCP
+from mcp.server.fastmcp.resources import FunctionResource
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+DB_PATH = os.path.join(HERE, "{{DB_PATH}}")
+CALLABLE_KINDS = ("function", "method", "route", "property")
+PROMPT_LIST_LIMIT = 50
+MIME_TYPES = {
+    ".py": "text/x-python", ".js": "text/javascript", ".mjs": "text/javascript", ".cjs": "text/javascript",
+    ".jsx": "text/javascript", ".ts": "text/x-typescript", ".tsx": "text/x-typescript", ".rs": "text/x-rust",
+    ".java": "text/x-java", ".go": "text/x-go", ".md": "text/markdown", ".json": "application/json",
+}
 
 mcp = FastMCP("{{PROJECT_NAME}}_Expert")
 
+
+def _config(section: str, key: str):
+    try:
+        with open(os.path.join(HERE, "config.json")) as f:
+            return json.load(f)[section][key]
+    except (OSError, ValueError, KeyError):
+        return None
+
+
+def _project_config(key: str):
+    return _config("project", key)
+
+
+def _project_name() -> str:
+    return _project_config("name") or "Project"
+
+
+def _rows(sql: str, *params) -> list:
+    with closing(sqlite3.connect(f"file:{DB_PATH}?mode=ro", uri=True)) as conn:
+        conn.row_factory = sqlite3.Row
+        return [dict(row) for row in conn.execute(sql, params)]
+
+
+def _get_symbol(symbol_id: str):
+    rows = _rows("SELECT * FROM symbols WHERE id = ?", symbol_id)
+    return rows[0] if rows else None
+
+
+def _find_symbols(name: str, kind: str = None, limit: int = 50) -> list:
+    return _rows(
+        "SELECT * FROM symbols WHERE (name LIKE ? OR qualified_name LIKE ?) AND (? IS NULL OR kind = ?) "
+        "ORDER BY name = ? OR qualified_name = ? DESC, length(name), file_path LIMIT ?",
+        f"%{name}%", f"%{name}%", kind, kind, name, name, limit,
+    )
+
+
+def _parameters(symbol_id: str) -> list:
+    return _rows(
+        "SELECT position, name, type_name AS type, default_value AS \"default\", kind FROM parameters "
+        "WHERE symbol_id = ? ORDER BY position",
+        symbol_id,
+    )
+
+
+def _with_parameters(symbols: list) -> list:
+    """Functions and methods with their `parameters`."""
+    for symbol in symbols:
+        if symbol["kind"] in CALLABLE_KINDS:
+            symbol["parameters"] = _parameters(symbol["id"])
+    return symbols
+
+
+def _relationships(symbol_id: str) -> list:
+    return _rows("SELECT * FROM relationships WHERE from_symbol_id = ?", symbol_id)
+
+
+def _dependents(symbol_id: str) -> list:
+    return _rows("SELECT * FROM relationships WHERE to_symbol_id = ?", symbol_id)
+
+
+def _all_symbols() -> list:
+    return _rows("SELECT * FROM symbols ORDER BY file_path, start_line")
+
+
+def _all_relationships() -> list:
+    return _rows("SELECT * FROM relationships")
+
+
+def _patterns() -> list:
+    return _rows("SELECT * FROM patterns")
+
+
+def _resolve_symbol(name: str) -> dict:
+    """Exact id first, then exact name, then partial name."""
+    symbol = _get_symbol(name)
+    if symbol:
+        return symbol
+    found = _find_symbols(name, None, 1)
+    if not found:
+        raise ValueError(f"Symbol not found: {name}")
+    return found[0]
+
+
+def _line_count(symbol: dict) -> int:
+    start, end = symbol.get("start_line"), symbol.get("end_line")
+    if start is not None and end is not None and end >= start:
+        return end - start + 1
+    return 0
+
+
+def _module_of(symbol: dict) -> str:
+    return symbol.get("scope") or "<root>"
+
+
+def _is_test_path(path: str) -> bool:
+    for segment in path.replace("\\", "/").split("/"):
+        segment = segment.lower()
+        if (segment in ("tests", "test", "__tests__") or segment.startswith("test_")
+                or ".test." in segment or ".spec." in segment
+                or segment.endswith("_test.py") or segment.endswith("_tests.rs")):
+            return True
+    return False
+
+
+def _fan_counts(relationships: list):
+    fan_out, fan_in = {}, {}
+    for rel in relationships:
+        if rel["from_symbol_id"]:
+            fan_out[rel["from_symbol_id"]] = fan_out.get(rel["from_symbol_id"], 0) + 1
+        if rel["to_symbol_id"]:
+            fan_in[rel["to_symbol_id"]] = fan_in.get(rel["to_symbol_id"], 0) + 1
+    return fan_out, fan_in
+
+
+def _edge(rel: dict, depth: int) -> dict:
+    return {"from": rel["from_symbol_id"], "to": rel["to_symbol_id"], "kind": rel["kind"],
+            "strength": rel["strength"], "depth": depth}
+
+
+def _tokenize(text: str) -> list:
+    """Lowercase terms split on punctuation, snake_case and camelCase boundaries."""
+    terms, current, prev_lower = [], "", False
+    for c in text:
+        if not c.isalnum():
+            if current:
+                terms.append(current)
+                current = ""
+            prev_lower = False
+            continue
+        if c.isupper() and prev_lower and current:
+            terms.append(current)
+            current = ""
+        prev_lower = c.islower() or c.isnumeric()
+        current += c.lower()
+    if current:
+        terms.append(current)
+    return terms
+
+
 @mcp.tool()
 def get_project_overview() -> dict:
     """Get comprehensive project statistics and structure."""
-    # Query database for metrics
-    pass
+    symbols = _all_symbols()
+    by_kind = {row["kind"]: row["count"] for row in _rows("SELECT kind, COUNT(*) AS count FROM symbols GROUP BY kind")}
+    files, extensions, modules = set(), {}, {}
+    for s in symbols:
+        if s["file_path"] not in files:
+            files.add(s["file_path"])
+            ext = os.path.splitext(s["file_path"])[1].lstrip(".")
+            extensions[ext] = extensions.get(ext, 0) + 1
+        modules[_module_of(s)] = modules.get(_module_of(s), 0) + 1
+    return {
+        "project": _project_name(),
+        "files": len(files),
+        "symbols": len(symbols),
+        "symbols_by_kind": dict(sorted(by_kind.items())),
+        "files_by_extension": dict(sorted(extensions.items())),
+        "symbols_by_module": dict(sorted(modules.items())),
+        "relationships": len(_all_relationships()),
+        "patterns": [p["name"] for p in _patterns() if p["name"]],
+    }
+
 
 @mcp.tool()
 def find_symbol(name: str, kind: str = None) -> list:
-    """Find symbols by name and optional type."""
-    # Search symbols table
-    pass
+    """Find symbols by name and optional type. Functions and methods include their parameters."""
+    return _with_parameters(_find_symbols(name, kind or None, 50))
+
+
+@mcp.tool()
+def find_references(symbol_name: str) -> dict:
+    """List the symbols that reference a symbol."""
+    target = _resolve_symbol(symbol_name)
+    references = [
+        {"from": rel["from_symbol_id"], "kind": rel["kind"],
+         "symbol": _get_symbol(rel["from_symbol_id"]) if rel["from_symbol_id"] else None}
+        for rel in _dependents(target["id"])
+    ]
+    return {"symbol": target, "references": references}
+
 
 @mcp.tool()
 def analyze_dependencies(symbol_name: str, depth: int = 2) -> dict:
     """Trace dependencies for a symbol."""
-    # Traverse relationship graph
-    pass
+    root = _resolve_symbol(symbol_name)
+    depth = max(1, min(depth, 10))
+    seen, queue, dependencies = {root["id"]}, deque([(root["id"], 0)]), []
+    while queue:
+        symbol_id, level = queue.popleft()
+        if level >= depth:
+            continue
+        for rel in _relationships(symbol_id):
+            dependencies.append(_edge(rel, level + 1))
+            to = rel["to_symbol_id"]
+            if to and to not in seen:
+                seen.add(to)
+                queue.append((to, level + 1))
+    dependents = [_edge(rel, 1) for rel in _dependents(root["id"])]
+    return {"symbol": root, "depth": depth, "dependencies": dependencies, "dependents": dependents}
+
 
 @mcp.tool()
 def trace_data_flow(variable: str) -> dict:
     """Trace how data flows through the system."""
-    # Follow variable usage
-    pass
+    flows = []
+    for symbol in _find_symbols(variable, None, 50):
+        if symbol["name"] != variable:
+            continue
+        flows.append({
+            "definition": symbol,
+            "used_by": [r["from_symbol_id"] for r in _dependents(symbol["id"]) if r["from_symbol_id"]],
+            "flows_to": [r["to_symbol_id"] for r in _relationships(symbol["id"]) if r["to_symbol_id"]],
+        })
+    return {"variable": variable, "flows": flows}
+
 
 @mcp.tool()
 def find_patterns(pattern_type: str = None) -> list:
     """Find design patterns in codebase."""
-    # Query patterns table
-    pass
+    wanted = pattern_type.lower() if pattern_type else None
+    result = []
+    for p in _patterns():
+        if wanted and wanted not in ((p["name"] or "").lower(), (p["pattern_type"] or "").lower()):
+            continue
+        try:
+            locations = json.loads(p["locations"]) if p["locations"] else p["locations"]
+        except ValueError:
+            locations = p["locations"]
+        result.append({"name": p["name"], "pattern_type": p["pattern_type"],
+                       "occurrences": p["occurrences"], "locations": locations})
+    return result
+
 
 @mcp.tool()
-def analyze_complexity(module: str = None, threshold: int = 10) -> dict:
-    """Analyze code complexity metrics."""
-    # Calculate complexity scores
-    pass
+def analyze_complexity(module: str = None, threshold: int = None) -> dict:
+    """Analyze code complexity metrics (approximated from symbol length and call fan-out).
+
+    threshold defaults to the build's analysis.complexity_threshold.
+    """
+    if threshold is None:
+        threshold = _config("analysis", "complexity_threshold") or 10
+    fan_out, _ = _fan_counts(_all_relationships())
+    scored = []
+    for s in _all_symbols():
+        if s["kind"] not in CALLABLE_KINDS or (module and _module_of(s) != module):
+            continue
+        lines, calls = _line_count(s), fan_out.get(s["id"], 0)
+        scored.append((1 + calls + lines // 10, s, lines, calls))
+    scored.sort(key=lambda item: (-item[0], item[1]["id"]))
+    average = sum(item[0] for item in scored) / len(scored) if scored else 0.0
+    return {
+        "module": module,
+        "threshold": threshold,
+        "analyzed": len(scored),
+        "average_complexity": average,
+        "over_threshold": [
+            {"symbol": s["id"], "file_path": s["file_path"], "complexity": score, "lines": lines, "calls": calls}
+            for score, s, lines, calls in scored if score >= threshold
+        ],
+    }
+
 
 @mcp.tool()
 def suggest_refactoring(symbol: str = None) -> list:
     """Suggest refactoring opportunities."""
-    # Analyze issues and suggest improvements
-    pass
+    candidates = [_resolve_symbol(symbol)] if symbol else _all_symbols()
+    fan_out, fan_in = _fan_counts(_all_relationships())
+    suggestions = []
+    for s in candidates:
+        lines, calls, callers = _line_count(s), fan_out.get(s["id"], 0), fan_in.get(s["id"], 0)
+
+        def push(issue, suggestion):
+            suggestions.append({"symbol": s["id"], "file_path": s["file_path"], "issue": issue, "suggestion": suggestion})
+
+        if s["kind"] in CALLABLE_KINDS and lines > 50:
+            push(f"{lines} lines long", "Extract cohesive blocks into smaller helper functions.")
+        if s["kind"] == "class" and lines > 300:
+            push(f"class spans {lines} lines", "Split responsibilities into separate classes.")
+        if calls > 10:
+            push(f"calls {calls} other symbols", "Reduce coupling by grouping collaborators behind an abstraction.")
+        if callers > 15:
+            push(f"used by {callers} symbols", "Keep this interface stable; consider documenting it as a public contract.")
+    return suggestions
+
 
 @mcp.tool()
 def semantic_search(query: str, limit: int = 10) -> list:
     """Search code using natural language."""
-    # Use embeddings for similarity search
-    pass
+    query_terms = {t for t in _tokenize(query) if len(t) > 1}
+    if not query_terms:
+        return []
+    content = {}
+    for row in _rows("SELECT symbol_id, content FROM embeddings"):
+        if row["symbol_id"] and row["content"]:
+            content[row["symbol_id"]] = content.get(row["symbol_id"], "") + row["content"]
+    results = []
+    for s in _all_symbols():
+        name_terms = set(_tokenize(s["name"]))
+        body = " ".join([s["file_path"], s["documentation"] or "", s["signature"] or "", content.get(s["id"], "")])
+        body_terms = set(_tokenize(body))
+        score = sum(3.0 if t in name_terms else 1.0 if t in body_terms else 0.0 for t in query_terms)
+        if score > 0:
+            results.append((score / (3.0 * len(query_terms)), s))
+    results.sort(key=lambda item: (-item[0], item[1]["id"]))
+    return [{"score": score, "symbol": s} for score, s in results[:max(limit, 1)]]
+
 
 @mcp.tool()
 def get_test_coverage(module: str = None) -> dict:
     """Analyze test coverage."""
-    # Find test files and map to source
-    pass
+    symbols = _all_symbols()
+    test_ids = {s["id"] for s in symbols if _is_test_path(s["file_path"]) or s["name"].startswith("test_")}
+    test_files = {s["file_path"] for s in symbols if s["id"] in test_ids}
+    covered = {r["to_symbol_id"] for r in _all_relationships()
+               if r["from_symbol_id"] in test_ids and r["to_symbol_id"]}
+    # `test_foo` conventionally exercises `foo`.
+    tested_names = {s["name"][len("test_"):] for s in symbols if s["id"] in test_ids and s["name"].startswith("test_")}
+    targets = [s for s in symbols
+               if s["kind"] in CALLABLE_KINDS and s["id"] not in test_ids
+               and (not module or _module_of(s) == module)]
+    for s in targets:
+        if s["name"] in tested_names:
+            covered.add(s["id"])
+    untested = [s["id"] for s in targets if s["id"] not in covered]
+    tested = len(targets) - len(untested)
+    return {
+        "module": module,
+        "test_files": len(test_files),
+        "callables": len(targets),
+        "tested": tested,
+        "coverage_percent": tested * 100.0 / len(targets) if targets else 0.0,
+        "untested": untested[:50],
+    }
+
 
 @mcp.tool()
 def explain_architecture() -> dict:
     """Explain the overall system architecture."""
-    # Analyze top-level structure
-    pass
\ No newline at end of file
+    symbols = _all_symbols()
+    module_by_id = {s["id"]: _module_of(s) for s in symbols}
+    modules = {}
+    for s in symbols:
+        files, count = modules.get(_module_of(s), (set(), 0))
+        files.add(s["file_path"])
+        modules[_module_of(s)] = (files, count + 1)
+    edges = {}
+    for rel in _all_relationships():
+        source, target = module_by_id.get(rel["from_symbol_id"]), module_by_id.get(rel["to_symbol_id"])
+        if source and target and source != target:
+            edges[(source, target)] = edges.get((source, target), 0) + 1
+    entry_points = sorted({
+        s["file_path"] for s in symbols
+        if os.path.splitext(os.path.basename(s["file_path"]))[0] in ("main", "index", "app", "server", "__main__", "lib")
+    })
+    return {
+        "summary": f"{_project_name()} has {len(modules)} modules, {len(symbols)} symbols "
+                   f"and {sum(edges.values())} cross-module dependencies.",
+        "modules": [{"name": name, "files": len(files), "symbols": count}
+                    for name, (files, count) in sorted(modules.items())],
+        "module_dependencies": [{"from": a, "to": b, "edges": count} for (a, b), count in sorted(edges.items())],
+        "entry_points": entry_points,
+        "patterns": [p["name"] for p in _patterns() if p["name"]],
+    }
+
+
+# Resources: file://<path> for every indexed file, symbol://<id> for every symbol.
+
+def _mime_type(path: str) -> str:
+    return MIME_TYPES.get(os.path.splitext(path)[1], "text/plain")
+
+
+def _read_source(path: str):
+    """Read an indexed file below the project root; paths escaping the root are refused."""
+    root = _project_config("root")
+    parts = path.replace("\\", "/").split("/")
+    if not root or os.path.isabs(path) or ".." in parts:
+        return None
+    try:
+        with open(os.path.join(root, path), encoding="utf-8") as f:
+            return f.read()
+    except (OSError, UnicodeDecodeError):
+        return None
+
+
+def _symbol_code(symbol: dict):
+    start, end = symbol.get("start_line"), symbol.get("end_line")
+    source = _read_source(symbol["file_path"])
+    if start is None or end is None or source is None:
+        return None
+    lines = source.splitlines()[max(start, 1) - 1:end]
+    return "\n".join(lines) if lines else None
+
+
+def _location(symbol: dict) -> str:
+    if symbol.get("start_line") is not None and symbol.get("end_line") is not None:
+        return f"{symbol['file_path']}:{symbol['start_line']}-{symbol['end_line']}"
+    return symbol["file_path"]
+
+
+def _read_file(path: str) -> str:
+    if not _rows("SELECT path FROM files WHERE path = ? AND skip_reason IS NULL", path):
+        raise ValueError(f"Resource not found: file://{path}")
+    source = _read_source(path)
+    if source is None:
+        raise ValueError(f"Resource not found: file://{path}")
+    return source
+
+
+def _read_symbol(symbol_id: str) -> str:
+    symbol = _get_symbol(symbol_id)
+    if not symbol:
+        raise ValueError(f"Resource not found: symbol://{symbol_id}")
+    return json.dumps({
+        "symbol": symbol,
+        "parameters": _parameters(symbol_id),
+        "code": _symbol_code(symbol),
+        "callers": [r["from_symbol_id"] for r in _dependents(symbol_id) if r["from_symbol_id"]],
+        "callees": [r["to_symbol_id"] for r in _relationships(symbol_id) if r["to_symbol_id"]],
+    }, indent=2)
+
+
+def _register_resources() -> None:
+    if not os.path.exists(DB_PATH):
+        return
+    files = _rows(
+        "SELECT path, (SELECT COUNT(*) FROM symbols WHERE symbols.file_path = files.path) AS count "
+        "FROM files WHERE skip_reason IS NULL ORDER BY path"
+    )
+    for f in files:
+        mcp.add_resource(FunctionResource(
+            uri=f"file://{f['path']}", name=f["path"], description=f"{f['count']} symbols",
+            mime_type=_mime_type(f["path"]), fn=lambda path=f["path"]: _read_file(path),
+        ))
+    for s in _all_symbols():
+        mcp.add_resource(FunctionResource(
+            uri=f"symbol://{s['id']}", name=s["name"], description=f"{s['kind']} in {_location(s)}",
+            mime_type="application/json", fn=lambda symbol_id=s["id"]: _read_symbol(symbol_id),
+        ))
+
+
+_register_resources()
+
+
+@mcp.resource("file://{path}", name="Source file")
+def source_file(path: str) -> str:
+    """Contents of an indexed file, by path relative to the project root."""
+    return _read_file(path)
+
+
+@mcp.resource("symbol://{id}", name="Symbol", mime_type="application/json")
+def symbol_resource(id: str) -> str:
+    """A symbol with its code, callers and callees, by id (`<file>::<name>`)."""
+    return _read_symbol(id)
+
+
+@mcp.resource("symbols://{name}", name="Symbol search", mime_type="application/json")
+def symbol_search(name: str) -> str:
+    """Symbols whose name contains the given text."""
+    return json.dumps(_find_symbols(name, None, 50), indent=2)
+
+
+# Prompts, pre-filled from the knowledge base.
+
+def _list_section(heading: str, items: list, empty: str) -> str:
+    text = f"\n## {heading}\n"
+    if not items:
+        text += f"- {empty}\n"
+    for item in items[:PROMPT_LIST_LIMIT]:
+        text += f"- {item}\n"
+    if len(items) > PROMPT_LIST_LIMIT:
+        text += f"- ... and {len(items) - PROMPT_LIST_LIMIT} more\n"
+    return text
+
+
+@mcp.prompt()
+def explain_module(module: str) -> str:
+    """Explain a module using its files, symbols and dependencies from the knowledge base."""
+    symbols = _all_symbols()
+    members = [s for s in symbols if _module_of(s) == module]
+    if not members:
+        known = ", ".join(sorted({_module_of(s) for s in symbols}))
+        raise ValueError(f"Unknown module: {module} (known: {known})")
+    module_by_id = {s["id"]: _module_of(s) for s in symbols}
+    uses, used_by = {}, {}
+    for rel in _all_relationships():
+        source, target = module_by_id.get(rel["from_symbol_id"]), module_by_id.get(rel["to_symbol_id"])
+        if source and target and source == module and target != module:
+            uses[target] = uses.get(target, 0) + 1
+        elif source and target and target == module and source != module:
+            used_by[source] = used_by.get(source, 0) + 1
+    files = {}
+    for s in members:
+        files[s["file_path"]] = files.get(s["file_path"], 0) + 1
+
+    def describe(s: dict) -> str:
+        doc = (s.get("documentation") or "").split("\n")[0].strip()
+        base = f"{s['kind']} `{s['name']}` ({_location(s)})"
+        return f"{base}: {doc}" if doc else base
+
+    text = f"Explain the `{module}` module of {_project_name()}.\n"
+    text += _list_section("Files", [f"file://{p} ({n} symbols)" for p, n in sorted(files.items())], "none")
+    text += _list_section("Symbols", [describe(s) for s in members], "none")
+    text += _list_section("Depends on", [f"`{m}` ({n} references)" for m, n in sorted(uses.items())], "no other modules")
+    text += _list_section("Used by", [f"`{m}` ({n} references)" for m, n in sorted(used_by.items())], "no other modules")
+    text += ("\nDescribe what this module is responsible for, its main entry points and how it "
+             "interacts with the modules above. Read the listed file:// resources for the code.\n")
+    return text
+
+
+@mcp.prompt()
+def review_change(symbol: str, change: str = None) -> str:
+    """Review a change to a symbol with its current code, callers and callees."""
+    target = _resolve_symbol(symbol)
+    text = f"Review a change to `{target['id']}` in {_project_name()}.\n"
+    text += f"\n## Symbol\n- kind: {target['kind']}\n- location: {_location(target)}\n"
+    if target.get("signature"):
+        text += f"- signature: `{target['signature']}`\n"
+    if target.get("documentation"):
+        text += f"- documentation: {target['documentation'].strip()}\n"
+    if change:
+        text += f"\n## Proposed change\n{change.rstrip()}\n"
+    code = _symbol_code(target)
+    if code:
+        text += f"\n## Current code\n```\n{code}\n```\n"
+
+    def edge(symbol_id: str, kind: str) -> str:
+        test = ", test" if _is_test_path(symbol_id.split("::")[0]) else ""
+        return f"`{symbol_id}` ({kind or 'references'}{test})"
+
+    callers = [edge(r["from_symbol_id"], r["kind"]) for r in _dependents(target["id"]) if r["from_symbol_id"]]
+    callees = [edge(r["to_symbol_id"], r["kind"]) for r in _relationships(target["id"]) if r["to_symbol_id"]]
+    text += _list_section("Callers", callers, "none indexed")
+    text += _list_section("Callees", callees, "none indexed")
+    text += ("\nCheck that the change keeps the behaviour the callers rely on, name the callers and "
+             "tests that need updating, and point out missing tests.\n")
+    return text
+
+
+if __name__ == "__main__":
+    # MCP_TRANSPORT=streamable-http serves the MCP HTTP endpoint on MCP_PORT
+    transport = os.environ.get("MCP_TRANSPORT", "stdio")
+    if transport != "stdio":
+        mcp.settings.host = os.environ.get("MCP_HOST", "127.0.0.1")
+        mcp.settings.port = int(os.environ.get("MCP_PORT", "{{SERVER_PORT}}"))
+    mcp.run(transport=transport)
@@ -1,4 +1,9 @@
 #!/bin/bash
 
-# Start the MCP server
-python3 server.py
\ No newline at end of file
+# Start the MCP server (stdio by default; pass a port to serve streamable HTTP)
+cd "$(dirname "$0")" || exit 1
+if [ -n "$1" ]; then
+    MCP_TRANSPORT=streamable-http MCP_PORT="$1" python3 server.py
+else
+    python3 server.py
+fi
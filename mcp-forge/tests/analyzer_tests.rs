@@ -1,7 +1,17 @@
 #[cfg(test)]
 mod tests {
     use std::path::Path;
-    use mcp_forge::analyzer::{discover_files, parser::CodeParser};
+    use mcp_forge::analyzer::{discover, discover_files, discover_files_with, parser::CodeParser};
+    use mcp_forge::analyzer::content::{decode, generated_marker, is_binary, minified, Encoding, SkipReason};
+    use mcp_forge::analyzer::parser::ParserPool;
+    use mcp_forge::analyzer::diagnostics::SyntaxErrorKind;
+    use mcp_forge::analyzer::embedded::{regions, RegionKind};
+    use mcp_forge::analyzer::languages::{registry, Project};
+    use mcp_forge::analyzer::patterns::PatternDetector;
+    use mcp_forge::analyzer::symbols::SymbolExtractor;
+    use mcp_forge::analyzer::tags::TagQueries;
+    use mcp_forge::config::{ByteSize, IndexingConfig, QueryOverride};
+    use mcp_forge::analyzer::report::{analyze, ReportFormat};
 
     #[test]
     fn test_discover_files() {
@@ -16,4 +26,716 @@ mod tests {
         let tree = parser.parse_file(path);
         assert!(tree.is_ok(), "Should parse Python file successfully");
     }
+
+    #[test]
+    fn test_analyze_report() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("backend")).unwrap();
+        std::fs::write(dir.path().join("backend/repo.py"), "class UserRepo:\n    def find_user(self):\n        pass\n\n    def save_user(self):\n        pass\n").unwrap();
+        std::fs::write(dir.path().join("index.ts"), "function createApp() {\n  return 1;\n}\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not code").unwrap();
+
+        let report = analyze(dir.path());
+        assert_eq!(report.files_analyzed, 2);
+        assert_eq!(report.files_skipped, 1);
+        assert_eq!(report.languages["python"].lines, 6);
+        assert_eq!(report.symbols_by_kind["class"], 1);
+        assert_eq!(report.top_modules[0].name, "backend");
+        assert!(report.patterns.iter().any(|p| p.name == "Repository"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.render(ReportFormat::Json)).unwrap();
+        assert_eq!(json["files_analyzed"], 2);
+        assert!(report.render(ReportFormat::Markdown).contains("| python | 1 | 6 |"));
+        assert!(report.render(ReportFormat::Table).contains("Top modules"));
+    }
+
+    #[test]
+    fn test_language_registry() {
+        assert_eq!(CodeParser::language_for(Path::new("app/models.py")), Some("python"));
+        assert_eq!(CodeParser::language_for(Path::new("notes.txt")), None);
+        let python = registry().get("python").unwrap();
+
+        let code = "class Admin(User):\n    def promote(self):\n        save(self)\n";
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_source("python", code).unwrap();
+        let extractor = SymbolExtractor::new();
+        let symbols = extractor.extract_symbols(&tree.root_node(), code, "app/models.py");
+        assert_eq!(symbols.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["Admin", "promote"]);
+
+        let tags = extractor.extract_tags(&tree.root_node(), code, "app/models.py");
+        let edges: Vec<(String, String)> = python
+            .relationships(&tree.root_node(), code, "app/models.py", &tags, &Project::default())
+            .into_iter()
+            .map(|r| (r.kind, r.to))
+            .collect();
+        assert!(edges.contains(&("inherits".to_string(), "app/models.py::User".to_string())));
+        assert!(edges.contains(&("calls".to_string(), "app/models.py::save".to_string())));
+
+        let indexing = IndexingConfig { languages: vec!["rust".to_string()], ..Default::default() };
+        assert!(CodeParser::for_indexing(&indexing).parse_source("python", code).is_err());
+    }
+
+    #[test]
+    fn test_java_support() {
+        let code = "package com.acme.service;\n\nimport com.acme.model.User;\nimport com.acme.repo.*;\n\n@Service\npublic class UserService extends BaseService implements Lookup<User> {\n    @Autowired\n    private final UserRepository repo;\n\n    public UserService(UserRepository repo) {\n        this.repo = repo;\n    }\n\n    public User find(long id) {\n        return repo.findById(id);\n    }\n}\n";
+        let path = "src/main/java/com/acme/service/UserService.java";
+        let files: Vec<String> = [
+            path,
+            "src/main/java/com/acme/service/BaseService.java",
+            "src/main/java/com/acme/model/User.java",
+            "src/main/java/com/acme/repo/UserRepository.java",
+            "src/main/java/com/acme/repo/Lookup.java",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_source("java", code).unwrap();
+        let extractor = SymbolExtractor::new();
+        let symbols = extractor.extract_symbols(&tree.root_node(), code, path);
+        let kinds: Vec<(&str, &str)> = symbols.iter().map(|s| (s.kind.as_str(), s.name.as_str())).collect();
+        assert_eq!(kinds, [
+            ("package", "com.acme.service"),
+            ("import", "com.acme.model.User"),
+            ("import", "com.acme.repo.*"),
+            ("class", "UserService"),
+            ("field", "repo"),
+            ("constructor", "UserService"),
+            ("method", "find"),
+        ]);
+        let field = symbols.iter().find(|s| s.kind == "field").unwrap();
+        assert_eq!(field.signature.as_deref(), Some("@Autowired private final UserRepository repo"));
+
+        let edges: Vec<(String, String, String)> = registry()
+            .get("java")
+            .unwrap()
+            .relationships(&tree.root_node(), code, path, &extractor.extract_tags(&tree.root_node(), code, path), &Project::new("", files))
+            .into_iter()
+            .map(|r| (r.kind, r.from, r.to))
+            .collect();
+        let class = format!("{}::UserService", path);
+        assert!(edges.contains(&("inherits".to_string(), class.clone(), "src/main/java/com/acme/service/BaseService.java::BaseService".to_string())));
+        assert!(edges.contains(&("implements".to_string(), class, "src/main/java/com/acme/repo/Lookup.java::Lookup".to_string())));
+        assert!(edges.contains(&("imports".to_string(), format!("{}::com.acme.model.User", path), "src/main/java/com/acme/model/User.java::User".to_string())));
+        assert!(edges.contains(&("calls".to_string(), format!("{}::UserService.find", path), format!("{}::findById", path))));
+
+        let patterns = PatternDetector::new().detect_patterns(code, "java");
+        assert_eq!(patterns.get("Dependency Injection"), Some(&0.8));
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let code = "class Users:\n    def get(self):\n        return load()\n\nclass Orders:\n    def get(self):\n        def load():\n            pass\n        return load()\n\n    def get(self, key):\n        pass\n";
+        let path = "src/shop/api.py";
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_source("python", code).unwrap();
+        let extractor = SymbolExtractor::new();
+        let symbols = extractor.extract_symbols(&tree.root_node(), code, path);
+        let scopes: Vec<(&str, &str, Option<&str>)> = symbols
+            .iter()
+            .map(|s| (s.id.strip_prefix("src/shop/api.py::").unwrap(), s.qualified_name.as_str(), s.parent_id.as_deref()))
+            .collect();
+        assert_eq!(scopes, [
+            ("Users", "shop.api.Users", None),
+            ("Users.get", "shop.api.Users.get", Some("src/shop/api.py::Users")),
+            ("Orders", "shop.api.Orders", None),
+            ("Orders.get", "shop.api.Orders.get", Some("src/shop/api.py::Orders")),
+            ("Orders.get.load", "shop.api.Orders.get.load", Some("src/shop/api.py::Orders.get")),
+            ("Orders.get~2", "shop.api.Orders.get", Some("src/shop/api.py::Orders")),
+        ]);
+
+        let tags = extractor.extract_tags(&tree.root_node(), code, path);
+        let calls: Vec<(String, String)> = registry()
+            .get("python")
+            .unwrap()
+            .relationships(&tree.root_node(), code, path, &tags, &Project::default())
+            .into_iter()
+            .filter(|r| r.kind == "calls")
+            .map(|r| (r.from, r.to))
+            .collect();
+        assert!(calls.contains(&("src/shop/api.py::Orders.get".to_string(), "src/shop/api.py::Orders.get.load".to_string())));
+
+        let rust = registry().get("rust").unwrap();
+        assert_eq!(rust.module_path("mcp-forge/src/analyzer/parser.rs"), "crate::analyzer::parser");
+        assert_eq!(rust.module_path("src/lib.rs"), "crate");
+        assert_eq!(registry().get("java").unwrap().module_path("src/main/java/com/acme/User.java"), "com.acme");
+        assert_eq!(registry().get("python").unwrap().module_path("app/models/__init__.py"), "app.models");
+    }
+
+    #[test]
+    fn test_go_support() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("svc/go.mod", "module example.com/svc\n\ngo 1.21\n");
+        write("svc/store/store.go", "package store\n\ntype Store interface {\n\tSave(u string) error\n}\n");
+        write("svc/store/memory.go", "package store\n\ntype Memory struct{}\n\nfunc (m *Memory) Save(u string) error { return nil }\n");
+        let main = "package main\n\nimport (\n\t\"fmt\"\n\t\"example.com/svc/store\"\n)\n\nconst Version = \"1\"\n\ntype Option func(*Server)\n\ntype Server struct{ store store.Store }\n\nfunc NewServer(opts ...Option) *Server {\n\treturn &Server{}\n}\n\nfunc (s *Server) Run() {\n\tfmt.Println(Version)\n}\n";
+        write("svc/cmd/main.go", main);
+
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_file(&dir.path().join("svc/cmd/main.go")).unwrap();
+        let extractor = SymbolExtractor::new();
+        let symbols = extractor.extract_symbols(&tree.root_node(), main, "svc/cmd/main.go");
+        let kinds: Vec<(&str, &str)> = symbols.iter().map(|s| (s.kind.as_str(), s.name.as_str())).collect();
+        assert_eq!(kinds, [
+            ("package", "main"),
+            ("import", "fmt"),
+            ("import", "example.com/svc/store"),
+            ("constant", "Version"),
+            ("type", "Option"),
+            ("struct", "Server"),
+            ("function", "NewServer"),
+            ("method", "Server.Run"),
+        ]);
+        let run = symbols.last().unwrap();
+        assert_eq!(run.signature.as_deref(), Some("func (s *Server) Run()"));
+        assert_eq!((run.id.as_str(), run.qualified_name.as_str()), ("svc/cmd/main.go::Server.Run", "svc/cmd.Server.Run"));
+        assert_eq!(run.parent_id.as_deref(), Some("svc/cmd/main.go::Server"));
+
+        let project = Project::from_paths(dir.path(), &discover_files(dir.path()));
+        let go = registry().get("go").unwrap();
+        let tags = extractor.extract_tags(&tree.root_node(), main, "svc/cmd/main.go");
+        let imports: Vec<String> = go
+            .relationships(&tree.root_node(), main, "svc/cmd/main.go", &tags, &project)
+            .into_iter()
+            .filter(|r| r.kind == "imports")
+            .map(|r| r.to)
+            .collect();
+        assert_eq!(imports, ["svc/store/memory.go::store", "svc/store/store.go::store"]);
+        let implements: Vec<(String, String)> = registry()
+            .project_relationships(&project)
+            .into_iter()
+            .filter(|r| r.kind == "implements")
+            .map(|r| (r.from, r.to))
+            .collect();
+        assert_eq!(implements, [("svc/store/memory.go::Memory".to_string(), "svc/store/store.go::Store".to_string())]);
+
+        let patterns = PatternDetector::new().detect_patterns(main, "go");
+        assert_eq!(patterns.get("Functional Options"), Some(&0.9));
+        assert_eq!(patterns.get("Factory"), Some(&0.7));
+    }
+
+    #[test]
+    fn test_rust_support() {
+        let code = "use std::fmt;\n\n/// A connection pool.\n#[derive(Debug, Clone)]\npub struct Pool {\n    pub(crate) size: usize,\n}\n\npub enum Mode {\n    Fast,\n    Safe,\n}\n\npub trait Source: fmt::Debug {\n    type Item;\n    fn next(&mut self) -> Option<Self::Item>;\n}\n\nimpl Pool {\n    pub const MAX: usize = 8;\n\n    pub(crate) fn get(&self) -> usize {\n        self.size\n    }\n}\n\nimpl Source for Pool {\n    type Item = usize;\n    fn next(&mut self) -> Option<usize> {\n        None\n    }\n}\n\nimpl fmt::Display for Pool {\n    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n        write!(f, \"pool\")\n    }\n}\n\nstatic POOLS: usize = 0;\n\nmacro_rules! pool {\n    () => { Pool { size: 0 } };\n}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn creates_pool() {}\n}\n";
+        let path = "src/db.rs";
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_source("rust", code).unwrap();
+        let extractor = SymbolExtractor::new();
+        let symbols = extractor.extract_symbols(&tree.root_node(), code, path);
+        let kinds: Vec<(&str, &str)> = symbols.iter().map(|s| (s.kind.as_str(), s.id.strip_prefix("src/db.rs::").unwrap())).collect();
+        assert_eq!(kinds, [
+            ("struct", "Pool"),
+            ("field", "Pool.size"),
+            ("enum", "Mode"),
+            ("variant", "Mode.Fast"),
+            ("variant", "Mode.Safe"),
+            ("trait", "Source"),
+            ("type", "Source.Item"),
+            ("method", "Source.next"),
+            ("impl", "impl Pool"),
+            ("constant", "Pool.MAX"),
+            ("method", "Pool.get"),
+            ("impl", "impl Source for Pool"),
+            ("type", "Pool.Item"),
+            ("method", "Pool.next"),
+            ("impl", "impl fmt::Display for Pool"),
+            ("method", "Pool.fmt"),
+            ("static", "POOLS"),
+            ("macro", "pool"),
+            ("module", "tests"),
+            ("function", "tests.creates_pool"),
+        ]);
+
+        let symbol = |id: &str| symbols.iter().find(|s| s.id == format!("src/db.rs::{}", id)).unwrap();
+        let pool = symbol("Pool");
+        assert_eq!(pool.documentation.as_deref(), Some("A connection pool."));
+        assert_eq!(pool.visibility.as_deref(), Some("pub"));
+        assert_eq!(pool.attributes, ["derive(Debug, Clone)"]);
+        let get = symbol("Pool.get");
+        assert_eq!(get.qualified_name, "crate::db::Pool::get");
+        assert_eq!(get.parent_id.as_deref(), Some("src/db.rs::Pool"));
+        assert_eq!(get.visibility.as_deref(), Some("pub(crate)"));
+        assert_eq!(symbol("Pool.size").visibility.as_deref(), Some("pub(crate)"));
+        assert_eq!(symbol("Pool.fmt").visibility, None);
+        assert_eq!(symbol("impl Pool").parent_id, None);
+        assert_eq!(symbol("tests").attributes, ["cfg(test)"]);
+        assert_eq!(symbol("tests.creates_pool").attributes, ["test"]);
+
+        let tags = extractor.extract_tags(&tree.root_node(), code, path);
+        let edges: Vec<(String, String, String)> = registry()
+            .get("rust")
+            .unwrap()
+            .relationships(&tree.root_node(), code, path, &tags, &Project::default())
+            .into_iter()
+            .map(|r| (r.kind, r.from, r.to))
+            .collect();
+        assert!(edges.contains(&("implements".to_string(), "src/db.rs::Pool".to_string(), "src/db.rs::Source".to_string())));
+        assert!(edges.contains(&("implements".to_string(), "src/db.rs::Pool".to_string(), "src/db.rs::Display".to_string())));
+        assert!(edges.contains(&("inherits".to_string(), "src/db.rs::Source".to_string(), "src/db.rs::Debug".to_string())));
+    }
+
+    #[test]
+    fn test_react_and_module_extensions() {
+        for (file, language) in [
+            ("App.tsx", "tsx"),
+            ("App.jsx", "javascript"),
+            ("index.mjs", "javascript"),
+            ("index.cjs", "javascript"),
+            ("util.mts", "typescript"),
+            ("util.cts", "typescript"),
+            ("stubs.pyi", "python"),
+        ] {
+            assert_eq!(CodeParser::language_for(Path::new(file)), Some(language), "{}", file);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("deploy");
+        std::fs::write(&script, "#!/usr/bin/env python3\ndef main():\n    pass\n").unwrap();
+        std::fs::write(dir.path().join("README"), "plain text").unwrap();
+        assert_eq!(CodeParser::language_for(&script), Some("python"));
+        assert_eq!(CodeParser::language_for(&dir.path().join("README")), None);
+        assert_eq!(registry().for_shebang("#!/usr/bin/env -S node --no-warnings").map(|l| l.name()), Some("javascript"));
+
+        let code = "import React, { useState } from 'react';\n\nexport function useCounter(start: number) {\n  const [count, setCount] = useState(start);\n  return { count, setCount };\n}\n\nexport const Counter = ({ start }: { start: number }) => {\n  const { count } = useCounter(start);\n  return <span>{count}</span>;\n};\n\nclass Legacy extends React.Component<Props> {\n  render() {\n    return <div />;\n  }\n}\n\nfunction format(n: number) {\n  return `${n}`;\n}\n";
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_source("tsx", code).unwrap();
+        assert!(!tree.root_node().has_error());
+        let extractor = SymbolExtractor::new();
+        let symbols = extractor.extract_symbols(&tree.root_node(), code, "web/Counter.tsx");
+        let kind = |name: &str| symbols.iter().find(|s| s.name == name).map(|s| s.kind.as_str());
+        assert_eq!(kind("useCounter"), Some("hook"));
+        assert_eq!(kind("Counter"), Some("component"));
+        assert_eq!(kind("Legacy"), Some("component"));
+        assert_eq!(kind("format"), Some("function"));
+
+        let edges: Vec<(String, String, String)> = registry()
+            .get("tsx")
+            .unwrap()
+            .relationships(&tree.root_node(), code, "web/Counter.tsx", &extractor.extract_tags(&tree.root_node(), code, "web/Counter.tsx"), &Project::default())
+            .into_iter()
+            .map(|r| (r.kind, r.from, r.to))
+            .collect();
+        assert!(edges.contains(&("uses_hook".to_string(), "web/Counter.tsx::Counter".to_string(), "web/Counter.tsx::useCounter".to_string())));
+        assert!(edges.contains(&("uses_hook".to_string(), "web/Counter.tsx::useCounter".to_string(), "web/Counter.tsx::useState".to_string())));
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let extract = |language: &str, path: &str, code: &str| {
+            let tree = CodeParser::new().parse_source(language, code).unwrap();
+            SymbolExtractor::new().extract_symbols(&tree.root_node(), code, path)
+        };
+
+        let python = extract("python", "app/users.py", "def find(user_id, active=True):\n    \"\"\"Find a user.\n\n    Looks in the cache first.\n\n    Args:\n        user_id (int): Id of the user,\n            never negative.\n        active: Only active users.\n\n    Returns:\n        User: The user.\n\n    Raises:\n        KeyError: If there is none.\n    \"\"\"\n");
+        let doc = python[0].doc_tags.as_ref().unwrap();
+        assert_eq!(doc.summary, "Find a user.");
+        let params: Vec<(&str, Option<&str>, &str)> = doc.params.iter().map(|p| (p.name.as_str(), p.type_name.as_deref(), p.description.as_str())).collect();
+        assert_eq!(params, [("user_id", Some("int"), "Id of the user, never negative."), ("active", None, "Only active users.")]);
+        let returns = doc.returns.as_ref().unwrap();
+        assert_eq!((returns.type_name.as_deref(), returns.description.as_str()), (Some("User"), "The user."));
+        assert_eq!((doc.throws[0].type_name.as_deref(), doc.throws[0].description.as_str()), (Some("KeyError"), "If there is none."));
+        assert!(python[0].documentation.as_deref().unwrap().contains("\n    user_id (int): Id of the user,\n"));
+
+        let ts = extract("typescript", "web/api.ts", "/* Copyright Acme. */\nfunction a() {}\n\n/**\n * Load a page.\n * @param {string} url - Page address.\n * @param {number} [retries=3] How often to retry.\n * @returns {Promise<Page>} The page.\n * @throws {HttpError} When the server fails.\n * @deprecated Use fetchPage.\n */\nfunction load(url: string, retries = 3) {}\n");
+        assert_eq!(ts[0].documentation, None);
+        let doc = ts[1].doc_tags.as_ref().unwrap();
+        let params: Vec<(&str, Option<&str>, &str)> = doc.params.iter().map(|p| (p.name.as_str(), p.type_name.as_deref(), p.description.as_str())).collect();
+        assert_eq!(params, [("url", Some("string"), "Page address."), ("retries", Some("number"), "How often to retry.")]);
+        assert_eq!(doc.returns.as_ref().unwrap().type_name.as_deref(), Some("Promise<Page>"));
+        assert_eq!(doc.throws[0].type_name.as_deref(), Some("HttpError"));
+        assert_eq!(doc.sections["deprecated"], "Use fetchPage.");
+
+        let java = extract("java", "src/Shop.java", "class Shop {\n    /* not documentation */\n    void open() {}\n\n    /**\n     * Pay an order.\n     *\n     * @param order the order\n     * @return the receipt\n     * @throws PaymentException if the card is declined\n     */\n    Receipt pay(Order order) { return null; }\n}\n");
+        assert_eq!(java[1].documentation, None);
+        let doc = java[2].doc_tags.as_ref().unwrap();
+        assert_eq!((doc.summary.as_str(), doc.params[0].name.as_str(), doc.params[0].description.as_str()), ("Pay an order.", "order", "the order"));
+        assert_eq!(doc.returns.as_ref().unwrap().description, "the receipt");
+        assert_eq!((doc.throws[0].type_name.as_deref(), doc.throws[0].description.as_str()), (Some("PaymentException"), "if the card is declined"));
+
+        let rust = extract("rust", "src/lib.rs", "// Not documentation.\n/// Open a file.\n///\n/// # Arguments\n///\n/// * `path` - Where the file is.\n///\n/// # Errors\n///\n/// When it does not exist.\n///\n/// # Examples\n///\n/// ```\n/// # use demo::open;\n/// open(\"a\");\n/// ```\npub fn open(path: &str) {}\n\nmod io {\n    //! Input and output.\n}\n");
+        assert_eq!(rust[0].documentation.as_deref().unwrap().lines().next(), Some("Open a file."));
+        let doc = rust[0].doc_tags.as_ref().unwrap();
+        assert_eq!((doc.params[0].name.as_str(), doc.params[0].description.as_str()), ("path", "Where the file is."));
+        assert_eq!(doc.sections["errors"], "When it does not exist.");
+        assert_eq!(doc.sections["examples"], "```\n# use demo::open;\nopen(\"a\");\n```");
+        assert_eq!(rust[1].documentation.as_deref(), Some("Input and output."));
+    }
+
+    #[test]
+    fn test_signatures() {
+        let extract = |language: &str, path: &str, code: &str| {
+            let tree = CodeParser::new().parse_source(language, code).unwrap();
+            SymbolExtractor::new().extract_symbols(&tree.root_node(), code, path)
+        };
+        let params = |symbol: &mcp_forge::analyzer::symbols::Symbol| -> Vec<(String, Option<String>, Option<String>, &'static str)> {
+            let callable = symbol.callable.as_ref().unwrap();
+            callable.parameters.iter().map(|p| (p.name.clone(), p.type_name.clone(), p.default.clone(), p.kind.as_str())).collect()
+        };
+        let param = |name: &str, ty: Option<&str>, default: Option<&str>, kind: &'static str| (name.to_string(), ty.map(str::to_string), default.map(str::to_string), kind);
+
+        let python = extract("python", "app/jobs.py", "class Queue:\n    async def push(self, job: Job, *args, retries: int = 3, **options) -> bool:\n        pass\n\n    @staticmethod\n    def drain(limit, *, force=False):\n        yield limit\n");
+        let push = python.iter().find(|s| s.name == "push").unwrap();
+        assert_eq!(push.signature.as_deref(), Some("async def push(self, job: Job, *args, retries: int = 3, **options) -> bool"));
+        assert_eq!(params(push), [
+            param("self", None, None, "receiver"),
+            param("job", Some("Job"), None, "positional"),
+            param("args", None, None, "variadic"),
+            param("retries", Some("int"), Some("3"), "keyword_only"),
+            param("options", None, None, "keywords"),
+        ]);
+        let callable = push.callable.as_ref().unwrap();
+        assert_eq!((callable.return_type.as_deref(), callable.modifiers.as_slice()), (Some("bool"), &["async".to_string()][..]));
+        let drain = python.iter().find(|s| s.name == "drain").unwrap();
+        assert_eq!(params(drain), [param("limit", None, None, "positional"), param("force", None, Some("False"), "keyword_only")]);
+        assert_eq!(drain.callable.as_ref().unwrap().modifiers, ["static", "generator"]);
+        assert!(python.iter().find(|s| s.name == "Queue").unwrap().callable.is_none());
+
+        let ts = extract("typescript", "web/store.ts", "class Store {\n    static async load<T extends Item>(key: string, limit?: number, retries = 3, ...tags: string[]): Promise<T> { return null; }\n}\n");
+        let load = ts.iter().find(|s| s.name.ends_with("load")).unwrap();
+        assert_eq!(params(load), [
+            param("key", Some("string"), None, "positional"),
+            param("limit", Some("number"), None, "optional"),
+            param("retries", None, Some("3"), "positional"),
+            param("tags", Some("string[]"), None, "variadic"),
+        ]);
+        let callable = load.callable.as_ref().unwrap();
+        assert_eq!(callable.return_type.as_deref(), Some("Promise<T>"));
+        assert_eq!(callable.type_parameters, ["T extends Item"]);
+        assert_eq!(callable.modifiers, ["static", "async"]);
+
+        let rust = extract("rust", "src/pool.rs", "struct Pool;\nimpl Pool {\n    pub const unsafe fn take<'a, T: Clone>(&'a mut self, count: usize) -> Option<&'a T> { None }\n}\n");
+        let take = rust.iter().find(|s| s.kind == "method").unwrap();
+        assert_eq!(take.signature.as_deref(), Some("pub const unsafe fn take<'a, T: Clone>(&'a mut self, count: usize) -> Option<&'a T>"));
+        assert_eq!(params(take), [param("&'a mut self", None, None, "receiver"), param("count", Some("usize"), None, "positional")]);
+        let callable = take.callable.as_ref().unwrap();
+        assert_eq!((callable.return_type.as_deref(), callable.type_parameters.as_slice()), (Some("Option<&'a T>"), &["'a".to_string(), "T: Clone".to_string()][..]));
+        assert_eq!(callable.modifiers, ["const", "unsafe"]);
+
+        let java = extract("java", "src/Log.java", "class Log {\n    public static synchronized int write(String[] lines, Object... args) { return 0; }\n}\n");
+        let write = java.iter().find(|s| s.kind == "method").unwrap();
+        assert_eq!(params(write), [param("lines", Some("String[]"), None, "positional"), param("args", Some("Object"), None, "variadic")]);
+        let callable = write.callable.as_ref().unwrap();
+        assert_eq!((callable.return_type.as_deref(), callable.modifiers.as_slice()), (Some("int"), &["static".to_string(), "synchronized".to_string()][..]));
+
+        let go = extract("go", "svc/server.go", "package svc\n\nfunc (s *Server) Send(to, from string, parts ...[]byte) (int, error) {\n    return 0, nil\n}\n");
+        let send = go.iter().find(|s| s.kind == "method").unwrap();
+        assert_eq!(params(send), [
+            param("s", Some("*Server"), None, "receiver"),
+            param("to", Some("string"), None, "positional"),
+            param("from", Some("string"), None, "positional"),
+            param("parts", Some("[]byte"), None, "variadic"),
+        ]);
+        assert_eq!(send.callable.as_ref().unwrap().return_type.as_deref(), Some("(int, error)"));
+    }
+
+    #[test]
+    fn test_python_support() {
+        let code = r#"from dataclasses import dataclass, field
+from typing import ClassVar
+from pydantic import BaseModel
+
+__all__ = ["User"]
+__all__ += ["create_user"]
+
+MAX_USERS: int = 100
+DEFAULT_ROLE = "member"
+cache = {}
+
+@dataclass(frozen=True)
+class User:
+    name: str
+    roles: list[str] = field(default_factory=list)
+    registry: ClassVar[dict] = {}
+    kind = "user"
+
+    def __init__(self, name: str):
+        self.name = name
+        self._token: str | None = None
+
+    @property
+    def display_name(self) -> str:
+        return self.name.title()
+
+class Settings(BaseModel):
+    debug: bool = False
+
+@app.get("/users/{user_id}")
+async def create_user(user_id: int):
+    pass
+
+def _helper():
+    pass
+"#;
+        let tree = CodeParser::new().parse_source("python", code).unwrap();
+        let symbols = SymbolExtractor::new().extract_symbols(&tree.root_node(), code, "app/users.py");
+        let find = |id: &str| symbols.iter().find(|s| s.id == format!("app/users.py::{}", id)).unwrap_or_else(|| panic!("no symbol {}", id));
+
+        let max = find("MAX_USERS");
+        assert_eq!((max.kind.as_str(), max.type_name.as_deref(), max.signature.as_deref()), ("constant", Some("int"), Some("MAX_USERS: int = 100")));
+        assert_eq!(find("DEFAULT_ROLE").kind, "constant");
+        assert!(symbols.iter().all(|s| s.name != "cache" && s.name != "__all__"));
+
+        let user = find("User");
+        assert_eq!(user.attributes, ["dataclass(frozen=True)"]);
+        assert_eq!(user.visibility.as_deref(), Some("public"));
+        let name = find("User.name");
+        assert_eq!((name.kind.as_str(), name.type_name.as_deref(), name.parent_id.as_deref()), ("field", Some("str"), Some("app/users.py::User")));
+        assert_eq!(find("User.roles").kind, "field");
+        assert_eq!(find("User.registry").kind, "attribute");
+        assert_eq!(find("User.kind").signature.as_deref(), Some("kind = \"user\""));
+
+        // `self.name` adds a second `name` to the class, not to `__init__`.
+        let token = find("User._token");
+        assert_eq!((token.kind.as_str(), token.type_name.as_deref(), token.visibility.as_deref()), ("attribute", Some("str | None"), Some("private")));
+        assert_eq!(token.qualified_name, "app.users.User._token");
+        assert_eq!(find("User.name~2").signature.as_deref(), Some("self.name = name"));
+        assert_eq!(find("User.__init__").visibility.as_deref(), Some("public"));
+
+        let display = find("User.display_name");
+        assert_eq!((display.kind.as_str(), display.attributes.as_slice()), ("property", &["property".to_string()][..]));
+        assert_eq!(find("Settings.debug").kind, "field");
+
+        let route = find("create_user");
+        assert_eq!((route.kind.as_str(), route.attributes.as_slice()), ("route", &["app.get(\"/users/{user_id}\")".to_string()][..]));
+        assert_eq!(route.visibility.as_deref(), Some("public"));
+        assert_eq!(find("_helper").visibility.as_deref(), Some("private"));
+        assert_eq!(find("MAX_USERS").visibility.as_deref(), Some("private"));
+        assert_eq!(find("Settings").visibility.as_deref(), Some("private"));
+        assert!(symbols.iter().filter(|s| s.kind == "import").all(|s| s.visibility.is_none()));
+    }
+
+    #[test]
+    fn test_tag_queries() {
+        let code = "class Shop:\n    \"\"\"Sells things.\"\"\"\n\n    def checkout(self):\n        pass\n\nroute(\"/cart\", show_cart)\n";
+        let mut parser = CodeParser::new();
+        let tree = parser.parse_source("python", code).unwrap();
+        let symbols = SymbolExtractor::new().extract_symbols(&tree.root_node(), code, "app/shop.py");
+        assert_eq!(symbols[0].documentation.as_deref(), Some("Sells things."));
+
+        let dir = tempfile::tempdir().unwrap();
+        let query = dir.path().join("python.scm");
+        std::fs::write(&query, "(call function: (identifier) @fn (#eq? @fn \"route\") arguments: (argument_list . (string) @name)) @definition.route\n").unwrap();
+        let mut indexing = IndexingConfig::default();
+        indexing.queries.insert("python".to_string(), QueryOverride { path: query.clone(), replace: false });
+        let extractor = SymbolExtractor::with_queries(TagQueries::for_indexing(&indexing).unwrap());
+        let kinds: Vec<(String, String)> = extractor
+            .extract_symbols(&tree.root_node(), code, "app/shop.py")
+            .into_iter()
+            .map(|s| (s.kind, s.name))
+            .collect();
+        assert_eq!(kinds, [
+            ("class".to_string(), "Shop".to_string()),
+            ("function".to_string(), "checkout".to_string()),
+            ("route".to_string(), "/cart".to_string()),
+        ]);
+
+        indexing.queries.get_mut("python").unwrap().replace = true;
+        let extractor = SymbolExtractor::with_queries(TagQueries::for_indexing(&indexing).unwrap());
+        assert_eq!(extractor.extract_symbols(&tree.root_node(), code, "app/shop.py").len(), 1);
+
+        std::fs::write(&query, "(call @definition.route").unwrap();
+        let error = TagQueries::for_indexing(&indexing).err().unwrap().to_string();
+        assert!(error.contains("indexing.queries.python"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let mut parser = CodeParser::new();
+        let clean = parser.parse("python", "def ok():\n    return 1\n").unwrap();
+        assert!(clean.diagnostics.is_clean());
+
+        let result = parser.parse("javascript", "function ok() {\n  return 1;\n}\nlet x = (1 + ;\n").unwrap();
+        let diagnostics = &result.diagnostics;
+        assert!(result.tree.is_some());
+        assert!(diagnostics.error_count >= 1);
+        assert_eq!(diagnostics.errors[0].start_line, 4);
+        assert!(diagnostics.errors.iter().all(|e| matches!(e.kind, SyntaxErrorKind::Error | SyntaxErrorKind::Missing)));
+        assert!(diagnostics.error_ratio() > 0.0 && diagnostics.error_ratio() < 0.5);
+        assert!(diagnostics.skip_reason(0.5).is_none());
+        assert!(diagnostics.skip_reason(0.0).unwrap().contains("syntax errors"));
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("good.py"), "def ok():\n    return 1\n").unwrap();
+        std::fs::write(dir.path().join("partial.py"), "def ok():\n    return 1\n\ndef broken(:\n").unwrap();
+        std::fs::write(dir.path().join("garbage.py"), "}}} ))) ]]] }}} )))\n").unwrap();
+        let report = analyze(dir.path());
+        let health = &report.parse_health;
+        assert_eq!((health.clean_files, health.partial_files, health.skipped_files), (1, 1, 1));
+        assert_eq!(report.files_analyzed, 2);
+        assert_eq!(health.files[0].file, "garbage.py");
+        assert!(health.files[0].skip_reason.is_some());
+        assert_eq!(health.files[1].file, "partial.py");
+        assert!(report.render(ReportFormat::Table).contains("Parse health: 1 clean, 1 partial, 1 skipped"));
+    }
+
+    #[test]
+    fn test_embedded_regions() {
+        let markdown = "# Guide\n\n```text\nnot code\n```\n\n```python\ndef connect(url):\n    return url\n```\n\n  ~~~ts\n  function open() {}\n  ~~~\n";
+        let found = regions(Path::new("docs/guide.md"), markdown);
+        let summary: Vec<_> = found.iter().map(|r| (r.language, r.host.kind, r.host.index, r.host.line_offset)).collect();
+        assert_eq!(summary, [("python", RegionKind::Block, 1, 7), ("typescript", RegionKind::Block, 2, 12)]);
+        assert_eq!(found[1].source, "function open() {}\n");
+
+        let component = "<template>\n  <div/>\n</template>\n<script src=\"./x.js\"></script>\n<script setup lang=\"ts\">\nfunction save(): void {}\n</script>\n";
+        let found = regions(Path::new("src/Form.vue"), component);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].language, found[0].host.index, found[0].host.line_offset), ("typescript", 1, 4));
+
+        let notebook = r##"{
+ "cells": [
+  {"cell_type": "markdown", "source": ["# Notes"]},
+  {"cell_type": "code", "source": [
+    "%matplotlib inline\n",
+    "def plot(data):\n",
+    "    return data\n"
+  ]}
+ ],
+ "metadata": {"kernelspec": {"language": "python"}}
+}"##;
+        let found = regions(Path::new("analysis.ipynb"), notebook);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].host.kind, found[0].host.index, found[0].host.line_offset), (RegionKind::Cell, 1, 4));
+        assert!(found[0].source.starts_with("# %matplotlib"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(docs.join("guide.md"), markdown).unwrap();
+        std::fs::write(dir.path().join("analysis.ipynb"), notebook).unwrap();
+        let pool = ParserPool::new(&IndexingConfig::default());
+        let extractor = SymbolExtractor::new();
+        let parsed = pool.parse_embedded(&docs.join("guide.md")).unwrap();
+        let symbols: Vec<_> = parsed.iter().flat_map(|p| extractor.extract_parsed(p, "docs/guide.md")).collect();
+        let connect = symbols.iter().find(|s| s.name == "connect").unwrap();
+        assert_eq!(connect.id, "docs/guide.md#1::connect");
+        assert_eq!((connect.file_path.as_str(), connect.start_line, connect.end_line), ("docs/guide.md", 8, 9));
+        assert!(symbols.iter().any(|s| s.id == "docs/guide.md#2::open" && s.start_line == 13));
+
+        let parsed = pool.parse_embedded(&dir.path().join("analysis.ipynb")).unwrap();
+        let plot = extractor.extract_parsed(&parsed[0], "analysis.ipynb").into_iter().find(|s| s.name == "plot").unwrap();
+        assert_eq!((plot.start_line, plot.region.unwrap().kind), (6, RegionKind::Cell));
+
+        let report = analyze(dir.path());
+        assert_eq!(report.files_analyzed, 2);
+        assert!(report.languages.contains_key("markdown") && report.languages.contains_key("jupyter"));
+    }
+
+    #[test]
+    fn test_skipped_content() {
+        assert!(is_binary(b"\x7fELF\x02\x01\x01\x00\x00"));
+        assert!(!is_binary("def ok():\n\treturn 1\n".as_bytes()));
+        assert_eq!(decode(b"\xEF\xBB\xBFx = 1\n").encoding, Encoding::Utf8Bom);
+        let utf16 = decode(&[0xFF, 0xFE, b'x', 0, b' ', 0, b'=', 0, b' ', 0, b'1', 0]);
+        assert_eq!((utf16.text.as_str(), utf16.encoding), ("x = 1", Encoding::Utf16Le));
+        let latin1 = decode(b"name = 'caf\xE9'\n");
+        assert_eq!(latin1.encoding, Encoding::Lossy);
+        assert!(latin1.text.contains('\u{FFFD}'));
+
+        assert!(generated_marker(Path::new("api.pb.go"), "package api\n").is_some());
+        assert!(generated_marker(Path::new("gen.py"), "# Code generated by tool. DO NOT EDIT.\nx = 1\n").is_some());
+        assert!(generated_marker(Path::new("lint.py"), "MARKER = \"@generated\"\n").is_none());
+        let bundle: String = (0..400).map(|i| format!("var a{0}=function(b{0}){{return b{0}*{0}}};", i)).collect();
+        assert!(minified(Path::new("bundle.js"), &bundle).is_some());
+        assert!(minified(Path::new("rule.js"), &format!("// {}\nlet x = 1;\n", "-".repeat(2000))).is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.py"), "def main():\n    return 1\n").unwrap();
+        std::fs::write(dir.path().join("legacy.py"), b"def greet():\n    return 'caf\xE9'\n").unwrap();
+        std::fs::write(dir.path().join("model_pb2.py"), "class Model:\n    pass\n").unwrap();
+        std::fs::write(dir.path().join("bundle.js"), &bundle).unwrap();
+        std::fs::write(dir.path().join("blob.py"), b"\x00\x01\x02\x03").unwrap();
+        std::fs::write(dir.path().join("huge.py"), "x = 1\n".repeat(10_000)).unwrap();
+        let indexing = IndexingConfig { max_file_size: ByteSize(32 * 1024), ..Default::default() };
+        let discovery = discover(dir.path(), &indexing);
+        assert_eq!(discovery.skipped.len(), 1);
+        assert!(matches!(discovery.skipped[0].1, SkipReason::TooLarge { limit: 32768, .. }));
+
+        let parsed = ParserPool::new(&indexing).parse_files(&discovery.files);
+        let kinds: Vec<_> = parsed
+            .iter()
+            .map(|(path, parsed)| (path.file_name().unwrap().to_str().unwrap(), parsed.as_ref().map(|p| p.encoding).map_err(SkipReason::kind)))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("app.py", Ok(Encoding::Utf8)),
+                ("blob.py", Err("binary")),
+                ("bundle.js", Err("minified")),
+                ("legacy.py", Ok(Encoding::Lossy)),
+                ("model_pb2.py", Err("generated")),
+            ]
+        );
+
+        let report = analyze(dir.path());
+        let skipped: Vec<_> = report.skipped_files.iter().map(|f| (f.file.as_str(), f.kind)).collect();
+        assert_eq!(skipped, [("blob.py", "binary"), ("bundle.js", "minified"), ("model_pb2.py", "generated")]);
+        assert_eq!(report.files_analyzed, 3);
+        assert!(report.render(ReportFormat::Table).contains("Skipped files"));
+    }
+
+    /// A project of `count` small files spread over modules and languages.
+    fn generated_project(count: usize) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = [
+            ("py", "class Service{0}:\n    def run(self, n):\n        return helper{0}(n) + 1\n\n\ndef helper{0}(n):\n    return n * 2\n"),
+            ("ts", "export class Store{0} {{\n  get(id: number): number {{\n    return load{0}(id);\n  }}\n}}\n\nfunction load{0}(id: number) {{\n  return id;\n}}\n"),
+            ("rs", "pub struct Cache{0} {{\n    size: usize,\n}}\n\nimpl Cache{0} {{\n    pub fn len(&self) -> usize {{\n        self.size\n    }}\n}}\n"),
+            ("java", "public class Repo{0} {{\n    public int find(int id) {{\n        return id + {0};\n    }}\n}}\n"),
+            ("go", "package m{0}\n\ntype Worker{0} struct {{}}\n\nfunc (w Worker{0}) Do() int {{\n\treturn {0}\n}}\n"),
+        ];
+        for i in 0..count {
+            let (ext, template) = sources[i % sources.len()];
+            let module = dir.path().join(format!("module{}", i % 20));
+            std::fs::create_dir_all(&module).unwrap();
+            std::fs::write(module.join(format!("file{}.{}", i, ext)), template.replace("{0}", &i.to_string()).replace("{{", "{").replace("}}", "}")).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_parser_pool() {
+        let dir = generated_project(60);
+        let mut files = discover_files(dir.path());
+        files.reverse();
+        let pool = ParserPool::new(&IndexingConfig::default());
+        let first = pool.parse_files(&files);
+        let paths: Vec<_> = first.iter().map(|(path, _)| path.clone()).collect();
+        files.sort();
+        assert_eq!(paths, files);
+
+        let second = pool.parse_files(&files);
+        let mut serial = CodeParser::new();
+        for ((_, a), (path, b)) in first.iter().zip(&second) {
+            let (a, b) = (a.as_ref().unwrap(), b.as_ref().unwrap());
+            assert_eq!(a.hash, b.hash);
+            let tree = serial.parse_file(path).unwrap();
+            assert_eq!(a.tree.as_ref().unwrap().root_node().to_sexp(), tree.root_node().to_sexp());
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark; run with `cargo test --release --test analyzer_tests -- --ignored --nocapture`"]
+    fn bench_parse_10k_files() {
+        const FILES: usize = 10_000;
+        let dir = generated_project(FILES);
+        let started = std::time::Instant::now();
+        let files = discover_files_with(dir.path(), &IndexingConfig::default());
+        let parsed = ParserPool::new(&IndexingConfig::default()).parse_files(&files);
+        let elapsed = started.elapsed();
+        assert_eq!(parsed.iter().filter(|(_, parsed)| parsed.is_ok()).count(), FILES);
+        println!(
+            "Parsed {} files in {:?} ({:.0} files/s) on {} threads",
+            FILES,
+            elapsed,
+            FILES as f64 / elapsed.as_secs_f64(),
+            rayon::current_num_threads()
+        );
+        assert!(elapsed < std::time::Duration::from_secs(30), "parsing {} files took {:?}", FILES, elapsed);
+    }
 }
@@ -0,0 +1,255 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use mcp_forge::config::ForgeConfig;
+    use mcp_forge::database::{self, queries};
+    use mcp_forge::server::builder::build;
+
+    fn write(root: &Path, file: &str, content: &str) {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Build `files` into a fresh output directory, without the embedding backend.
+    async fn build_fixture(files: &[(&str, &str)]) -> (tempfile::TempDir, tempfile::TempDir) {
+        let source = tempfile::tempdir().unwrap();
+        for (file, content) in files {
+            write(source.path(), file, content);
+        }
+        let output = tempfile::tempdir().unwrap();
+        let mut config = ForgeConfig::default();
+        config.project.name = "Shop".to_string();
+        config.analysis.embeddings = false;
+        config.analysis.complexity_threshold = 7;
+        build(source.path().to_path_buf(), output.path().to_path_buf(), &config).await.unwrap();
+        (source, output)
+    }
+
+    #[tokio::test]
+    async fn test_build_writes_the_index() {
+        let (_source, output) = build_fixture(&[
+            ("app/db.py", "def query(sql):\n    return sql\n"),
+            (
+                "app/service.py",
+                "from app.db import query\n\n\ndef load(user_id):\n    return query(user_id)\n\n\nclass UserService:\n    def get_user(self, user_id):\n        return load(user_id)\n\n\nclass AdminService:\n    def get_user(self, user_id):\n        return load(user_id)\n\n\ndef create_service():\n    return UserService()\n",
+            ),
+        ])
+        .await;
+        assert!(output.path().join("server.py").is_file());
+        assert_eq!(database::project_name(output.path()), "Shop");
+        assert_eq!(database::complexity_threshold(output.path()), 7);
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let load = queries::get_symbol(&pool, "app/service.py::load").await.unwrap().unwrap();
+        assert_eq!(load.kind, "function");
+        assert_eq!(load.file_path, "app/service.py");
+        assert_eq!(load.start_line, Some(4));
+        assert!(queries::get_symbol(&pool, "app/db.py::query").await.unwrap().is_some());
+        // Methods of the same name are told apart by their class.
+        assert!(queries::get_symbol(&pool, "app/service.py::UserService.get_user").await.unwrap().is_some());
+        assert!(queries::get_symbol(&pool, "app/service.py::AdminService.get_user").await.unwrap().is_some());
+        let parameters = queries::get_parameters(&pool, "app/service.py::UserService.get_user").await.unwrap();
+        assert_eq!(parameters.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["self", "user_id"]);
+        assert_eq!(parameters[0].kind, "receiver");
+        assert_eq!(parameters[1].kind, "positional");
+
+        let calls = queries::get_relationships(&pool, "app/service.py::UserService.get_user").await.unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].to_symbol_id.as_deref(), Some("app/service.py::load"));
+        assert_eq!(calls[0].kind.as_deref(), Some("calls"));
+        // `query` is resolved within service.py, where it is not defined.
+        assert!(queries::get_relationships(&pool, "app/service.py::load").await.unwrap().is_empty());
+
+        let patterns = queries::get_patterns(&pool).await.unwrap();
+        let factory = patterns.iter().find(|p| p.name.as_deref() == Some("Factory")).unwrap();
+        assert_eq!(factory.pattern_type.as_deref(), Some("creational"));
+        assert_eq!(factory.locations.as_deref(), Some("[\"app/service.py\"]"));
+        assert!(queries::get_all_embeddings(&pool).await.unwrap().is_empty());
+
+        let files = queries::get_files(&pool).await.unwrap();
+        assert_eq!(files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), ["app/db.py", "app/service.py"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_records_skipped_files() {
+        let (_source, output) = build_fixture(&[
+            ("app/db.py", "def query(sql):\n    return sql\n"),
+            ("app/models_pb2.py", "# @generated by protoc. DO NOT EDIT!\ndef descriptor():\n    pass\n"),
+            ("app/blob.py", "\0\0\0\0\x01\x02"),
+            ("app/bom.py", "\u{feff}def ping():\n    pass\n"),
+        ])
+        .await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let files = queries::get_files(&pool).await.unwrap();
+        let generated = files.iter().find(|f| f.path == "app/models_pb2.py").unwrap();
+        assert_eq!(generated.skip_kind.as_deref(), Some("generated"));
+        assert!(generated.skip_reason.is_some());
+        assert_eq!(generated.language.as_deref(), Some("python"));
+        assert!(queries::get_symbol(&pool, "app/models_pb2.py::descriptor").await.unwrap().is_none());
+        let binary = files.iter().find(|f| f.path == "app/blob.py").unwrap();
+        assert_eq!(binary.skip_kind.as_deref(), Some("binary"));
+        let indexed = files.iter().find(|f| f.path == "app/db.py").unwrap();
+        assert_eq!(indexed.skip_reason, None);
+        assert_eq!(indexed.skip_kind, None);
+        assert_eq!(indexed.encoding.as_deref(), Some("utf-8"));
+        let bom = files.iter().find(|f| f.path == "app/bom.py").unwrap();
+        assert_eq!(bom.skip_reason, None);
+        assert_eq!(bom.encoding.as_deref(), Some("utf-8-bom"));
+        assert!(queries::get_symbol(&pool, "app/bom.py::ping").await.unwrap().is_some());
+
+        let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(output.path().join("build-report.json")).unwrap()).unwrap();
+        let skipped = report["skipped_files"].as_array().unwrap();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0]["file"], "app/blob.py");
+        assert_eq!(skipped[0]["kind"], "binary");
+        assert_eq!(skipped[1]["file"], "app/models_pb2.py");
+    }
+
+    #[tokio::test]
+    async fn test_build_records_parse_diagnostics() {
+        let broken = "def ok():\n    return 1\n\n\ndef broken(:\n    return 2\n\n\ndef also_ok():\n    return 3\n\n\ndef fine():\n    return 4\n";
+        let (_source, output) = build_fixture(&[("app/db.py", "def query(sql):\n    return sql\n"), ("app/broken.py", broken)]).await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let files = queries::get_files(&pool).await.unwrap();
+        let file = files.iter().find(|f| f.path == "app/broken.py").unwrap();
+        assert_eq!(file.skip_reason, None);
+        assert_eq!(file.lines, Some(14));
+        assert!(file.error_count > 0, "{:?}", file);
+        let diagnostics = queries::get_parse_diagnostics(&pool, "app/broken.py").await.unwrap();
+        assert_eq!(diagnostics.len() as i64, file.error_count);
+        assert!(diagnostics.iter().all(|d| d.start_line == Some(5)), "{:?}", diagnostics);
+        assert!(queries::get_parse_diagnostics(&pool, "app/db.py").await.unwrap().is_empty());
+        // Definitions around the error are still indexed.
+        assert!(queries::get_symbol(&pool, "app/broken.py::also_ok").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_build_stores_qualified_names() {
+        let (_source, output) = build_fixture(&[
+            ("app/cache.py", "class Local:\n    def get(self, key):\n        return key\n\n\nclass Remote:\n    def get(self, key):\n        return key\n"),
+            ("src/db.rs", "pub fn connect() {}\n"),
+        ])
+        .await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let local = queries::get_symbol(&pool, "app/cache.py::Local").await.unwrap().unwrap();
+        assert_eq!(local.qualified_name.as_deref(), Some("app.cache.Local"));
+        assert_eq!(local.parent_id, None);
+        let local_get = queries::get_symbol(&pool, "app/cache.py::Local.get").await.unwrap().unwrap();
+        assert_eq!(local_get.qualified_name.as_deref(), Some("app.cache.Local.get"));
+        assert_eq!(local_get.parent_id.as_deref(), Some("app/cache.py::Local"));
+        // The scope is the module, whatever encloses the symbol inside it.
+        assert_eq!(local_get.scope.as_deref(), Some("app.cache"));
+        let remote_get = queries::get_symbol(&pool, "app/cache.py::Remote.get").await.unwrap().unwrap();
+        assert_eq!(remote_get.qualified_name.as_deref(), Some("app.cache.Remote.get"));
+        assert_eq!(remote_get.parent_id.as_deref(), Some("app/cache.py::Remote"));
+        // Rust items are qualified by their module path.
+        let connect = queries::get_symbol(&pool, "src/db.rs::connect").await.unwrap().unwrap();
+        assert_eq!(connect.qualified_name.as_deref(), Some("crate::db::connect"));
+        assert_eq!(connect.parent_id, None);
+        assert_eq!(connect.scope.as_deref(), Some("crate::db"));
+        let outline = queries::get_child_symbols(&pool, "app/cache.py::Remote").await.unwrap();
+        assert_eq!(outline.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), ["app/cache.py::Remote.get"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_stores_rust_items() {
+        let db = "#[derive(Debug, Clone)]\npub struct Pool {\n    pub(crate) size: usize,\n}\n\nimpl Pool {\n    pub fn get(&self) {}\n}\n\npub(crate) const MAX: usize = 8;\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn gets() {}\n}\n";
+        let (_source, output) = build_fixture(&[("src/db.rs", db)]).await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let pool_struct = queries::get_symbol(&pool, "src/db.rs::Pool").await.unwrap().unwrap();
+        assert_eq!(pool_struct.kind, "struct");
+        assert_eq!(pool_struct.visibility.as_deref(), Some("pub"));
+        assert_eq!(pool_struct.attributes.as_deref(), Some(r#"["derive(Debug, Clone)"]"#));
+        let size = queries::get_symbol(&pool, "src/db.rs::Pool.size").await.unwrap().unwrap();
+        assert_eq!(size.kind, "field");
+        assert_eq!(size.visibility.as_deref(), Some("pub(crate)"));
+        assert_eq!(size.attributes, None);
+        // Methods of an `impl` block belong to its type.
+        let get = queries::get_symbol(&pool, "src/db.rs::Pool.get").await.unwrap().unwrap();
+        assert_eq!(get.qualified_name.as_deref(), Some("crate::db::Pool::get"));
+        assert_eq!(get.parent_id.as_deref(), Some("src/db.rs::Pool"));
+        let max = queries::get_symbol(&pool, "src/db.rs::MAX").await.unwrap().unwrap();
+        assert_eq!(max.kind, "constant");
+        assert_eq!(max.visibility.as_deref(), Some("pub(crate)"));
+        let tests = queries::get_symbol(&pool, "src/db.rs::tests").await.unwrap().unwrap();
+        assert_eq!(tests.kind, "module");
+        assert_eq!(tests.visibility, None);
+        assert_eq!(tests.attributes.as_deref(), Some(r#"["cfg(test)"]"#));
+        let gets = queries::get_symbol(&pool, "src/db.rs::tests.gets").await.unwrap().unwrap();
+        assert_eq!(gets.attributes.as_deref(), Some(r#"["test"]"#));
+    }
+
+    #[tokio::test]
+    async fn test_build_stores_python_members() {
+        let models = "from dataclasses import dataclass\n\n__all__ = [\"User\"]\n\nLIMIT: int = 10\n\n\n@dataclass\nclass User:\n    age: int = 0\n\n    def __init__(self, name):\n        self._cache = {}\n\n    @property\n    def label(self) -> str:\n        return str(self.age)\n\n    @staticmethod\n    def make():\n        return User(\"x\")\n";
+        let (_source, output) = build_fixture(&[("app/models.py", models)]).await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        // `__all__` leaves it out of the exported surface.
+        let limit = queries::get_symbol(&pool, "app/models.py::LIMIT").await.unwrap().unwrap();
+        assert_eq!(limit.kind, "constant");
+        assert_eq!(limit.visibility.as_deref(), Some("private"));
+        assert_eq!(limit.type_name.as_deref(), Some("int"));
+        let user = queries::get_symbol(&pool, "app/models.py::User").await.unwrap().unwrap();
+        assert_eq!(user.visibility.as_deref(), Some("public"));
+        assert_eq!(user.attributes.as_deref(), Some(r#"["dataclass"]"#));
+        assert_eq!(user.type_name, None);
+        let age = queries::get_symbol(&pool, "app/models.py::User.age").await.unwrap().unwrap();
+        assert_eq!(age.kind, "field");
+        assert_eq!(age.type_name.as_deref(), Some("int"));
+        let cache = queries::get_symbol(&pool, "app/models.py::User._cache").await.unwrap().unwrap();
+        assert_eq!(cache.kind, "attribute");
+        assert_eq!(cache.visibility.as_deref(), Some("private"));
+        let label = queries::get_symbol(&pool, "app/models.py::User.label").await.unwrap().unwrap();
+        assert_eq!(label.kind, "property");
+        assert_eq!(label.attributes.as_deref(), Some(r#"["property"]"#));
+        assert_eq!(label.return_type.as_deref(), Some("str"));
+        let make = queries::get_symbol(&pool, "app/models.py::User.make").await.unwrap().unwrap();
+        assert_eq!(make.kind, "function");
+        assert_eq!(make.attributes.as_deref(), Some(r#"["staticmethod"]"#));
+    }
+
+    #[tokio::test]
+    async fn test_build_stores_embedded_regions() {
+        let guide = "# Guide\n\n```python\ndef connect(url):\n    return url\n```\n\nText.\n\n```python\ndef connect(url, retries):\n    return url\n```\n";
+        let (_source, output) = build_fixture(&[("docs/guide.md", guide)]).await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let first = queries::get_symbol(&pool, "docs/guide.md#0::connect").await.unwrap().unwrap();
+        assert_eq!(first.file_path, "docs/guide.md");
+        assert_eq!(first.region_kind.as_deref(), Some("block"));
+        assert_eq!(first.region_index, Some(0));
+        assert_eq!(first.start_line, Some(4));
+        // The second block redefines `connect` on the host file's line 11.
+        let second = queries::get_symbol(&pool, "docs/guide.md#1::connect").await.unwrap().unwrap();
+        assert_eq!(second.region_index, Some(1));
+        assert_eq!(second.start_line, Some(11));
+        let files = queries::get_files(&pool).await.unwrap();
+        assert_eq!(files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), ["docs/guide.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_stores_doc_tags() {
+        let (_source, output) = build_fixture(&[
+            ("src/db.rs", "/// Get a connection.\n///\n/// # Errors\n///\n/// Fails when the pool is closed.\npub fn connect() {}\n"),
+            ("app/users.py", "def label(user):\n    \"\"\"Display label.\n\n    Returns:\n        str: The label.\n    \"\"\"\n    return user\n\n\ndef plain(user):\n    return user\n"),
+        ])
+        .await;
+
+        let pool = database::open_index(output.path()).await.unwrap();
+        let connect = queries::get_symbol(&pool, "src/db.rs::connect").await.unwrap().unwrap();
+        assert!(connect.documentation.is_some());
+        assert_eq!(connect.doc_tags.as_deref(), Some(r#"{"summary":"Get a connection.","sections":{"errors":"Fails when the pool is closed."}}"#));
+        let label = queries::get_symbol(&pool, "app/users.py::label").await.unwrap().unwrap();
+        assert_eq!(label.doc_tags.as_deref(), Some(r#"{"summary":"Display label.","returns":{"type":"str","description":"The label."}}"#));
+        // Undocumented symbols have no doc tags.
+        let plain = queries::get_symbol(&pool, "app/users.py::plain").await.unwrap().unwrap();
+        assert_eq!(plain.doc_tags, None);
+    }
+}
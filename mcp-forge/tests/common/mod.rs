@@ -0,0 +1,31 @@
+use mcp_forge::analyzer::docs;
+use mcp_forge::analyzer::signature::{Parameter, ParameterKind, Signature};
+use mcp_forge::database::queries::FileRecord;
+use mcp_forge::database::{queries, schema};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+/// Build output directory with a small, known index.
+pub async fn seeded_output_dir() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("database.sqlite"))
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await.unwrap();
+    schema::create_schema(&pool).await.unwrap();
+    queries::insert_symbol(&pool, "backend/service.py::get_user", "get_user", "function", "backend/service.py", 10, 20, "backend", Some("Fetch a user by id.\n\nArgs:\n    user_id (int): Id of the user."), None).await.unwrap();
+    let doc_tags = docs::parse("Fetch a user by id.\n\nArgs:\n    user_id (int): Id of the user.").unwrap();
+    queries::set_symbol_doc_tags(&pool, "backend/service.py::get_user", &doc_tags).await.unwrap();
+    queries::set_symbol_scope(&pool, "backend/service.py::get_user", "backend.service.get_user", None).await.unwrap();
+    let callable = Signature { parameters: vec![Parameter::new("user_id", ParameterKind::Positional).typed(Some("int".to_string()))], return_type: Some("User".to_string()), ..Signature::default() };
+    queries::set_symbol_callable(&pool, "backend/service.py::get_user", &callable).await.unwrap();
+    queries::insert_symbol(&pool, "backend/db.py::query", "query", "function", "backend/db.py", 1, 5, "backend", None, None).await.unwrap();
+    queries::insert_symbol(&pool, "tests/test_service.py::test_get_user", "test_get_user", "function", "tests/test_service.py", 1, 4, "tests", None, None).await.unwrap();
+    for path in ["backend/service.py", "backend/db.py", "tests/test_service.py"] {
+        queries::insert_file(&pool, &FileRecord { path: path.to_string(), language: Some("python".to_string()), ..FileRecord::default() }).await.unwrap();
+    }
+    queries::insert_relationship(&pool, "backend/service.py::get_user", "backend/db.py::query", "calls", 1.0).await.unwrap();
+    queries::insert_pattern(&pool, "Singleton", "creational", 1, "[\"backend/db.py\"]").await.unwrap();
+    pool.close().await;
+    std::fs::write(dir.path().join("config.json"), r#"{"project":{"name":"Demo"}}"#).unwrap();
+    dir
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use mcp_forge::analyzer::discover_files_with;
+    use mcp_forge::config::{ByteSize, ConfigOverrides, ConfigSource, EnvOverrides, ForgeConfig};
+
+    #[test]
+    fn test_parse_yaml_config() {
+        let config = ForgeConfig::from_yaml(
+            "project:\n  name: Shop\nindexing:\n  exclude_patterns: [\"vendor/\"]\n  max_file_size: 512KB\n  languages: [python]\nanalysis:\n  embeddings: false\nserver:\n  port: 4000\n",
+        )
+        .unwrap();
+        assert_eq!(config.project.name, "Shop");
+        assert_eq!(config.project.description, "AI expert for my codebase");
+        assert_eq!(config.indexing.max_file_size, ByteSize(512 * 1024));
+        assert!(config.indexing.includes_language("python"));
+        assert!(!config.indexing.includes_language("rust"));
+        assert!(config.analysis.patterns);
+        assert!(!config.analysis.embeddings);
+        assert_eq!(config.server.port, 4000);
+
+        assert_eq!(ForgeConfig::from_yaml("").unwrap(), ForgeConfig::default());
+        assert!(ForgeConfig::from_yaml("project:\n  nmae: Typo\n").is_err());
+        assert!(ForgeConfig::from_yaml("indexing:\n  max_file_size: 5XB\n").is_err());
+    }
+
+    #[test]
+    fn test_byte_size() {
+        assert_eq!(ByteSize::parse("5MB").unwrap(), ByteSize(5 * 1024 * 1024));
+        assert_eq!(ByteSize::parse("1.5 kb").unwrap(), ByteSize(1536));
+        assert_eq!(ByteSize::parse("42").unwrap(), ByteSize(42));
+        assert!(ByteSize::parse("-1MB").is_err());
+        assert_eq!(ByteSize(5 * 1024 * 1024).to_string(), "5MB");
+        assert_eq!(ByteSize(1000).to_string(), "1000B");
+    }
+
+    #[test]
+    fn test_validation_reports_every_problem() {
+        let mut config = ForgeConfig::default();
+        config.project.name = " ".to_string();
+        config.indexing.languages = vec!["cobol".to_string()];
+        config.server.port = 0;
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("project.name"));
+        assert!(message.contains("\"cobol\""));
+        assert!(message.contains("server.port"));
+    }
+
+    #[test]
+    fn test_env_and_cli_precedence() {
+        let mut config = ForgeConfig::from_yaml("project:\n  name: FromFile\nserver:\n  port: 4000\n").unwrap();
+        let env: HashMap<&str, &str> = [
+            ("MCP_FORGE_PROJECT_NAME", "FromEnv"),
+            ("MCP_FORGE_PORT", "5000"),
+            ("MCP_FORGE_EXCLUDE_PATTERNS", "dist/, *.min.js"),
+            ("MCP_FORGE_EMBEDDINGS", "false"),
+        ]
+        .into_iter()
+        .collect();
+        EnvOverrides::new(|key: &str| env.get(key).map(|v| v.to_string())).apply(&mut config).unwrap();
+        assert_eq!(config.project.name, "FromEnv");
+        assert_eq!(config.server.port, 5000);
+        assert_eq!(config.indexing.exclude_patterns, vec!["dist/", "*.min.js"]);
+        assert!(!config.analysis.embeddings);
+
+        let overrides = ConfigOverrides { project_name: Some("FromCli".to_string()), port: Some(6000), ..Default::default() };
+        overrides.apply(&mut config).unwrap();
+        assert_eq!(config.project.name, "FromCli");
+        assert_eq!(config.server.port, 6000);
+
+        let bad_port = EnvOverrides::new(|key: &str| (key == "MCP_FORGE_PORT").then(|| "http".to_string()));
+        assert!(bad_port.apply(&mut config).is_err());
+    }
+
+    #[test]
+    fn test_discovery_honours_indexing_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("vendor")).unwrap();
+        std::fs::write(dir.path().join("vendor/lib.py"), "x = 1\n").unwrap();
+        std::fs::write(dir.path().join("app.py"), "x = 1\n").unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("big.py"), "x = 1\n".repeat(100)).unwrap();
+        std::fs::write(dir.path().join("mcp-forge.yaml"), "indexing:\n  exclude_patterns: [\"vendor/\"]\n  max_file_size: 100\n  languages: [python]\n").unwrap();
+
+        let config = ForgeConfig::load(dir.path(), None, &ConfigOverrides::default()).unwrap();
+        let mut files: Vec<String> = discover_files_with(dir.path(), &config.indexing)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["app.py", "mcp-forge.yaml"]);
+    }
+}
@@ -0,0 +1,56 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::seeded_output_dir;
+    use mcp_forge::config::ConfigOverrides;
+    use mcp_forge::database::queries;
+    use mcp_forge::doctor::{CheckStatus, Doctor};
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+    fn doctor(source: &std::path::Path, output: &std::path::Path) -> Doctor {
+        // Keep the embedding backend out of it; it depends on the machine.
+        let overrides = ConfigOverrides { embeddings: Some(false), ..Default::default() };
+        Doctor { overrides, ..Doctor::new(source, output) }
+    }
+
+    #[tokio::test]
+    async fn test_doctor_reports_integrity_problems() {
+        let source = tempfile::tempdir().unwrap();
+        let dir = seeded_output_dir().await;
+
+        let report = doctor(source.path(), dir.path()).run().await;
+        assert!(report.is_healthy(), "{}", report.render_table());
+        assert_eq!(report.check("schema version").unwrap().status, CheckStatus::Ok);
+        assert_eq!(report.check("templates").unwrap().status, CheckStatus::Ok);
+
+        // Writers without foreign key enforcement (older builds, hand edits) can leave these behind.
+        let options = SqliteConnectOptions::new().filename(dir.path().join("database.sqlite")).foreign_keys(false);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        queries::insert_relationship(&pool, "backend/service.py::get_user", "backend/cache.py::lookup", "calls", 1.0).await.unwrap();
+        queries::insert_embedding(&pool, "backend/gone.py::old", vec![0u8; 4], "old", "test").await.unwrap();
+        sqlx::query("PRAGMA user_version = 0").execute(&pool).await.unwrap();
+        pool.close().await;
+
+        let report = doctor(source.path(), dir.path()).run().await;
+        assert!(!report.is_healthy());
+        let relationships = report.check("relationships").unwrap();
+        assert_eq!(relationships.status, CheckStatus::Fail);
+        assert!(relationships.detail.contains("backend/cache.py::lookup"));
+        assert!(relationships.fix.as_deref().unwrap().contains("mcp-forge build"));
+        assert_eq!(report.check("embeddings index").unwrap().status, CheckStatus::Fail);
+        assert_eq!(report.check("schema version").unwrap().status, CheckStatus::Fail);
+        assert!(report.render_table().contains("3 check(s) failed"));
+    }
+
+    #[tokio::test]
+    async fn test_doctor_without_index() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::write(source.path().join("mcp-forge.yaml"), "server:\n  template_dir: missing-templates\n").unwrap();
+        let report = doctor(source.path(), &source.path().join("mcp-server")).run().await;
+        assert_eq!(report.check("database").unwrap().status, CheckStatus::Warn);
+        assert_eq!(report.check("output").unwrap().status, CheckStatus::Ok);
+        assert_eq!(report.check("templates").unwrap().status, CheckStatus::Fail);
+        assert!(!report.is_healthy());
+    }
+}
@@ -0,0 +1,201 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::common::seeded_output_dir;
+    use mcp_forge::config::ForgeConfig;
+    use mcp_forge::database;
+    use mcp_forge::server::templates::{ServerTarget, TemplateSet, TemplateVars};
+    use mcp_forge::server::tools::{call_tool, KnowledgeTools, CALLABLE_KINDS};
+    use serde_json::{json, Value};
+
+    /// Generate `target` into a temp dir and return it with the main server source.
+    fn generate(target: ServerTarget) -> (tempfile::TempDir, String) {
+        let mut config = ForgeConfig::default();
+        config.project.name = "Demo Shop".to_string();
+        config.server.target = target;
+        let vars = TemplateVars::new(&config, vec!["python".to_string()]);
+        let dir = tempfile::tempdir().unwrap();
+        TemplateSet::for_config(&config).unwrap().write_to(dir.path(), &vars).unwrap();
+
+        let main = match target {
+            ServerTarget::Python => "server.py",
+            ServerTarget::Typescript => "src/index.ts",
+            ServerTarget::Rust => "src/main.rs",
+        };
+        let source = std::fs::read_to_string(dir.path().join(main)).unwrap();
+        (dir, source)
+    }
+
+    fn assert_files(dir: &Path, files: &[&str]) {
+        for file in files {
+            assert!(dir.join(file).is_file(), "missing {}", file);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join("start.sh")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    fn tool_names() -> Vec<String> {
+        KnowledgeTools.tool_definitions().iter().map(|t| t["name"].as_str().unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn test_generate_python_server() {
+        let (dir, source) = generate(ServerTarget::Python);
+        assert_files(dir.path(), &["server.py", "requirements.txt", "start.sh", "generate_embeddings.py"]);
+        assert!(source.contains("FastMCP(\"Demo Shop_Expert\")"));
+        assert!(!source.contains("{{"));
+        for tool in tool_names() {
+            assert!(source.contains(&format!("@mcp.tool()\ndef {}(", tool)), "python target lacks {}", tool);
+        }
+
+        if has_python() {
+            let status = std::process::Command::new("python3")
+                .args(["-c", "import ast, sys; ast.parse(open(sys.argv[1]).read())"])
+                .arg(dir.path().join("server.py"))
+                .status()
+                .unwrap();
+            assert!(status.success(), "generated server.py is not valid Python");
+        }
+    }
+
+    fn has_python() -> bool {
+        std::process::Command::new("python3").arg("--version").output().is_ok_and(|o| o.status.success())
+    }
+
+    /// Stand-in for the `mcp` package, enough to import the generated `server.py`.
+    const FASTMCP_STUB: &str = "class FastMCP:\n    def __init__(self, *args, **kwargs):\n        pass\n\n    def _register(self, *args, **kwargs):\n        return lambda fn: fn\n\n    tool = resource = prompt = _register\n\n    def add_resource(self, resource):\n        pass\n";
+
+    #[tokio::test]
+    async fn test_templates_return_parameters() {
+        let seeded = seeded_output_dir().await;
+        let pool = database::open_index(seeded.path()).await.unwrap();
+        let index = database::IndexInfo::load(seeded.path());
+        let native = call_tool(&pool, &index, "find_symbol", &json!({"name": "get_user"})).await.unwrap();
+        assert_eq!(native[0]["parameters"][0]["name"], "user_id");
+
+        let quoted: Vec<String> = CALLABLE_KINDS.iter().map(|kind| format!("\"{}\"", kind)).collect();
+        let (_ts_dir, typescript) = generate(ServerTarget::Typescript);
+        assert!(typescript.contains(&format!("const CALLABLE_KINDS = [{}];", quoted.join(", "))));
+        assert!(typescript.contains("withParameters(findSymbols(name, kind || null, 50))"));
+        assert!(typescript.contains("parameters: parametersOf(id)"));
+        let (dir, python) = generate(ServerTarget::Python);
+        assert!(python.contains(&format!("CALLABLE_KINDS = ({})", quoted.join(", "))));
+        if !has_python() {
+            return;
+        }
+
+        std::fs::copy(seeded.path().join("database.sqlite"), dir.path().join("database.sqlite")).unwrap();
+        std::fs::write(dir.path().join("config.json"), r#"{"analysis":{"complexity_threshold":3}}"#).unwrap();
+        let fastmcp = dir.path().join("stubs/mcp/server/fastmcp");
+        std::fs::create_dir_all(&fastmcp).unwrap();
+        for package in ["stubs/mcp", "stubs/mcp/server"] {
+            std::fs::write(dir.path().join(package).join("__init__.py"), "").unwrap();
+        }
+        std::fs::write(fastmcp.join("__init__.py"), FASTMCP_STUB).unwrap();
+        std::fs::write(fastmcp.join("resources.py"), "class FunctionResource:\n    def __init__(self, **kwargs):\n        pass\n").unwrap();
+        let output = std::process::Command::new("python3")
+            .args(["-c", "import json, server; print(json.dumps({'found': server.find_symbol('get_user'), 'read': json.loads(server._read_symbol('backend/service.py::get_user')), 'complexity': server.analyze_complexity()}))"])
+            .current_dir(dir.path())
+            .env("PYTHONPATH", dir.path().join("stubs"))
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let python: Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(python["found"][0]["parameters"], native[0]["parameters"]);
+        assert_eq!(python["read"]["parameters"], native[0]["parameters"]);
+        assert_eq!(python["complexity"]["threshold"], 3);
+    }
+
+    #[test]
+    fn test_generate_typescript_server() {
+        let (dir, source) = generate(ServerTarget::Typescript);
+        assert_files(dir.path(), &["package.json", "tsconfig.json", "src/index.ts", "start.sh"]);
+        let package: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("package.json")).unwrap()).unwrap();
+        assert_eq!(package["name"], "demo-shop");
+        assert!(package["dependencies"]["@modelcontextprotocol/sdk"].is_string());
+        assert!(!source.contains("{{"));
+        for tool in tool_names() {
+            assert!(source.contains(&format!("register(\"{}\"", tool)), "typescript target lacks {}", tool);
+        }
+    }
+
+    #[test]
+    fn test_generate_rust_server() {
+        let (dir, source) = generate(ServerTarget::Rust);
+        assert_files(dir.path(), &["Cargo.toml", "src/main.rs", "start.sh"]);
+        assert!(source.contains("//! Demo Shop MCP server"));
+        let manifest = std::fs::read_to_string(dir.path().join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"demo-shop\""));
+
+        // The standalone server compiles the native server's own sources.
+        for (generated, original) in [
+            ("src/server/tools.rs", "src/server/tools.rs"),
+            ("src/server/native.rs", "src/server/native.rs"),
+            ("src/database/queries.rs", "src/database/queries.rs"),
+            ("src/database/records.rs", "src/database/records.rs"),
+            ("src/error.rs", "src/error.rs"),
+        ] {
+            assert_eq!(
+                std::fs::read_to_string(dir.path().join(generated)).unwrap(),
+                std::fs::read_to_string(original).unwrap(),
+                "{} differs from {}",
+                generated,
+                original
+            );
+        }
+        // Only `database`, `error` and `server` are copied; anything else they use would not compile there.
+        for file in rust_files(&dir.path().join("src")) {
+            let text = std::fs::read_to_string(&file).unwrap();
+            for (start, _) in text.match_indices("crate::") {
+                let module: String = text[start + "crate::".len()..].chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                assert!(["database", "error", "server"].contains(&module.as_str()), "{:?} uses crate::{}", file, module);
+            }
+        }
+    }
+
+    /// `cargo check` the generated Rust server; needs its dependencies in the cargo cache or the network.
+    #[test]
+    #[ignore]
+    fn test_generated_rust_server_compiles() {
+        let (dir, _) = generate(ServerTarget::Rust);
+        let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated-rust-server");
+        let status = std::process::Command::new(env!("CARGO"))
+            .arg("check")
+            .arg("--quiet")
+            .current_dir(dir.path())
+            .env("CARGO_TARGET_DIR", target_dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    fn rust_files(dir: &Path) -> Vec<std::path::PathBuf> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(rust_files(&path));
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn test_target_parsing() {
+        assert_eq!("TypeScript".parse::<ServerTarget>().unwrap(), ServerTarget::Typescript);
+        assert!("go".parse::<ServerTarget>().is_err());
+        let config = ForgeConfig::from_yaml("server:\n  target: rust\n").unwrap();
+        assert_eq!(config.server.target, ServerTarget::Rust);
+        assert!(ForgeConfig::from_yaml("server:\n  target: cobol\n").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::seeded_output_dir;
+    use mcp_forge::server::http::{HttpTransport, McpNotify, SESSION_HEADER};
+    use mcp_forge::server::native::McpServer;
+    use serde_json::{json, Value};
+    use tokio_stream::StreamExt;
+
+    async fn start() -> (String, HttpTransport, tempfile::TempDir) {
+        let dir = seeded_output_dir().await;
+        let transport = HttpTransport::new(McpServer::open(dir.path()).await.unwrap());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(transport.clone().serve_listener(listener));
+        (url, transport, dir)
+    }
+
+    #[tokio::test]
+    async fn test_post_initialize_and_call() {
+        let (url, _transport, _dir) = start().await;
+        let client = reqwest::Client::new();
+
+        let init = client
+            .post(&url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+            .send()
+            .await
+            .unwrap();
+        let session = init.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+        let body: Value = init.json().await.unwrap();
+        assert_eq!(body["result"]["serverInfo"]["name"], "Demo_Expert");
+
+        let accepted = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(accepted.status(), 202);
+
+        let call: Value = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "find_symbol", "arguments": {"name": "query"}}}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(call["result"]["content"][0]["text"].as_str().unwrap().contains("backend/db.py::query"));
+
+        let closed = client.delete(&url).header(SESSION_HEADER, &session).send().await.unwrap();
+        assert_eq!(closed.status(), 200);
+        let stale = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&json!({"jsonrpc": "2.0", "id": 3, "method": "ping"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(stale.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_sse_notifications_and_origin() {
+        let (url, transport, _dir) = start().await;
+        let client = reqwest::Client::new();
+
+        let forbidden = client
+            .post(&url)
+            .header("Origin", "http://evil.example")
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(forbidden.status(), 403);
+
+        let stream = client.get(&url).header("Accept", "text/event-stream").send().await.unwrap();
+        assert_eq!(stream.status(), 200);
+        transport.notify("notifications/message", Some(json!({"level": "info", "data": "index updated"})));
+
+        let mut body = stream.bytes_stream();
+        let mut received = String::new();
+        while !received.contains("index updated") {
+            let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next()).await.unwrap().unwrap().unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        assert!(received.contains("event: message"));
+    }
+
+    #[tokio::test]
+    async fn test_index_updates_are_announced() {
+        use mcp_forge::incremental::IndexUpdate;
+
+        let dir = seeded_output_dir().await;
+        let server = McpServer::open(dir.path()).await.unwrap().with_resource_changes(true);
+        let init = server.handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#).await.unwrap();
+        let init: Value = serde_json::from_str(&init).unwrap();
+        assert_eq!(init["result"]["capabilities"]["resources"]["listChanged"], true);
+
+        let transport = HttpTransport::new(server);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(transport.clone().serve_listener(listener));
+        let stream = reqwest::Client::new().get(&url).header("Accept", "text/event-stream").send().await.unwrap();
+
+        let update = IndexUpdate { file_path: "backend/db.py".to_string(), ..Default::default() };
+        update.notify(&transport);
+        let mut body = stream.bytes_stream();
+        let mut received = String::new();
+        while !received.contains("notifications/resources/updated") {
+            let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next()).await.unwrap().unwrap().unwrap();
+            received.push_str(&String::from_utf8_lossy(&chunk));
+        }
+        assert!(received.contains("notifications/resources/list_changed"));
+        assert!(received.contains("file://backend/db.py"));
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use mcp_forge::analyzer::parser::CodeParser;
+    use mcp_forge::analyzer::symbols::{Symbol, SymbolExtractor};
+    use mcp_forge::config::IndexingConfig;
+    use mcp_forge::incremental::reparse::{input_edit, Reparser};
+
+    type Summary = (String, String, usize, usize, Option<String>, Option<String>);
+
+    fn summary(symbols: &[Symbol]) -> Vec<Summary> {
+        symbols
+            .iter()
+            .map(|s| (s.id.clone(), s.kind.clone(), s.start_line, s.end_line, s.documentation.clone(), s.signature.clone()))
+            .collect()
+    }
+
+    fn full_extraction(language: &str, source: &str, file_path: &str) -> Vec<Summary> {
+        let tree = CodeParser::new().parse_source(language, source).unwrap();
+        summary(&SymbolExtractor::new().extract_symbols(&tree.root_node(), source, file_path))
+    }
+
+    #[test]
+    fn test_input_edit() {
+        let edit = input_edit("def a():\n    pass\n", "def a():\n    return 1\n").unwrap();
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (13, 17, 21));
+        assert_eq!((edit.start_position.row, edit.start_position.column), (1, 4));
+        assert!(input_edit("same", "same").is_none());
+        // Never splits a multi-byte character.
+        let edit = input_edit("x = \"é\"", "x = \"è\"").unwrap();
+        assert_eq!((edit.start_byte, edit.old_end_byte), (5, 7));
+    }
+
+    #[test]
+    fn test_reparse_matches_full_extraction() {
+        let root = Path::new("/project");
+        let path = root.join("app/shop.py");
+        let versions = [
+            "class Shop:\n    \"\"\"Sells things.\"\"\"\n\n    def checkout(self):\n        pass\n\n\ndef helper():\n    return 1\n",
+            // Body edit: only the enclosing definitions are touched.
+            "class Shop:\n    \"\"\"Sells things.\"\"\"\n\n    def checkout(self):\n        return charge(self)\n\n\ndef helper():\n    return 1\n",
+            // Inserted lines shift everything below.
+            "import os\n\n\nclass Shop:\n    \"\"\"Sells things.\"\"\"\n\n    def checkout(self):\n        return charge(self)\n\n\ndef helper():\n    return 1\n",
+            // Rename and docstring change.
+            "import os\n\n\nclass Store:\n    \"\"\"Sells more things.\"\"\"\n\n    def checkout(self):\n        return charge(self)\n\n\ndef helper():\n    return 1\n",
+            // Deletion.
+            "import os\n\n\nclass Store:\n    \"\"\"Sells more things.\"\"\"\n\n    def checkout(self):\n        return charge(self)\n",
+        ];
+        let mut reparser = Reparser::new(root, &IndexingConfig::default());
+        let first = reparser.update(&path, versions[0].to_string()).unwrap();
+        assert!(!first.incremental);
+        for (i, version) in versions.iter().enumerate().skip(1) {
+            let update = reparser.update(&path, version.to_string()).unwrap();
+            assert!(update.incremental);
+            assert_eq!(summary(&update.symbols), full_extraction("python", version, "app/shop.py"), "version {}", i);
+            if i == 1 {
+                let touched: Vec<&str> = update.reextracted.iter().map(|s| s.name.as_str()).collect();
+                assert_eq!(touched, ["Shop", "checkout"]);
+            }
+            if i == 4 {
+                assert_eq!(update.removed, ["app/shop.py::helper"]);
+            }
+        }
+        assert!(reparser.update(&path, versions[4].to_string()).unwrap().reextracted.is_empty());
+    }
+
+    #[test]
+    fn test_reparse_tracks_doc_comments() {
+        let path = Path::new("src/Shop.java");
+        let versions = [
+            "class Shop {\n    /** Pays. */\n    void pay() {}\n\n    void refund() {}\n}\n",
+            "class Shop {\n    /** Pays the bill. */\n    void pay() {}\n\n    void refund() {}\n}\n",
+            "class Shop {\n    /** Pays the bill. */\n    void pay() {}\n\n    /** Gives it back. */\n    void refund() {}\n}\n",
+            "class Shop {\n    void pay() {}\n\n    /** Gives it back. */\n    void refund() { pay(); }\n}\n",
+        ];
+        let mut reparser = Reparser::new("", &IndexingConfig::default());
+        reparser.update(path, versions[0].to_string()).unwrap();
+        for (i, version) in versions.iter().enumerate().skip(1) {
+            let update = reparser.update(path, version.to_string()).unwrap();
+            assert_eq!(summary(&update.symbols), full_extraction("java", version, "src/Shop.java"), "version {}", i);
+        }
+
+        // Rust attributes sit between an item and its doc comments.
+        let path = Path::new("src/pool.rs");
+        let versions = [
+            "/// A pool.\n#[derive(Debug)]\npub struct Pool {}\n",
+            "/// A connection pool.\n#[derive(Debug)]\npub struct Pool {}\n",
+            "/// A connection pool.\n#[derive(Debug, Clone)]\npub struct Pool {}\n",
+        ];
+        reparser.update(path, versions[0].to_string()).unwrap();
+        for (i, version) in versions.iter().enumerate().skip(1) {
+            let update = reparser.update(path, version.to_string()).unwrap();
+            assert_eq!(summary(&update.symbols), full_extraction("rust", version, "src/pool.rs"), "version {}", i);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_updates_the_index() {
+        use mcp_forge::config::ForgeConfig;
+        use mcp_forge::database::{self, queries};
+        use mcp_forge::incremental::IncrementalBuilder;
+
+        let source = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        let shop = source.path().join("app/shop.py");
+        let helpers = source.path().join("app/helpers.py");
+        std::fs::create_dir_all(shop.parent().unwrap()).unwrap();
+        std::fs::write(&shop, "def checkout(cart):\n    return cart\n").unwrap();
+        std::fs::write(&helpers, "def fmt(value):\n    return str(value)\n").unwrap();
+        let mut config = ForgeConfig::default();
+        config.analysis.embeddings = false;
+        mcp_forge::server::builder::build(source.path().to_path_buf(), output.path().to_path_buf(), &config).await.unwrap();
+
+        let watcher = IncrementalBuilder::new(source.path().to_path_buf(), source.path().join(".mcp-forge"), &config.indexing);
+        watcher.prime();
+        assert!(watcher.changed(&shop).is_none(), "unchanged files are not reparsed");
+        std::fs::write(&shop, "def charge(cart):\n    return cart\n\n\ndef checkout(cart):\n    return charge(cart)\n").unwrap();
+        let pool = database::open_index_writable(output.path()).await.unwrap();
+        watcher.changed(&shop).unwrap().apply(&pool).await.unwrap();
+
+        let names: Vec<String> = queries::get_symbols_in_file(&pool, "app/shop.py").await.unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["charge", "checkout"]);
+        let checkout = queries::get_symbol(&pool, "app/shop.py::checkout").await.unwrap().unwrap();
+        assert_eq!(checkout.start_line, Some(5));
+        assert_eq!(queries::get_parameters(&pool, &checkout.id).await.unwrap().len(), 1);
+        let calls = queries::get_relationships(&pool, &checkout.id).await.unwrap();
+        assert_eq!(calls.iter().map(|r| r.to_symbol_id.as_deref().unwrap()).collect::<Vec<_>>(), ["app/shop.py::charge"]);
+
+        std::fs::remove_file(&helpers).unwrap();
+        watcher.removed(&helpers).unwrap().apply(&pool).await.unwrap();
+        assert!(queries::get_symbols_in_file(&pool, "app/helpers.py").await.unwrap().is_empty());
+        assert!(queries::get_files(&pool).await.unwrap().iter().all(|f| f.path != "app/helpers.py"));
+        assert!(queries::get_dangling_relationships(&pool).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_watch_skips_ignored_files() {
+        use mcp_forge::incremental::IncrementalBuilder;
+
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(source.path().join("app")).unwrap();
+        std::fs::create_dir_all(source.path().join("build/gen")).unwrap();
+        std::fs::write(source.path().join(".gitignore"), "build/\n").unwrap();
+        let indexing = IndexingConfig { exclude_patterns: vec!["*_pb2.py".to_string()], ..IndexingConfig::default() };
+        let watcher = IncrementalBuilder::new(source.path().to_path_buf(), source.path().join(".mcp-forge"), &indexing);
+
+        let main = source.path().join("app/main.py");
+        let generated = source.path().join("app/api_pb2.py");
+        let built = source.path().join("build/gen/out.py");
+        for path in [&main, &generated, &built] {
+            std::fs::write(path, "def run():\n    pass\n").unwrap();
+        }
+        assert_eq!(watcher.changed(&main).unwrap().file_path, "app/main.py");
+        assert!(watcher.changed(&generated).is_none(), "excluded by indexing.exclude_patterns");
+        assert!(watcher.changed(&built).is_none(), "inside a directory ignored by .gitignore");
+    }
+
+    #[tokio::test]
+    async fn test_watch_records_file_health() {
+        use mcp_forge::config::ForgeConfig;
+        use mcp_forge::database::{self, queries};
+        use mcp_forge::incremental::IncrementalBuilder;
+
+        let source = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        let shop = source.path().join("app/shop.py");
+        std::fs::create_dir_all(shop.parent().unwrap()).unwrap();
+        std::fs::write(&shop, "def checkout(cart):\n    return cart\n").unwrap();
+        let mut config = ForgeConfig::default();
+        config.analysis.embeddings = false;
+        mcp_forge::server::builder::build(source.path().to_path_buf(), output.path().to_path_buf(), &config).await.unwrap();
+        let pool = database::open_index_writable(output.path()).await.unwrap();
+        let watcher = IncrementalBuilder::new(source.path().to_path_buf(), source.path().join(".mcp-forge"), &config.indexing);
+        watcher.prime();
+
+        // A new file gets its `files` row along with its symbols.
+        let cart = source.path().join("app/cart.py");
+        std::fs::write(&cart, "def total(items):\n    return sum(items)\n").unwrap();
+        watcher.changed(&cart).unwrap().apply(&pool).await.unwrap();
+        let file = queries::get_file(&pool, "app/cart.py").await.unwrap().unwrap();
+        assert_eq!((file.language.as_deref(), file.lines, file.skip_reason), (Some("python"), Some(2), None));
+
+        // A few syntax errors are recorded, and the file stays indexed.
+        std::fs::write(&shop, "def checkout(cart):\n    return cart)\n\n\ndef pay(cart):\n    return cart\n\n\ndef ship(cart):\n    return cart\n").unwrap();
+        watcher.changed(&shop).unwrap().apply(&pool).await.unwrap();
+        let file = queries::get_file(&pool, "app/shop.py").await.unwrap().unwrap();
+        assert!(file.error_count > 0);
+        assert_eq!(file.skip_reason, None);
+        assert_eq!(queries::get_parse_diagnostics(&pool, "app/shop.py").await.unwrap().len() as i64, file.error_count);
+        assert!(queries::get_symbol(&pool, "app/shop.py::ship").await.unwrap().is_some());
+
+        // Fixed again: the errors are gone.
+        std::fs::write(&shop, "def checkout(cart):\n    return cart\n").unwrap();
+        watcher.changed(&shop).unwrap().apply(&pool).await.unwrap();
+        assert_eq!(queries::get_file(&pool, "app/shop.py").await.unwrap().unwrap().error_count, 0);
+        assert!(queries::get_parse_diagnostics(&pool, "app/shop.py").await.unwrap().is_empty());
+
+        // Turned into generated code: skipped before parsing, and its symbols are dropped.
+        std::fs::write(&shop, "# Code generated by shopgen. DO NOT EDIT.\ndef checkout(cart):\n    return cart\n").unwrap();
+        watcher.changed(&shop).unwrap().apply(&pool).await.unwrap();
+        let file = queries::get_file(&pool, "app/shop.py").await.unwrap().unwrap();
+        assert_eq!(file.skip_kind.as_deref(), Some("generated"));
+        assert!(queries::get_symbols_in_file(&pool, "app/shop.py").await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use mcp_forge::config::{ForgeConfig, CONFIG_VERSION};
+    use mcp_forge::init::{InitAction, Workspace, CACHE_DIR, CLIENTS_FILE};
+
+    fn workspace(root: &std::path::Path) -> Workspace {
+        Workspace { command: "mcp-forge".to_string(), ..Workspace::new(root) }
+    }
+
+    #[test]
+    fn test_init_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("app.py"), "def main():\n    pass\n").unwrap();
+        std::fs::write(dir.path().join("util.py"), "x = 1\n").unwrap();
+        std::fs::write(dir.path().join("index.ts"), "export const x = 1;\n").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.log").unwrap();
+        let workspace = Workspace { name: Some("Demo Shop".to_string()), ..workspace(dir.path()) };
+
+        let report = workspace.scaffold(&mut |_| panic!("nothing to upgrade")).unwrap();
+        let config_path = dir.path().join("mcp-forge.yaml");
+        assert_eq!(report.action_for(&config_path), Some(InitAction::Created));
+        assert_eq!(report.action_for(&dir.path().join(".gitignore")), Some(InitAction::Updated));
+        assert!(dir.path().join(CACHE_DIR).join(CLIENTS_FILE).is_file());
+
+        let config = ForgeConfig::from_file(&config_path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.project.name, "Demo Shop");
+        assert_eq!(config.indexing.languages, vec!["python", "typescript"]);
+        let gitignore = std::fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert!(gitignore.starts_with("*.log\n"));
+        assert!(gitignore.contains("/.mcp-forge/\n/mcp-server/\n"));
+
+        assert_eq!(report.snippets.server_name, "demo-shop");
+        let args = &report.snippets.stdio_json["mcpServers"]["demo-shop"]["args"];
+        assert_eq!(args[0], "serve");
+        assert!(args[1].as_str().unwrap().ends_with("mcp-server"));
+        assert_eq!(report.snippets.http_json["mcpServers"]["demo-shop"]["url"], "http://127.0.0.1:3000/mcp");
+
+        let again = workspace.scaffold(&mut |_| panic!("nothing to upgrade")).unwrap();
+        assert!(again.actions.iter().all(|(action, _)| *action == InitAction::Unchanged), "{:?}", again.actions);
+        assert_eq!(std::fs::read_to_string(dir.path().join(".gitignore")).unwrap(), gitignore);
+    }
+
+    #[test]
+    fn test_init_offers_config_upgrade() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("mcp-forge.yaml");
+        std::fs::write(&config_path, "project:\n  name: Legacy\nserver:\n  port: 4000\n").unwrap();
+        let workspace = workspace(dir.path());
+
+        let mut asked = 0;
+        let declined = workspace.scaffold(&mut |_| { asked += 1; false }).unwrap();
+        assert_eq!(asked, 1);
+        assert_eq!(declined.action_for(&config_path), Some(InitAction::Outdated));
+        assert!(!std::fs::read_to_string(&config_path).unwrap().contains("version"));
+
+        let upgraded = workspace.scaffold(&mut |_| true).unwrap();
+        assert_eq!(upgraded.action_for(&config_path), Some(InitAction::Upgraded));
+        assert!(dir.path().join("mcp-forge.yaml.bak").is_file());
+        let config = ForgeConfig::from_file(&config_path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.project.name, "Legacy");
+        assert_eq!(config.server.port, 4000);
+        assert_eq!(upgraded.snippets.http_json["mcpServers"]["legacy"]["url"], "http://127.0.0.1:4000/mcp");
+    }
+}
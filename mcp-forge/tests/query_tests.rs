@@ -0,0 +1,38 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::seeded_output_dir;
+    use mcp_forge::database;
+    use mcp_forge::query::QueryVerb;
+
+    #[tokio::test]
+    async fn test_query_verbs() {
+        let dir = seeded_output_dir().await;
+        let pool = database::open_index(dir.path()).await.unwrap();
+        let project = database::IndexInfo::load(dir.path());
+        assert_eq!(project.project_name, "Demo");
+
+        let symbol = QueryVerb::Symbol { name: "get_user".to_string(), kind: Some("function".to_string()) };
+        let table = symbol.render_table(&symbol.run(&pool, &project).await.unwrap());
+        assert!(table.starts_with("NAME"));
+        assert!(table.contains("backend/service.py:10-20"));
+
+        let refs = QueryVerb::Refs { name: "query".to_string() };
+        let result = refs.run(&pool, &project).await.unwrap();
+        assert_eq!(result["references"][0]["from"], "backend/service.py::get_user");
+        assert!(refs.render_table(&result).contains("calls"));
+
+        let deps = QueryVerb::Deps { name: "get_user".to_string(), depth: 2 };
+        assert!(deps.render_table(&deps.run(&pool, &project).await.unwrap()).contains("backend/db.py::query"));
+
+        let patterns = QueryVerb::Patterns { pattern_type: Some("singleton".to_string()) };
+        assert!(patterns.render_table(&patterns.run(&pool, &project).await.unwrap()).contains("backend/db.py"));
+
+        let search = QueryVerb::Search { query: "nothing matches".to_string(), limit: 5 };
+        assert_eq!(search.render_table(&search.run(&pool, &project).await.unwrap()), "(no results)\n");
+
+        let missing = QueryVerb::Refs { name: "does_not_exist".to_string() };
+        assert!(missing.run(&pool, &project).await.is_err());
+    }
+}
@@ -0,0 +1,170 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use super::common::seeded_output_dir;
+    use mcp_forge::database::queries::{self, FileRecord};
+    use mcp_forge::server::native::McpServer;
+    use mcp_forge::server::resources::PAGE_SIZE;
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    fn tool_payload(response: &Value) -> Value {
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    async fn request(server: &McpServer, message: Value) -> Value {
+        let reply = server.handle_message(&message.to_string()).await.unwrap();
+        serde_json::from_str(&reply).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_and_list_tools() {
+        let dir = seeded_output_dir().await;
+        let server = McpServer::open(dir.path()).await.unwrap();
+
+        let init = request(&server, json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2024-11-05"}})).await;
+        assert_eq!(init["result"]["serverInfo"]["name"], "Demo_Expert");
+        assert!(server.handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).await.is_none());
+
+        let list = request(&server, json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"})).await;
+        let names: Vec<&str> = list["result"]["tools"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert!(names.contains(&"find_symbol"));
+        assert!(names.contains(&"semantic_search"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls() {
+        let dir = seeded_output_dir().await;
+        let server = McpServer::open(dir.path()).await.unwrap();
+
+        let found = request(&server, json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "find_symbol", "arguments": {"name": "get_user"}}})).await;
+        assert_eq!(tool_payload(&found)[0]["id"], "backend/service.py::get_user");
+        assert_eq!(tool_payload(&found)[0]["doc_tags"]["params"][0], json!({"name": "user_id", "type": "int", "description": "Id of the user."}));
+        assert_eq!(tool_payload(&found)[0]["parameters"], json!([{"position": 0, "name": "user_id", "type": "int", "default": null, "kind": "positional"}]));
+        assert_eq!(tool_payload(&found)[0]["return_type"], "User");
+        let qualified = request(&server, json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "find_symbol", "arguments": {"name": "service.get_user"}}})).await;
+        assert_eq!(tool_payload(&qualified)[0]["qualified_name"], "backend.service.get_user");
+
+        let deps = request(&server, json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "analyze_dependencies", "arguments": {"symbol_name": "get_user"}}})).await;
+        assert_eq!(tool_payload(&deps)["dependencies"][0]["to"], "backend/db.py::query");
+
+        let search = request(&server, json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {"name": "semantic_search", "arguments": {"query": "fetch user"}}})).await;
+        assert_eq!(tool_payload(&search)[0]["symbol"]["name"], "get_user");
+
+        let coverage = request(&server, json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {"name": "get_test_coverage", "arguments": {"module": "backend"}}})).await;
+        assert_eq!(tool_payload(&coverage)["tested"], 1);
+
+        let missing = request(&server, json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": {"name": "find_symbol", "arguments": {}}})).await;
+        assert_eq!(missing["result"]["isError"], true);
+
+        let unknown = request(&server, json!({"jsonrpc": "2.0", "id": 6, "method": "sampling/createMessage"})).await;
+        assert_eq!(unknown["error"]["code"], -32601);
+    }
+
+    #[tokio::test]
+    async fn test_complexity_threshold_from_config() {
+        let dir = seeded_output_dir().await;
+        std::fs::write(dir.path().join("config.json"), r#"{"project":{"name":"Demo"},"analysis":{"complexity_threshold":3}}"#).unwrap();
+        let server = McpServer::open(dir.path()).await.unwrap();
+
+        let configured = request(&server, json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": "analyze_complexity", "arguments": {}}})).await;
+        assert_eq!(tool_payload(&configured)["threshold"], 3);
+        assert_eq!(tool_payload(&configured)["over_threshold"], json!([{"symbol": "backend/service.py::get_user", "file_path": "backend/service.py", "complexity": 3, "lines": 11, "calls": 1}]));
+        let explicit = request(&server, json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {"name": "analyze_complexity", "arguments": {"threshold": 1}}})).await;
+        assert_eq!(tool_payload(&explicit)["over_threshold"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resources_and_prompts() {
+        let dir = seeded_output_dir().await;
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(source.path().join("backend")).unwrap();
+        let lines: Vec<String> = (1..=20).map(|n| format!("line {}", n)).collect();
+        std::fs::write(source.path().join("backend/service.py"), lines.join("\n")).unwrap();
+        let config = json!({"project": {"name": "Demo", "root": source.path()}});
+        std::fs::write(dir.path().join("config.json"), config.to_string()).unwrap();
+        let server = McpServer::open(dir.path()).await.unwrap();
+
+        let list = request(&server, json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"})).await;
+        let uris: Vec<&str> = list["result"]["resources"].as_array().unwrap().iter().map(|r| r["uri"].as_str().unwrap()).collect();
+        assert!(uris.contains(&"file://backend/service.py"));
+        assert!(uris.contains(&"symbol://backend/service.py::get_user"));
+
+        let file = request(&server, json!({"jsonrpc": "2.0", "id": 2, "method": "resources/read", "params": {"uri": "file://backend/service.py"}})).await;
+        assert_eq!(file["result"]["contents"][0]["mimeType"], "text/x-python");
+        let symbol = request(&server, json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read", "params": {"uri": "symbol://backend/service.py::get_user"}})).await;
+        let payload: Value = serde_json::from_str(symbol["result"]["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert!(payload["code"].as_str().unwrap().starts_with("line 10\nline 11"));
+        assert_eq!(payload["callees"][0], "backend/db.py::query");
+        // Not indexed, and outside the project in any case.
+        let outside = request(&server, json!({"jsonrpc": "2.0", "id": 4, "method": "resources/read", "params": {"uri": "file://../config.json"}})).await;
+        assert_eq!(outside["error"]["code"], -32002);
+        // Discovered but skipped, so never indexed even though it exists on disk.
+        std::fs::write(source.path().join("backend/bundle.py"), "x = 1").unwrap();
+        let pool = sqlx::SqlitePool::connect(&format!("sqlite:{}", dir.path().join("database.sqlite").display())).await.unwrap();
+        queries::insert_file(&pool, &FileRecord { path: "backend/bundle.py".to_string(), skip_reason: Some("minified".to_string()), skip_kind: Some("minified".to_string()), ..FileRecord::default() }).await.unwrap();
+        pool.close().await;
+        let skipped = request(&server, json!({"jsonrpc": "2.0", "id": 4, "method": "resources/read", "params": {"uri": "file://backend/bundle.py"}})).await;
+        assert_eq!(skipped["error"]["code"], -32002);
+
+        let review = request(&server, json!({"jsonrpc": "2.0", "id": 5, "method": "prompts/get", "params": {"name": "review_change", "arguments": {"symbol": "get_user", "change": "Cache results"}}})).await;
+        let text = review["result"]["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("Cache results"));
+        assert!(text.contains("`backend/db.py::query`"));
+        let module = request(&server, json!({"jsonrpc": "2.0", "id": 6, "method": "prompts/get", "params": {"name": "explain_module", "arguments": {"module": "nowhere"}}})).await;
+        assert_eq!(module["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_pages() {
+        let dir = seeded_output_dir().await;
+        let pool = sqlx::SqlitePool::connect(&format!("sqlite:{}", dir.path().join("database.sqlite").display())).await.unwrap();
+        for n in 0..250 {
+            queries::insert_symbol(&pool, &format!("backend/db.py::helper_{:03}", n), &format!("helper_{:03}", n), "function", "backend/db.py", 10 + n, 10 + n, "backend", None, None).await.unwrap();
+        }
+        pool.close().await;
+        let server = McpServer::open(dir.path()).await.unwrap();
+
+        let first = request(&server, json!({"jsonrpc": "2.0", "id": 1, "method": "resources/list"})).await;
+        let resources = first["result"]["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), PAGE_SIZE);
+        assert_eq!(resources[0], json!({"uri": "file://backend/db.py", "name": "backend/db.py", "description": "251 symbols", "mimeType": "text/x-python"}));
+        assert_eq!(resources[2]["uri"], "file://tests/test_service.py");
+        assert_eq!(resources[3]["uri"], "symbol://backend/db.py::query");
+        assert_eq!(first["result"]["nextCursor"], "200");
+
+        let second = request(&server, json!({"jsonrpc": "2.0", "id": 2, "method": "resources/list", "params": {"cursor": "200"}})).await;
+        let resources = second["result"]["resources"].as_array().unwrap();
+        // 3 files and 253 symbols in all.
+        assert_eq!(resources.len(), 56);
+        assert_eq!(resources[0]["uri"], "symbol://backend/db.py::helper_196");
+        assert_eq!(resources.last().unwrap()["uri"], "symbol://tests/test_service.py::test_get_user");
+        assert!(second["result"].get("nextCursor").is_none());
+
+        let invalid = request(&server, json!({"jsonrpc": "2.0", "id": 3, "method": "resources/list", "params": {"cursor": "-1"}})).await;
+        assert!(invalid["error"]["message"].as_str().unwrap().contains("Invalid cursor"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_over_stream() {
+        let dir = seeded_output_dir().await;
+        let server = McpServer::open(dir.path()).await.unwrap();
+        let (client, server_io) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let (client_read, mut client_write) = tokio::io::split(client);
+
+        let serving = tokio::spawn(async move { server.serve(BufReader::new(server_read), server_write).await });
+        client_write.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"ping\"}\nnot json\n").await.unwrap();
+        client_write.shutdown().await.unwrap();
+
+        let mut lines = BufReader::new(client_read).lines();
+        let pong: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(pong["id"], 7);
+        let parse_error: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(parse_error["error"]["code"], -32700);
+        drop(client_write);
+        serving.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use mcp_forge::config::ForgeConfig;
+    use mcp_forge::server::templates::{render_str, ServerTarget, TemplateSet, TemplateVars};
+    use std::path::Path;
+
+    fn vars() -> TemplateVars {
+        let mut config = ForgeConfig::default();
+        config.project.name = "Shop".to_string();
+        TemplateVars::new(&config, vec!["python".to_string(), "rust".to_string()])
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let vars = vars();
+        assert_eq!(render_str("{{PROJECT_NAME}} ({{LANGUAGES}}) on {{SERVER_PORT}}", &vars).unwrap(), "Shop (python, rust) on 3000");
+        assert_eq!(render_str("f\"{{x}}\" {{ not_a_var }}", &vars).unwrap(), "f\"{{x}}\" {{ not_a_var }}");
+        assert!(render_str("{{PROJECT_NMAE}}", &vars).unwrap_err().contains("PROJECT_NMAE"));
+        assert!(vars.tools.iter().any(|t| t == "find_symbol"));
+    }
+
+    #[test]
+    fn test_embedded_templates_render() {
+        let rendered = TemplateSet::embedded(ServerTarget::Python).render_all(&vars()).unwrap();
+        let server = String::from_utf8(rendered[Path::new("server.py")].clone()).unwrap();
+        assert!(server.contains("FastMCP(\"Shop_Expert\")"));
+        assert!(server.contains("Tools: get_project_overview, find_symbol"));
+        assert!(!server.contains("{{"));
+        assert!(rendered.contains_key(Path::new("start.sh")));
+    }
+
+    #[test]
+    fn test_template_dir_overlay() {
+        let templates = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(templates.path().join("docs")).unwrap();
+        std::fs::write(templates.path().join("server.py"), "# {{PROJECT_NAME}} by the platform team\n").unwrap();
+        std::fs::write(templates.path().join("docs/README.md"), "Database: {{DB_PATH}}\n").unwrap();
+
+        let output = tempfile::tempdir().unwrap();
+        let set = TemplateSet::with_dir(templates.path(), ServerTarget::Python).unwrap();
+        set.write_to(output.path(), &vars()).unwrap();
+        assert_eq!(std::fs::read_to_string(output.path().join("server.py")).unwrap(), "# Shop by the platform team\n");
+        assert_eq!(std::fs::read_to_string(output.path().join("docs/README.md")).unwrap(), "Database: database.sqlite\n");
+        assert!(output.path().join("requirements.txt").is_file());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(output.path().join("start.sh")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        std::fs::write(templates.path().join("broken.txt"), "{{UNKNOWN}}").unwrap();
+        let error = TemplateSet::with_dir(templates.path(), ServerTarget::Python).unwrap().render_all(&vars()).unwrap_err().to_string();
+        assert!(error.contains("broken.txt"));
+        assert!(TemplateSet::with_dir(&templates.path().join("missing"), ServerTarget::Python).is_err());
+    }
+}